@@ -16,6 +16,7 @@
 
 use license_gen::Builder;
 use std::path::PathBuf;
+use std::process::Command;
 use std::{env, fs, io};
 
 fn main() -> io::Result<()> {
@@ -28,5 +29,19 @@ fn main() -> io::Result<()> {
         PathBuf::from(env::var("OUT_DIR").unwrap()).join("licenses.txt"),
     )?)?;
 
+    // the build info shown by `version` command and about window to identify the exe
+    let git_hash = Command::new("git")
+        .args(["rev-parse", "--short=9", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_owned())
+        .unwrap_or_else(|| "unknown".to_owned());
+    println!("cargo:rustc-env=BUILD_GIT_HASH={}", git_hash);
+    let date = chrono::Utc::now().format("%Y-%m-%d");
+    println!("cargo:rustc-env=BUILD_DATE={}", date);
+    let target = env::var("TARGET").unwrap();
+    println!("cargo:rustc-env=BUILD_TARGET={}", target);
+
     Ok(())
 }
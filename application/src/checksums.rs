@@ -0,0 +1,143 @@
+// VRC Log Renamer - the tool to rename logs of VRChat to have date info
+// Copyright (C) 2022 anatawa12
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `SHA256SUMS` manifest in the output folder, in the format of `sha256sum` so the archived
+//! logs can also be verified by other tools after the folder is copied elsewhere.
+
+use crate::{copy_file_attributes, copy_file_times};
+use sha2::{Digest, Sha256};
+use std::io::{ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+const MANIFEST_NAME: &str = "SHA256SUMS";
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum Verified {
+    Ok,
+    Corrupted,
+    Missing,
+}
+
+/// appends the hash of the file archived in the output folder to the manifest.
+/// the file is hashed if the hash is not known
+pub(crate) fn append(folder: &Path, path: &Path, sha256: Option<&str>) -> io::Result<()> {
+    let relative = path
+        .strip_prefix(folder)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "not in the output folder"))?;
+    let hash = match sha256 {
+        Some(sha256) => sha256.to_owned(),
+        None => file_sha256(path)?,
+    };
+    let mut manifest = fs::File::options()
+        .create(true)
+        .append(true)
+        .open(folder.join(MANIFEST_NAME))?;
+    writeln!(manifest, "{}  {}", hash, relative.display())
+}
+
+/// hashes the files in the manifest again. the last entry is used if a file is listed twice.
+/// empty if there's no manifest
+pub(crate) fn verify(folder: &Path) -> io::Result<Vec<(PathBuf, Verified)>> {
+    let manifest = match fs::read_to_string(folder.join(MANIFEST_NAME)) {
+        Ok(manifest) => manifest,
+        Err(ref e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut entries = Vec::<(PathBuf, &str)>::new();
+    for line in manifest.lines() {
+        // ` *` is the binary mode of sha256sum
+        let (hash, relative) = match line.split_once("  ").or_else(|| line.split_once(" *")) {
+            Some(entry) => entry,
+            None => continue,
+        };
+        let path = folder.join(relative);
+        entries.retain(|(x, _)| x != &path);
+        entries.push((path, hash));
+    }
+    let mut verified = Vec::new();
+    for (path, hash) in entries {
+        let state = match file_sha256(&path) {
+            Ok(actual) if actual.eq_ignore_ascii_case(hash) => Verified::Ok,
+            Ok(_) => Verified::Corrupted,
+            Err(ref e) if e.kind() == ErrorKind::NotFound => Verified::Missing,
+            Err(e) => return Err(e),
+        };
+        verified.push((path, state));
+    }
+    Ok(verified)
+}
+
+/// copies the file reading it only once and returns the hash of the content,
+/// so the multi-GB log is not read again to append to the manifest
+pub(crate) fn copy_hashed(
+    from: &Path,
+    to: &Path,
+    buffer_size: usize,
+    progress: &mut dyn FnMut(u64, u64) -> bool,
+) -> io::Result<String> {
+    let mut from_file = fs::File::open(from)?;
+    let metadata = from_file.metadata()?;
+    let mut to_file = fs::File::options().create_new(true).write(true).open(to)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; buffer_size];
+    let mut copied = 0;
+    let result = loop {
+        let read = match from_file.read(&mut buffer) {
+            Ok(0) => break Ok(()),
+            Ok(read) => read,
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => break Err(e),
+        };
+        if let Err(e) = to_file.write_all(&buffer[..read]) {
+            break Err(e);
+        }
+        hasher.update(&buffer[..read]);
+        copied += read as u64;
+        if !progress(copied, metadata.len()) {
+            break Err(io::Error::new(ErrorKind::Interrupted, "copying cancelled"));
+        }
+    };
+    if let Err(e) = result {
+        // don't leave the partial copy like CopyFileExW
+        drop(to_file);
+        let _ = fs::remove_file(to);
+        return Err(e);
+    }
+    copy_file_times(&metadata, &to_file)?;
+    drop(to_file);
+    copy_file_attributes(&metadata, to)?;
+    Ok(hex(hasher))
+}
+
+fn file_sha256(path: &Path) -> io::Result<String> {
+    read_sha256(fs::File::open(path)?)
+}
+
+/// hashes the content read in a stream not to load the large log to the memory
+pub(crate) fn read_sha256(mut reader: impl Read) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut reader, &mut hasher)?;
+    Ok(hex(hasher))
+}
+
+fn hex(hasher: Sha256) -> String {
+    hasher
+        .finalize()
+        .iter()
+        .map(|x| format!("{:02x}", x))
+        .collect()
+}
@@ -14,14 +14,17 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+use crate::pattern::Pattern;
 use crate::{config_file_path, local_low_appdata_path};
-use chrono::format::{Fixed, Item, Numeric, Pad, StrftimeItems};
+use chrono::format::Item;
+use chrono::{FixedOffset, NaiveDate, NaiveTime};
 use io::Error;
 use regex::Regex;
-use serde::ser::Error as _;
-use serde::Serialize;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize};
 use std::io::ErrorKind;
 use std::path::PathBuf;
+use std::time::Duration;
 use std::{fs, io};
 use toml::Value;
 
@@ -48,395 +51,2413 @@ macro_rules! default_fns {
     };
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ConfigFile {
+    #[serde(
+        skip_serializing_if = "ConfigFile::is_unknown_keys_default",
+        default = "ConfigFile::unknown_keys_default"
+    )]
+    unknown_keys: UnknownKeys,
+    /// checks updates on GitHub at GUI startup
+    #[serde(
+        skip_serializing_if = "ConfigFile::is_check_updates_default",
+        default = "ConfigFile::check_updates_default"
+    )]
+    check_updates: bool,
+    /// runs scheduled renames with background process and I/O priority
+    #[serde(
+        skip_serializing_if = "ConfigFile::is_low_priority_default",
+        default = "ConfigFile::low_priority_default"
+    )]
+    low_priority: bool,
+    /// examines only the logs modified after the last run without errors
+    #[serde(
+        skip_serializing_if = "ConfigFile::is_incremental_default",
+        default = "ConfigFile::incremental_default"
+    )]
+    incremental: bool,
+    /// the colors of GUI. `auto` follows the app theme of Windows
+    #[serde(
+        skip_serializing_if = "ConfigFile::is_theme_default",
+        default = "ConfigFile::theme_default"
+    )]
+    theme: Theme,
+    /// the folders recently used in path fields of GUI, the most recent first
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    recent_folders: Vec<String>,
+    #[serde(default)]
     source: Source,
+    /// the sources other than `source`
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    sources: Vec<Source>,
+    #[serde(default)]
     output: Output,
+    #[serde(skip_serializing_if = "Notify::is_empty", default)]
+    notify: Notify,
+    #[serde(skip_serializing_if = "Hooks::is_empty", default)]
+    hooks: Hooks,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    upload: Option<Upload>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pictures: Option<Pictures>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    crashes: Option<Crashes>,
+    #[serde(skip_serializing_if = "Schedule::is_empty", default)]
+    schedule: Schedule,
+}
+
+impl ConfigFile {
+    default_fns!(unknown_keys: UnknownKeys = UnknownKeys::Error);
+    default_fns!(check_updates: bool = false);
+    default_fns!(low_priority: bool = false);
+    default_fns!(incremental: bool = true);
+    default_fns!(theme: Theme = Theme::Auto);
+}
+
+impl ConfigFile {
+    pub fn check_updates(&self) -> bool {
+        self.check_updates
+    }
+    pub fn low_priority(&self) -> bool {
+        self.low_priority
+    }
+    pub fn incremental(&self) -> bool {
+        self.incremental
+    }
+    pub fn theme(&self) -> Theme {
+        self.theme
+    }
+    pub fn recent_folders(&self) -> &[String] {
+        &self.recent_folders
+    }
+    /// moves the folders to the front of recent folders and drops the oldest ones
+    pub fn add_recent_folders<'a>(&mut self, folders: impl IntoIterator<Item = &'a str>) {
+        for folder in folders {
+            if folder.is_empty() {
+                continue;
+            }
+            self.recent_folders.retain(|x| !x.eq_ignore_ascii_case(folder));
+            self.recent_folders.insert(0, folder.to_owned());
+        }
+        self.recent_folders.truncate(MAX_RECENT_FOLDERS);
+    }
+    pub fn source(&self) -> &Source {
+        &self.source
+    }
+    pub fn output(&self) -> &Output {
+        &self.output
+    }
+    /// limits the launch dates of logs of all sources like `--since` and `--until` options.
+    /// None keeps the configured value
+    pub fn set_date_range(&mut self, since: Option<NaiveDate>, until: Option<NaiveDate>) {
+        for source in std::iter::once(&mut self.source).chain(self.sources.iter_mut()) {
+            if since.is_some() {
+                source.since = since;
+            }
+            if until.is_some() {
+                source.until = until;
+            }
+        }
+    }
+    /// returns `source` and then additional `sources`
+    pub fn sources(&self) -> impl Iterator<Item = &Source> {
+        std::iter::once(&self.source).chain(self.sources.iter())
+    }
+    /// returns the output config for the source, overridden by the `output` of the source
+    pub fn output_for(&self, source: &Source) -> Output {
+        let mut output = self.output.clone();
+        if let Some(folder) = &source.output.folder {
+            output.folder = folder.clone();
+        }
+        if let Some(pattern) = &source.output.pattern {
+            output.pattern = pattern.clone();
+        }
+        output
+    }
+    pub fn notify(&self) -> &Notify {
+        &self.notify
+    }
+    pub fn hooks(&self) -> &Hooks {
+        &self.hooks
+    }
+    pub fn upload(&self) -> Option<&Upload> {
+        self.upload.as_ref()
+    }
+    pub fn pictures(&self) -> Option<&Pictures> {
+        self.pictures.as_ref()
+    }
+    pub fn crashes(&self) -> Option<&Crashes> {
+        self.crashes.as_ref()
+    }
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+    /// sets the schedule. the task path and triggers are kept
+    pub fn set_schedule(&mut self, schedule: Schedule) {
+        let task_path = std::mem::take(&mut self.schedule.task_path);
+        let triggers = std::mem::take(&mut self.schedule.triggers);
+        self.schedule = Schedule {
+            task_path,
+            triggers,
+            ..schedule
+        };
+    }
+
+    /// sets the source. the values not editable in GUI are kept
+    pub fn set_source(&mut self, source: Source) {
+        let output = std::mem::take(&mut self.source.output);
+        let launch_time = std::mem::take(&mut self.source.launch_time);
+        let in_use = self.source.in_use;
+        let locked_retry = self.source.locked_retry;
+        let locked_retry_delay_ms = self.source.locked_retry_delay_ms;
+        let unparsable = self.source.unparsable;
+        let hard_link = self.source.hard_link;
+        let leave_link = self.source.leave_link;
+        let capture_live = self.source.capture_live;
+        let recursive = self.source.recursive;
+        let since = self.source.since;
+        let until = self.source.until;
+        self.source = Source {
+            output,
+            launch_time,
+            in_use,
+            locked_retry,
+            locked_retry_delay_ms,
+            unparsable,
+            hard_link,
+            leave_link,
+            capture_live,
+            recursive,
+            since,
+            until,
+            ..source
+        };
+    }
+    /// enables renaming pictures with the folders or disables if None.
+    /// other values of pictures are kept
+    pub fn set_pictures_folders(&mut self, folders: Option<(ConfigPath, ConfigPath)>) {
+        match folders {
+            Some((folder, output_folder)) => {
+                let pictures = self.pictures.get_or_insert_with(Default::default);
+                pictures.folder = folder;
+                pictures.output_folder = output_folder;
+            }
+            None => self.pictures = None,
+        }
+    }
+    /// sets the output. the values not editable in GUI are kept
+    pub fn set_output(&mut self, output: Output) {
+        let replacement = self.output.replacement;
+        let time_zone = self.output.time_zone.take();
+        let split_worlds = self.output.split_worlds;
+        let compress = self.output.compress;
+        let checksums = self.output.checksums;
+        let copy_buffer_kib = self.output.copy_buffer_kib;
+        let read_only = self.output.read_only;
+        let dedupe = self.output.dedupe;
+        let filter_lines = std::mem::take(&mut self.output.filter_lines);
+        let mirror_folders = std::mem::take(&mut self.output.mirror_folders);
+        self.output = Output {
+            replacement,
+            time_zone,
+            split_worlds,
+            compress,
+            checksums,
+            copy_buffer_kib,
+            read_only,
+            dedupe,
+            filter_lines,
+            mirror_folders,
+            ..output
+        };
+    }
+}
+
+/// the colors of GUI
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    Auto,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    fn parse(str: &str) -> io::Result<Self> {
+        match str {
+            "auto" => Ok(Self::Auto),
+            "light" => Ok(Self::Light),
+            "dark" => Ok(Self::Dark),
+            _ => Err(Error::new(
+                ErrorKind::InvalidInput,
+                "theme must be 'auto', 'light' or 'dark'",
+            )),
+        }
+    }
+}
+
+/// the number of folders kept in `recent_folders`
+const MAX_RECENT_FOLDERS: usize = 8;
+
+/// how to handle keys not known by this version
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum UnknownKeys {
+    Error,
+    Warn,
+}
+
+impl UnknownKeys {
+    fn parse(str: &str) -> io::Result<Self> {
+        match str {
+            "error" => Ok(Self::Error),
+            "warn" => Ok(Self::Warn),
+            _ => Err(Error::new(
+                ErrorKind::InvalidData,
+                "unknown_keys must be 'error' or 'warn'",
+            )),
+        }
+    }
+}
+
+/// reports keys not in `CONFIG_KEYS` with the nearest known key
+fn check_unknown_keys(toml: &Value, mode: UnknownKeys) -> io::Result<()> {
+    // each table in `sources` accepts the same keys as `source`
+    fn walk(table: &toml::value::Table, prefix: &str, shown: &str, messages: &mut Vec<String>) {
+        for (name, value) in table {
+            let (key, shown_key) = if prefix.is_empty() {
+                (name.clone(), name.clone())
+            } else {
+                (format!("{}.{}", prefix, name), format!("{}.{}", shown, name))
+            };
+            if CONFIG_KEYS.contains(&key.as_str()) {
+                continue;
+            }
+            let table_prefix = format!("{}.", key);
+            match value {
+                Value::Table(table) if CONFIG_KEYS.iter().any(|x| x.starts_with(&table_prefix)) => {
+                    walk(table, &key, &shown_key, messages);
+                    continue;
+                }
+                Value::Array(array) if key == "sources" => {
+                    for (i, element) in array.iter().enumerate() {
+                        let shown_key = format!("sources[{}]", i);
+                        match element {
+                            Value::Table(table) => walk(table, "source", &shown_key, messages),
+                            _ => messages.push(format!("{} must be a table", shown_key)),
+                        }
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+            let mut message = format!("unknown config key: {}", shown_key);
+            if let Some(suggestion) = nearest_known_name(prefix, name) {
+                message.push_str(&format!(". did you mean '{}'?", suggestion));
+            }
+            messages.push(message);
+        }
+    }
+
+    let mut messages = Vec::new();
+    if let Value::Table(table) = toml {
+        walk(table, "", "", &mut messages);
+    }
+    if messages.is_empty() {
+        return Ok(());
+    }
+    match mode {
+        UnknownKeys::Error => Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "{}\n(set unknown_keys = \"warn\" to ignore unknown keys)",
+                messages.join("\n")
+            ),
+        )),
+        UnknownKeys::Warn => {
+            for message in messages {
+                eprintln!("warning: {}", message);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// finds the known key or table name in the table most similar to the name
+fn nearest_known_name(prefix: &str, name: &str) -> Option<String> {
+    let children = CONFIG_KEYS.iter().filter_map(|key| {
+        let rest = if prefix.is_empty() {
+            *key
+        } else {
+            key.strip_prefix(prefix)?.strip_prefix('.')?
+        };
+        Some(rest.split('.').next().unwrap())
+    });
+    children
+        .map(|child| (edit_distance(name, child), child))
+        .filter(|(distance, child)| *distance <= name.len().max(child.len()) * 2 / 3)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, child)| child.to_owned())
+}
+
+/// the levenshtein distance of two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                prev
+            } else {
+                prev.min(row[j]).min(row[j + 1]) + 1
+            };
+            prev = current;
+        }
+    }
+    row[b.len()]
+}
+
+/// the keys accepted by `ConfigFile::get_value` and `ConfigFile::set_value`
+pub const CONFIG_KEYS: &[&str] = &[
+    "unknown_keys",
+    "check_updates",
+    "low_priority",
+    "incremental",
+    "theme",
+    "recent_folders",
+    "source.folder",
+    "source.pattern",
+    "source.exclude_pattern",
+    "source.keep_old",
+    "source.hard_link",
+    "source.leave_link",
+    "source.capture_live",
+    "source.recursive",
+    "source.since",
+    "source.until",
+    "source.header_format",
+    "source.launch_time",
+    "source.in_use",
+    "source.locked_retry",
+    "source.locked_retry_delay_ms",
+    "source.unparsable",
+    "source.output.folder",
+    "source.output.pattern",
+    "output.folder",
+    "output.pattern",
+    "output.utc_time",
+    "output.time_zone",
+    "output.file_ctime",
+    "output.replacement",
+    "output.split_worlds",
+    "output.compress",
+    "output.checksums",
+    "output.copy_buffer_kib",
+    "output.read_only",
+    "output.dedupe",
+    "output.filter_lines",
+    "output.mirror_folders",
+    "notify.discord.webhook_url",
+    "hooks.before_run",
+    "hooks.after_file",
+    "hooks.after_run",
+    "upload.kind",
+    "upload.url",
+    "upload.bucket",
+    "upload.region",
+    "upload.username",
+    "upload.password",
+    "upload.credential",
+    "upload.retry",
+    "pictures.folder",
+    "pictures.pattern",
+    "pictures.keep_old",
+    "pictures.output_folder",
+    "pictures.output_pattern",
+    "crashes.folder",
+    "schedule.time",
+    "schedule.interval_days",
+    "schedule.task_path",
+    "schedule.triggers",
+    "schedule.wake_to_run",
+    "schedule.start_when_available",
+    "schedule.installer",
+];
+
+fn parse_bool(key: &str, value: &str) -> io::Result<bool> {
+    match value {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("{} must be true or false", key),
+        )),
+    }
+}
+
+/// parses toml array of strings like `["a", "b"]`
+fn parse_string_array(key: &str, value: &str) -> io::Result<Vec<String>> {
+    #[derive(Deserialize)]
+    struct Array {
+        value: Vec<String>,
+    }
+    toml::from_str::<Array>(&format!("value = {}", value))
+        .map(|x| x.value)
+        .map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("{} must be an array of strings like [\"a\", \"b\"]", key),
+            )
+        })
+}
+
+fn parse_pattern_arg(value: &str) -> io::Result<Pattern> {
+    let pattern = Pattern::parse(value).map_err(|e| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("'{}' is invalid log file pattern: {}", value, e),
+        )
+    })?;
+    if let Some(c) = invalid_pattern_char(pattern.items()) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("'{}' cannot be used in file name: '{}'", c, value),
+        ));
+    }
+    Ok(pattern)
+}
+
+fn invalid_key(key: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidInput,
+        format!("unknown config key: {}", key),
+    )
 }
 
 impl ConfigFile {
-    fn read_from_file(&mut self, toml: &Value) -> io::Result<()> {
-        if let Some(source) = toml.get("source") {
-            self.source.read_from_file(source)?
+    /// returns the value of the key. None if the optional value is not set
+    pub fn get_value(&self, key: &str) -> io::Result<Option<Value>> {
+        fn string(value: impl ToString) -> Value {
+            Value::String(value.to_string())
+        }
+        let upload = self.upload.as_ref();
+        let pictures = self.pictures.as_ref();
+        Ok(match key {
+            "unknown_keys" => Some(match self.unknown_keys {
+                UnknownKeys::Error => string("error"),
+                UnknownKeys::Warn => string("warn"),
+            }),
+            "check_updates" => Some(Value::Boolean(self.check_updates)),
+            "low_priority" => Some(Value::Boolean(self.low_priority)),
+            "incremental" => Some(Value::Boolean(self.incremental)),
+            "theme" => Some(match self.theme {
+                Theme::Auto => string("auto"),
+                Theme::Light => string("light"),
+                Theme::Dark => string("dark"),
+            }),
+            "recent_folders" => Some(Value::Array(
+                self.recent_folders.iter().map(string).collect(),
+            )),
+            "source.folder" => Some(string(self.source.folder.raw())),
+            "source.pattern" => Some(string(self.source.pattern.as_str())),
+            "source.exclude_pattern" => self.source.exclude_pattern.as_ref().map(|x| string(x.as_str())),
+            "source.keep_old" => Some(Value::Boolean(self.source.keep_old)),
+            "source.hard_link" => Some(Value::Boolean(self.source.hard_link)),
+            "source.leave_link" => Some(Value::Boolean(self.source.leave_link)),
+            "source.capture_live" => Some(Value::Boolean(self.source.capture_live)),
+            "source.recursive" => Some(Value::Boolean(self.source.recursive)),
+            "source.since" => self.source.since.map(|x| string(x.format(DATE_FORMAT))),
+            "source.until" => self.source.until.map(|x| string(x.format(DATE_FORMAT))),
+            "source.header_format" => Some(string(&self.source.header_format)),
+            "source.launch_time" => Some(Value::Array(
+                self.source.launch_time.iter().map(|x| string(x.name())).collect(),
+            )),
+            "source.in_use" => Some(string(match self.source.in_use {
+                InUse::Open => "open",
+                InUse::Process => "process",
+                InUse::Mtime => "mtime",
+                InUse::None => "none",
+            })),
+            "source.locked_retry" => Some(Value::Integer(self.source.locked_retry.into())),
+            "source.locked_retry_delay_ms" => {
+                Some(Value::Integer(self.source.locked_retry_delay_ms.into()))
+            }
+            "source.unparsable" => Some(match self.source.unparsable {
+                Unparsable::Error => string("error"),
+                Unparsable::Quarantine => string("quarantine"),
+                Unparsable::Ctime => string("ctime"),
+            }),
+            "source.output.folder" => self.source.output.folder.as_ref().map(|x| string(x.raw())),
+            "source.output.pattern" => self
+                .source
+                .output
+                .pattern
+                .as_ref()
+                .map(|x| string(x.as_str())),
+            "output.folder" => Some(string(self.output.folder.raw())),
+            "output.pattern" => Some(string(self.output.pattern_as_string())),
+            "output.utc_time" => Some(Value::Boolean(self.output.utc_time)),
+            "output.time_zone" => self.output.time_zone.as_ref().map(string),
+            "output.file_ctime" => Some(Value::Boolean(self.output.file_ctime)),
+            "output.replacement" => Some(string(self.output.replacement)),
+            "output.split_worlds" => Some(Value::Boolean(self.output.split_worlds)),
+            "output.compress" => Some(Value::Boolean(self.output.compress)),
+            "output.checksums" => Some(Value::Boolean(self.output.checksums)),
+            "output.copy_buffer_kib" => Some(Value::Integer(self.output.copy_buffer_kib.into())),
+            "output.read_only" => Some(Value::Boolean(self.output.read_only)),
+            "output.dedupe" => Some(match self.output.dedupe {
+                Dedupe::Off => string("off"),
+                Dedupe::Skip => string("skip"),
+                Dedupe::Link => string("link"),
+            }),
+            "output.filter_lines" => Some(Value::Array(
+                self.output.filter_lines.iter().map(|x| string(x.as_str())).collect(),
+            )),
+            "output.mirror_folders" => Some(Value::Array(
+                self.output.mirror_folders.iter().map(|x| string(x.raw())).collect(),
+            )),
+            "notify.discord.webhook_url" => self.notify.discord().map(|x| string(&x.webhook_url)),
+            "hooks.before_run" => self.hooks.before_run.as_ref().map(string),
+            "hooks.after_file" => self.hooks.after_file.as_ref().map(string),
+            "hooks.after_run" => self.hooks.after_run.as_ref().map(string),
+            "upload.kind" => upload.map(|x| match x.kind {
+                UploadKind::WebDav => string("webdav"),
+                UploadKind::S3 => string("s3"),
+            }),
+            "upload.url" => upload.map(|x| string(&x.url)),
+            "upload.bucket" => upload.and_then(|x| x.bucket.as_ref().map(string)),
+            "upload.region" => upload.and_then(|x| x.region.as_ref().map(string)),
+            "upload.username" => upload.and_then(|x| x.username.as_ref().map(string)),
+            "upload.password" => upload.and_then(|x| x.password.as_ref().map(string)),
+            "upload.credential" => upload.and_then(|x| x.credential.as_ref().map(string)),
+            "upload.retry" => upload.map(|x| Value::Integer(x.retry.into())),
+            "pictures.folder" => pictures.map(|x| string(x.folder.raw())),
+            "pictures.pattern" => pictures.map(|x| string(x.pattern.as_str())),
+            "pictures.keep_old" => pictures.map(|x| Value::Boolean(x.keep_old)),
+            "pictures.output_folder" => pictures.map(|x| string(x.output_folder.raw())),
+            "pictures.output_pattern" => {
+                pictures.map(|x| string(x.output_pattern.as_str()))
+            }
+            "crashes.folder" => self.crashes.as_ref().map(|x| string(x.folder.raw())),
+            "schedule.time" => Some(string(self.schedule.time_as_string())),
+            "schedule.interval_days" => Some(Value::Integer(self.schedule.interval_days.into())),
+            "schedule.task_path" => Some(string(&self.schedule.task_path)),
+            "schedule.triggers" => Some(Value::Array(
+                self.schedule.triggers.iter().map(string).collect(),
+            )),
+            "schedule.wake_to_run" => Some(Value::Boolean(self.schedule.wake_to_run)),
+            "schedule.start_when_available" => {
+                Some(Value::Boolean(self.schedule.start_when_available))
+            }
+            "schedule.installer" => Some(match self.schedule.installer {
+                Installer::TaskScheduler => string("task_scheduler"),
+                Installer::RunKey => string("run_key"),
+            }),
+            _ => return Err(invalid_key(key)),
+        })
+    }
+
+    /// validates and sets the value of the key
+    pub fn set_value(&mut self, key: &str, value: &str) -> io::Result<()> {
+        match key {
+            "unknown_keys" => self.unknown_keys = UnknownKeys::parse(value)?,
+            "check_updates" => self.check_updates = parse_bool(key, value)?,
+            "low_priority" => self.low_priority = parse_bool(key, value)?,
+            "incremental" => self.incremental = parse_bool(key, value)?,
+            "theme" => self.theme = Theme::parse(value)?,
+            "recent_folders" => self.recent_folders = parse_string_array(key, value)?,
+            "source.folder" => self.source.folder = ConfigPath::new(value.to_owned())?,
+            "source.pattern" => {
+                self.source.pattern =
+                    Regex::new(value).map_err(|e| Error::new(ErrorKind::InvalidInput, e))?
+            }
+            "source.exclude_pattern" => {
+                self.source.exclude_pattern = Source::parse_exclude_pattern(value)?
+            }
+            "source.keep_old" => self.source.keep_old = parse_bool(key, value)?,
+            "source.hard_link" => self.source.hard_link = parse_bool(key, value)?,
+            "source.leave_link" => self.source.leave_link = parse_bool(key, value)?,
+            "source.capture_live" => self.source.capture_live = parse_bool(key, value)?,
+            "source.recursive" => self.source.recursive = parse_bool(key, value)?,
+            "source.since" => self.source.since = Source::parse_date(value)?,
+            "source.until" => self.source.until = Source::parse_date(value)?,
+            "source.header_format" => {
+                self.source.header_format = Source::parse_header_format(value)?
+            }
+            "source.launch_time" => {
+                self.source.launch_time = parse_string_array(key, value)?
+                    .iter()
+                    .map(|x| LaunchTime::parse(x))
+                    .collect::<io::Result<_>>()?
+            }
+            "source.in_use" => self.source.in_use = InUse::parse(value)?,
+            "source.locked_retry" => {
+                self.source.locked_retry = value
+                    .parse()
+                    .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+                self.source.validate_locked_retry(ErrorKind::InvalidInput)?
+            }
+            "source.locked_retry_delay_ms" => {
+                self.source.locked_retry_delay_ms = value
+                    .parse()
+                    .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+                self.source.validate_locked_retry(ErrorKind::InvalidInput)?
+            }
+            "source.unparsable" => self.source.unparsable = Unparsable::parse(value)?,
+            "source.output.folder" => {
+                self.source.output.folder = Some(ConfigPath::new(value.to_owned())?)
+            }
+            "source.output.pattern" => self.source.output.pattern = Some(parse_pattern_arg(value)?),
+            "output.folder" => self.output.folder = ConfigPath::new(value.to_owned())?,
+            "output.pattern" => self.output.pattern = parse_pattern_arg(value)?,
+            "output.utc_time" => self.output.utc_time = parse_bool(key, value)?,
+            "output.time_zone" => self.output.time_zone = TimeZone::parse(value)?,
+            "output.file_ctime" => self.output.file_ctime = parse_bool(key, value)?,
+            "output.split_worlds" => self.output.split_worlds = parse_bool(key, value)?,
+            "output.compress" => self.output.compress = parse_bool(key, value)?,
+            "output.checksums" => self.output.checksums = parse_bool(key, value)?,
+            "output.copy_buffer_kib" => {
+                self.output.copy_buffer_kib = match value.parse() {
+                    Ok(0) => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidInput,
+                            "output.copy_buffer_kib must be positive",
+                        ))
+                    }
+                    Ok(kib) => kib,
+                    Err(e) => return Err(Error::new(ErrorKind::InvalidInput, e)),
+                }
+            }
+            "output.read_only" => self.output.read_only = parse_bool(key, value)?,
+            "output.dedupe" => self.output.dedupe = Dedupe::parse(value)?,
+            "output.filter_lines" => {
+                self.output.filter_lines = parse_string_array(key, value)?
+                    .iter()
+                    .map(|x| Regex::new(x).map_err(|e| Error::new(ErrorKind::InvalidInput, e)))
+                    .collect::<io::Result<_>>()?
+            }
+            "output.mirror_folders" => {
+                self.output.mirror_folders = parse_string_array(key, value)?
+                    .into_iter()
+                    .map(ConfigPath::new)
+                    .collect::<io::Result<_>>()?
+            }
+            "output.replacement" => {
+                let mut chars = value.chars();
+                self.output.replacement = match (chars.next(), chars.next()) {
+                    (Some(c), None) => c,
+                    _ => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidInput,
+                            "output.replacement must be a character",
+                        ))
+                    }
+                }
+            }
+            "notify.discord.webhook_url" => {
+                self.notify.discord = Some(DiscordNotify {
+                    webhook_url: value.to_owned(),
+                })
+            }
+            "hooks.before_run" => self.hooks.before_run = Some(value.to_owned()),
+            "hooks.after_file" => self.hooks.after_file = Some(value.to_owned()),
+            "hooks.after_run" => self.hooks.after_run = Some(value.to_owned()),
+            _ if key.starts_with("upload.") => {
+                let upload = self.upload.get_or_insert_with(Default::default);
+                match key {
+                    "upload.kind" => {
+                        upload.kind = match value {
+                            "webdav" => UploadKind::WebDav,
+                            "s3" => UploadKind::S3,
+                            _ => {
+                                return Err(Error::new(
+                                    ErrorKind::InvalidInput,
+                                    "upload.kind must be 'webdav' or 's3'",
+                                ))
+                            }
+                        }
+                    }
+                    "upload.url" => upload.url = value.to_owned(),
+                    "upload.bucket" => upload.bucket = Some(value.to_owned()),
+                    "upload.region" => upload.region = Some(value.to_owned()),
+                    "upload.username" => upload.username = Some(value.to_owned()),
+                    "upload.password" => upload.password = Some(value.to_owned()),
+                    "upload.credential" => upload.credential = Some(value.to_owned()),
+                    "upload.retry" => {
+                        upload.retry = value
+                            .parse()
+                            .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+                        upload.validate_retry(ErrorKind::InvalidInput)?
+                    }
+                    _ => return Err(invalid_key(key)),
+                }
+            }
+            _ if key.starts_with("pictures.") => {
+                let pictures = self.pictures.get_or_insert_with(Default::default);
+                match key {
+                    "pictures.folder" => pictures.folder = ConfigPath::new(value.to_owned())?,
+                    "pictures.pattern" => {
+                        pictures.pattern =
+                            Regex::new(value).map_err(|e| Error::new(ErrorKind::InvalidInput, e))?
+                    }
+                    "pictures.keep_old" => pictures.keep_old = parse_bool(key, value)?,
+                    "pictures.output_folder" => {
+                        pictures.output_folder = ConfigPath::new(value.to_owned())?
+                    }
+                    "pictures.output_pattern" => pictures.output_pattern = parse_pattern_arg(value)?,
+                    _ => return Err(invalid_key(key)),
+                }
+            }
+            "crashes.folder" => {
+                self.crashes = Some(Crashes {
+                    folder: ConfigPath::new(value.to_owned())?,
+                })
+            }
+            "schedule.time" => self.schedule.time = Schedule::parse_time(value)?,
+            "schedule.interval_days" => {
+                self.schedule.interval_days = Schedule::parse_interval_days(value)?
+            }
+            "schedule.task_path" => self.schedule.task_path = Schedule::parse_task_path(value)?,
+            "schedule.triggers" => {
+                self.schedule.triggers = parse_string_array(key, value)?
+                    .iter()
+                    .map(|x| Trigger::parse(x))
+                    .collect::<io::Result<_>>()?
+            }
+            "schedule.wake_to_run" => self.schedule.wake_to_run = parse_bool(key, value)?,
+            "schedule.start_when_available" => {
+                self.schedule.start_when_available = parse_bool(key, value)?
+            }
+            "schedule.installer" => self.schedule.installer = Installer::parse(value)?,
+            _ => return Err(invalid_key(key)),
+        }
+        Ok(())
+    }
+
+    /// returns the config file with all keys and default values commented out.
+    /// optional values are shown as empty
+    pub fn default_config_text() -> String {
+        let mut config = ConfigFile::default();
+        config.notify.discord = Some(Default::default());
+        config.upload = Some(Default::default());
+        config.pictures = Some(Default::default());
+        config.crashes = Some(Default::default());
+
+        let mut text = String::new();
+        text.push_str("# the default config of VRC Log Renamer.\n");
+        text.push_str("# uncomment the table header and keys to change the value.\n");
+        let mut current_table = "";
+        for key in CONFIG_KEYS {
+            let (table, name) = key.rsplit_once('.').unwrap_or(("", key));
+            if table != current_table {
+                text.push_str(&format!("\n# [{}]\n", table));
+                current_table = table;
+            }
+            let value = config
+                .get_value(key)
+                .unwrap()
+                .unwrap_or_else(|| Value::String(String::new()));
+            text.push_str(&format!("# {} = {}\n", name, value));
+        }
+        text
+    }
+
+    /// checks the values which depend on other values
+    pub fn validate(&self) -> io::Result<()> {
+        if is_invalid_file_name_char(self.output.replacement) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "output.replacement '{}' cannot be used in file name",
+                    self.output.replacement
+                ),
+            ));
         }
-        if let Some(output) = toml.get("output") {
-            self.output.read_from_file(output)?
+        for source in self.sources() {
+            source.validate_locked_retry(ErrorKind::InvalidData)?;
+        }
+        if let Some(upload) = &self.upload {
+            upload.validate()?;
         }
         Ok(())
     }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Source {
+    #[serde(
+        skip_serializing_if = "Source::is_folder_default",
+        default = "Source::folder_default"
+    )]
+    folder: ConfigPath,
+    #[serde(
+        skip_serializing_if = "Source::is_pattern_default",
+        default = "Source::pattern_default",
+        serialize_with = "serialize_regex",
+        deserialize_with = "deserialize_regex"
+    )]
+    pattern: Regex,
+    /// the logs matching this are not renamed even if they match `pattern`
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        serialize_with = "serialize_optional_regex",
+        deserialize_with = "deserialize_optional_regex"
+    )]
+    exclude_pattern: Option<Regex>,
+    #[serde(
+        skip_serializing_if = "Source::is_keep_old_default",
+        default = "Source::keep_old_default"
+    )]
+    keep_old: bool,
+    /// creates hard link instead of copying if `keep_old` and the output is in the same volume
+    #[serde(
+        skip_serializing_if = "Source::is_hard_link_default",
+        default = "Source::hard_link_default"
+    )]
+    hard_link: bool,
+    /// leaves a symbolic link, or a shortcut if not permitted, at the original path after moving
+    /// for the tools looking for the logs in the VRChat folder
+    #[serde(
+        skip_serializing_if = "Source::is_leave_link_default",
+        default = "Source::leave_link_default"
+    )]
+    leave_link: bool,
+    /// copies the log in use by the running VRChat to `partial` folder in the output folder
+    /// through a shadow copy of the volume. requires administrator
+    #[serde(
+        skip_serializing_if = "Source::is_capture_live_default",
+        default = "Source::capture_live_default"
+    )]
+    capture_live: bool,
+    /// also finds the logs in the subfolders. `{source:reldir}` in the output pattern is replaced
+    /// with the subfolder like `2022\`
+    #[serde(
+        skip_serializing_if = "Source::is_recursive_default",
+        default = "Source::recursive_default"
+    )]
+    recursive: bool,
+    /// the logs launched before this date are not renamed
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        serialize_with = "serialize_optional_date",
+        deserialize_with = "deserialize_optional_date"
+    )]
+    since: Option<NaiveDate>,
+    /// the logs launched after this date are not renamed
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        serialize_with = "serialize_optional_date",
+        deserialize_with = "deserialize_optional_date"
+    )]
+    until: Option<NaiveDate>,
+    /// the format of the time at the head of the log, which is read as the launch time
+    #[serde(
+        skip_serializing_if = "Source::is_header_format_default",
+        default = "Source::header_format_default",
+        deserialize_with = "deserialize_header_format"
+    )]
+    header_format: String,
+    /// where to read the launch time from. tried in order until one is found
+    #[serde(
+        skip_serializing_if = "Source::is_launch_time_default",
+        default = "Source::launch_time_default"
+    )]
+    launch_time: Vec<LaunchTime>,
+    #[serde(
+        skip_serializing_if = "Source::is_in_use_default",
+        default = "Source::in_use_default"
+    )]
+    in_use: InUse,
+    /// how many times to open the locked log again, like the one antivirus is scanning
+    #[serde(
+        skip_serializing_if = "Source::is_locked_retry_default",
+        default = "Source::locked_retry_default"
+    )]
+    locked_retry: u32,
+    /// the wait before the first retry, which is doubled for each retry
+    #[serde(
+        skip_serializing_if = "Source::is_locked_retry_delay_ms_default",
+        default = "Source::locked_retry_delay_ms_default"
+    )]
+    locked_retry_delay_ms: u32,
+    #[serde(
+        skip_serializing_if = "Source::is_unparsable_default",
+        default = "Source::unparsable_default"
+    )]
+    unparsable: Unparsable,
+    /// overrides of `[output]` for this source
+    #[serde(skip_serializing_if = "SourceOutput::is_empty", default)]
+    output: SourceOutput,
+}
+
+/// where the launch time of the log is read from
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LaunchTime {
+    /// the time at the head of the log in `header_format`
+    Header,
+    /// the creation time of the file
+    Ctime,
+    /// the modification time of the file
+    Mtime,
+    /// the captures of `pattern` named `year`, `month`, `day`, `hour`, `minute` and `second`,
+    /// or `Y`, `m`, `d`, `H`, `M` and `S` like strftime
+    FileName,
+}
+
+impl LaunchTime {
+    fn parse(str: &str) -> io::Result<Self> {
+        match str {
+            "header" => Ok(Self::Header),
+            "ctime" => Ok(Self::Ctime),
+            "mtime" => Ok(Self::Mtime),
+            "file_name" => Ok(Self::FileName),
+            _ => Err(Error::new(
+                ErrorKind::InvalidInput,
+                "source.launch_time must be 'header', 'ctime', 'mtime' or 'file_name'",
+            )),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Header => "header",
+            Self::Ctime => "ctime",
+            Self::Mtime => "mtime",
+            Self::FileName => "file_name",
+        }
+    }
+}
+
+/// how to find the log VRChat is writing to, which is not renamed
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum InUse {
+    /// the log cannot be opened for writing. may not work on network shares ignoring the lock
+    Open,
+    /// VRChat is running and the log is the newest one in the folder
+    Process,
+    /// the log is modified in the last few minutes
+    Mtime,
+    /// every log is renamed
+    None,
+}
+
+impl InUse {
+    fn parse(str: &str) -> io::Result<Self> {
+        match str {
+            "open" => Ok(Self::Open),
+            "process" => Ok(Self::Process),
+            "mtime" => Ok(Self::Mtime),
+            "none" => Ok(Self::None),
+            _ => Err(Error::new(
+                ErrorKind::InvalidInput,
+                "source.in_use must be 'open', 'process', 'mtime' or 'none'",
+            )),
+        }
+    }
+}
+
+/// how to handle empty or corrupted logs whose launch time cannot be read
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Unparsable {
+    /// fails renaming the log every run
+    Error,
+    /// moves the log to `unparsed` folder in the output folder
+    Quarantine,
+    /// names the log with the creation time of the file
+    Ctime,
+}
+
+impl Unparsable {
+    fn parse(str: &str) -> io::Result<Self> {
+        match str {
+            "error" => Ok(Self::Error),
+            "quarantine" => Ok(Self::Quarantine),
+            "ctime" => Ok(Self::Ctime),
+            _ => Err(Error::new(
+                ErrorKind::InvalidInput,
+                "source.unparsable must be 'error', 'quarantine' or 'ctime'",
+            )),
+        }
+    }
+}
+
+/// how to handle the log whose content is same as the one archived before,
+/// like after moving VRChat folder or restoring it from backup
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Dedupe {
+    /// archives the log again
+    Off,
+    /// doesn't archive the log
+    Skip,
+    /// creates hard link to the archived one instead of copying
+    Link,
+}
+
+impl Dedupe {
+    fn parse(str: &str) -> io::Result<Self> {
+        match str {
+            "off" => Ok(Self::Off),
+            "skip" => Ok(Self::Skip),
+            "link" => Ok(Self::Link),
+            _ => Err(Error::new(
+                ErrorKind::InvalidInput,
+                "output.dedupe must be 'off', 'skip' or 'link'",
+            )),
+        }
+    }
+}
+
+/// the output config overridden for each source.
+/// the values not specified fall back to the global `[output]`
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SourceOutput {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    folder: Option<ConfigPath>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        serialize_with = "serialize_optional_pattern",
+        deserialize_with = "deserialize_optional_pattern"
+    )]
+    pattern: Option<Pattern>,
+}
+
+impl SourceOutput {
+    fn is_empty(&self) -> bool {
+        self.folder.is_none() && self.pattern.is_none()
+    }
+}
+
+/// the folder path in config.
+/// environment variables (`%VAR%` or `${env:VAR}`) and known folders (`{LocalLow}`, `{Documents}` etc.)
+/// are expanded on load, and the path before expansion is saved to keep config portable.
+#[derive(Debug, Clone)]
+pub struct ConfigPath {
+    raw: String,
+    expanded: PathBuf,
+}
+
+impl ConfigPath {
+    pub fn new(raw: String) -> io::Result<Self> {
+        let expanded = expand_path(&raw)?;
+        Ok(Self { raw, expanded })
+    }
+
+    fn from_path(path: PathBuf) -> Self {
+        Self {
+            raw: path.to_string_lossy().into_owned(),
+            expanded: path,
+        }
+    }
+
+    pub fn raw(&self) -> &str {
+        &self.raw
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.expanded
+    }
+}
+
+impl Serialize for ConfigPath {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&self.raw)
+    }
+}
+
+impl<'de> Deserialize<'de> for ConfigPath {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        ConfigPath::new(String::deserialize(d)?).map_err(D::Error::custom)
+    }
+}
+
+fn expand_path(raw: &str) -> io::Result<PathBuf> {
+    let mut builder = String::new();
+    let mut rest = raw;
+    while let Some(start) = rest.find(&['%', '$', '{'][..]) {
+        builder.push_str(&rest[..start]);
+        rest = &rest[start..];
+        if let Some(var) = rest.strip_prefix("${env:") {
+            let end = var.find('}').ok_or_else(|| {
+                Error::new(ErrorKind::InvalidData, format!("unclosed '${{env:' in {}", raw))
+            })?;
+            let value = std::env::var(&var[..end]).map_err(|_| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!("environment variable {} is not defined", &var[..end]),
+                )
+            })?;
+            builder.push_str(&value);
+            rest = &var[end + 1..];
+        } else if let Some(name) = rest.strip_prefix('{') {
+            let end = name.find('}').ok_or_else(|| {
+                Error::new(ErrorKind::InvalidData, format!("unclosed '{{' in {}", raw))
+            })?;
+            builder.push_str(&known_folder(&name[..end])?.to_string_lossy());
+            rest = &name[end + 1..];
+        } else if let Some(name) = rest.strip_prefix('%') {
+            // like cmd.exe, undefined %VAR% is kept as is
+            match name
+                .find('%')
+                .and_then(|end| Some((end, std::env::var(&name[..end]).ok()?)))
+            {
+                Some((end, value)) => {
+                    builder.push_str(&value);
+                    rest = &name[end + 1..];
+                }
+                None => {
+                    builder.push('%');
+                    rest = name;
+                }
+            }
+        } else {
+            builder.push_str(&rest[..1]);
+            rest = &rest[1..];
+        }
+    }
+    builder.push_str(rest);
+    Ok(PathBuf::from(builder))
+}
+
+fn known_folder(name: &str) -> io::Result<PathBuf> {
+    use winsafe::co::{KF, KNOWNFOLDERID};
+    let id = match name {
+        "LocalLow" => KNOWNFOLDERID::LocalAppDataLow,
+        "LocalAppData" => KNOWNFOLDERID::LocalAppData,
+        "AppData" => KNOWNFOLDERID::RoamingAppData,
+        "Profile" => KNOWNFOLDERID::Profile,
+        "Desktop" => KNOWNFOLDERID::Desktop,
+        "Documents" => KNOWNFOLDERID::Documents,
+        "Downloads" => KNOWNFOLDERID::Downloads,
+        "Pictures" => KNOWNFOLDERID::Pictures,
+        _ => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("unknown folder {{{}}}", name),
+            ))
+        }
+    };
+    winsafe::SHGetKnownFolderPath(&id, KF::DEFAULT, None)
+        .map(PathBuf::from)
+        .map_err(|e| Error::other(format!("getting {{{}}}: {}", name, e)))
+}
+
+fn serialize_regex<S: serde::Serializer>(regex: &Regex, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(regex.as_str())
+}
+
+fn deserialize_regex<'de, D: Deserializer<'de>>(d: D) -> Result<Regex, D::Error> {
+    Regex::new(&String::deserialize(d)?).map_err(D::Error::custom)
+}
+
+fn serialize_optional_regex<S: serde::Serializer>(
+    regex: &Option<Regex>,
+    s: S,
+) -> Result<S::Ok, S::Error> {
+    serialize_regex(regex.as_ref().unwrap(), s)
+}
+
+fn deserialize_optional_regex<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Regex>, D::Error> {
+    deserialize_regex(d).map(Some)
+}
+
+/// the format of dates in config like `2022-10-01`
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+fn serialize_optional_date<S: serde::Serializer>(
+    date: &Option<NaiveDate>,
+    s: S,
+) -> Result<S::Ok, S::Error> {
+    s.serialize_str(&date.unwrap().format(DATE_FORMAT).to_string())
+}
+
+fn deserialize_optional_date<'de, D: Deserializer<'de>>(
+    d: D,
+) -> Result<Option<NaiveDate>, D::Error> {
+    Source::parse_date(&String::deserialize(d)?).map_err(D::Error::custom)
+}
+
+fn deserialize_header_format<'de, D: Deserializer<'de>>(d: D) -> Result<String, D::Error> {
+    Source::parse_header_format(&String::deserialize(d)?).map_err(D::Error::custom)
+}
+
+fn serialize_optional_time_zone<S: serde::Serializer>(
+    time_zone: &Option<TimeZone>,
+    s: S,
+) -> Result<S::Ok, S::Error> {
+    s.collect_str(time_zone.as_ref().unwrap())
+}
+
+fn deserialize_optional_time_zone<'de, D: Deserializer<'de>>(
+    d: D,
+) -> Result<Option<TimeZone>, D::Error> {
+    TimeZone::parse(&String::deserialize(d)?).map_err(D::Error::custom)
+}
+
+fn serialize_regexes<S: serde::Serializer>(regexes: &[Regex], s: S) -> Result<S::Ok, S::Error> {
+    s.collect_seq(regexes.iter().map(Regex::as_str))
+}
+
+fn deserialize_regexes<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<Regex>, D::Error> {
+    Vec::<String>::deserialize(d)?
+        .iter()
+        .map(|x| Regex::new(x).map_err(D::Error::custom))
+        .collect()
+}
+
+impl Source {
+    default_fns!(folder: ConfigPath = ConfigPath::from_path(local_low_appdata_path().join("VRChat").join("VRChat")); |x| x.raw());
+    default_fns!(pattern: Regex = Regex::new(r#"^output_log_(?:\d{4}-\d{2}-\d{2}_)?\d{2}-\d{2}-\d{2}(?P<in_sec_num>\d+)?\.txt$"#).unwrap(); |x| x.as_str());
+    default_fns!(keep_old: bool = true);
+    default_fns!(hard_link: bool = false);
+    default_fns!(leave_link: bool = false);
+    default_fns!(capture_live: bool = false);
+    default_fns!(recursive: bool = false);
+    default_fns!(header_format: String = "%Y.%m.%d %H:%M:%S".to_owned());
+    default_fns!(launch_time: Vec<LaunchTime> = vec![LaunchTime::Header]);
+    default_fns!(in_use: InUse = InUse::Open);
+    default_fns!(locked_retry: u32 = 0);
+    default_fns!(locked_retry_delay_ms: u32 = 1000);
+    const MAX_LOCKED_RETRY: u32 = 10;
+    const MAX_LOCKED_RETRY_DELAY_MS: u32 = 60_000;
+
+    fn validate_locked_retry(&self, kind: ErrorKind) -> io::Result<()> {
+        if self.locked_retry > Self::MAX_LOCKED_RETRY {
+            return Err(Error::new(
+                kind,
+                format!(
+                    "source.locked_retry must be at most {}",
+                    Self::MAX_LOCKED_RETRY
+                ),
+            ));
+        }
+        if self.locked_retry_delay_ms > Self::MAX_LOCKED_RETRY_DELAY_MS {
+            return Err(Error::new(
+                kind,
+                format!(
+                    "source.locked_retry_delay_ms must be at most {}",
+                    Self::MAX_LOCKED_RETRY_DELAY_MS
+                ),
+            ));
+        }
+        Ok(())
+    }
+    default_fns!(unparsable: Unparsable = Unparsable::Error);
+
+    pub fn folder(&self) -> &PathBuf {
+        self.folder.path()
+    }
+    pub fn folder_raw(&self) -> &str {
+        self.folder.raw()
+    }
+    pub fn pattern(&self) -> &Regex {
+        &self.pattern
+    }
+    pub fn exclude_pattern(&self) -> Option<&Regex> {
+        self.exclude_pattern.as_ref()
+    }
+    pub fn keep_old(&self) -> bool {
+        self.keep_old
+    }
+    pub fn hard_link(&self) -> bool {
+        self.hard_link
+    }
+    pub fn leave_link(&self) -> bool {
+        self.leave_link
+    }
+    pub fn capture_live(&self) -> bool {
+        self.capture_live
+    }
+    pub fn recursive(&self) -> bool {
+        self.recursive
+    }
+
+    pub fn has_date_range(&self) -> bool {
+        self.since.is_some() || self.until.is_some()
+    }
+
+    /// true if the launch date is in `since` and `until`. both are inclusive
+    pub fn is_in_date_range(&self, date: NaiveDate) -> bool {
+        self.since.is_none_or(|since| since <= date) && self.until.is_none_or(|until| date <= until)
+    }
+
+    /// parses the date like `2022-10-01`. empty means no limit
+    pub fn parse_date(value: &str) -> io::Result<Option<NaiveDate>> {
+        if value.is_empty() {
+            return Ok(None);
+        }
+        NaiveDate::parse_from_str(value, DATE_FORMAT)
+            .map(Some)
+            .map_err(|_| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("'{}' is not a date like 2022-10-01", value),
+                )
+            })
+    }
+    pub fn header_format(&self) -> &str {
+        &self.header_format
+    }
+
+    pub fn launch_time(&self) -> &[LaunchTime] {
+        &self.launch_time
+    }
+
+    pub fn in_use(&self) -> InUse {
+        self.in_use
+    }
+
+    pub fn locked_retry(&self) -> u32 {
+        self.locked_retry
+    }
+
+    pub fn locked_retry_delay(&self) -> Duration {
+        Duration::from_millis(self.locked_retry_delay_ms.into())
+    }
+
+    /// checks the strftime format of the log header can be written without time zone
+    pub fn parse_header_format(value: &str) -> io::Result<String> {
+        use std::fmt::Write as _;
+        let sample = NaiveDate::from_ymd(2022, 10, 1).and_hms(12, 34, 56);
+        let mut formatted = String::new();
+        if value.is_empty() || write!(formatted, "{}", sample.format(value)).is_err() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("'{}' is invalid header format", value),
+            ));
+        }
+        Ok(value.to_owned())
+    }
+    pub fn unparsable(&self) -> Unparsable {
+        self.unparsable
+    }
+
+    /// true if the file name matches the exclude pattern
+    pub fn is_excluded(&self, file_name: &str) -> bool {
+        self.exclude_pattern
+            .as_ref()
+            .is_some_and(|x| x.is_match(file_name))
+    }
+
+    /// parses the exclude pattern. empty means no file is excluded
+    pub fn parse_exclude_pattern(value: &str) -> io::Result<Option<Regex>> {
+        if value.is_empty() {
+            return Ok(None);
+        }
+        Regex::new(value)
+            .map(Some)
+            .map_err(|e| Error::new(ErrorKind::InvalidInput, e))
+    }
+
+    pub fn new(
+        folder: ConfigPath,
+        pattern: Regex,
+        exclude_pattern: Option<Regex>,
+        header_format: String,
+        keep_old: bool,
+    ) -> Self {
+        Self {
+            folder,
+            pattern,
+            exclude_pattern,
+            keep_old,
+            hard_link: Self::hard_link_default(),
+            leave_link: Self::leave_link_default(),
+            capture_live: Self::capture_live_default(),
+            recursive: Self::recursive_default(),
+            since: None,
+            until: None,
+            header_format,
+            launch_time: Self::launch_time_default(),
+            in_use: Self::in_use_default(),
+            locked_retry: Self::locked_retry_default(),
+            locked_retry_delay_ms: Self::locked_retry_delay_ms_default(),
+            unparsable: Self::unparsable_default(),
+            output: Default::default(),
+        }
+    }
+}
+
+impl Default for Source {
+    fn default() -> Self {
+        Source {
+            folder: Self::folder_default(),
+            pattern: Self::pattern_default(),
+            exclude_pattern: None,
+            keep_old: Self::keep_old_default(),
+            hard_link: Self::hard_link_default(),
+            leave_link: Self::leave_link_default(),
+            capture_live: Self::capture_live_default(),
+            recursive: Self::recursive_default(),
+            since: None,
+            until: None,
+            header_format: Self::header_format_default(),
+            launch_time: Self::launch_time_default(),
+            in_use: Self::in_use_default(),
+            locked_retry: Self::locked_retry_default(),
+            locked_retry_delay_ms: Self::locked_retry_delay_ms_default(),
+            unparsable: Self::unparsable_default(),
+            output: Default::default(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Output {
+    #[serde(
+        skip_serializing_if = "Output::is_folder_default",
+        default = "Output::folder_default"
+    )]
+    folder: ConfigPath,
+    #[serde(
+        skip_serializing_if = "Output::is_pattern_default",
+        default = "Output::pattern_default",
+        serialize_with = "serialize_pattern",
+        deserialize_with = "deserialize_pattern"
+    )]
+    pattern: Pattern,
+    #[serde(
+        skip_serializing_if = "Output::is_utc_time_default",
+        default = "Output::utc_time_default"
+    )]
+    utc_time: bool,
+    /// the fixed offset like `+09:00` or the IANA time zone like `Asia/Tokyo` to format the names
+    /// in. `utc_time` is ignored if this is set
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default,
+        serialize_with = "serialize_optional_time_zone",
+        deserialize_with = "deserialize_optional_time_zone"
+    )]
+    time_zone: Option<TimeZone>,
+    #[serde(
+        skip_serializing_if = "Output::is_file_ctime_default",
+        default = "Output::file_ctime_default"
+    )]
+    file_ctime: bool,
+    /// the character used instead of characters cannot be used in file name
+    #[serde(
+        skip_serializing_if = "Output::is_replacement_default",
+        default = "Output::replacement_default"
+    )]
+    replacement: char,
+    /// also writes the log split per world visit into `worlds` folder
+    #[serde(
+        skip_serializing_if = "Output::is_split_worlds_default",
+        default = "Output::split_worlds_default"
+    )]
+    split_worlds: bool,
+    /// compresses the written logs with NTFS compression, which keeps them readable directly
+    #[serde(
+        skip_serializing_if = "Output::is_compress_default",
+        default = "Output::compress_default"
+    )]
+    compress: bool,
+    /// maintains `SHA256SUMS` in the output folder with the hashes of the archived logs
+    /// to find corrupted or missing logs with `verify-archive`
+    #[serde(
+        skip_serializing_if = "Output::is_checksums_default",
+        default = "Output::checksums_default"
+    )]
+    checksums: bool,
+    /// the buffer to copy the log with, hashing it at the same time for `checksums`
+    #[serde(
+        skip_serializing_if = "Output::is_copy_buffer_kib_default",
+        default = "Output::copy_buffer_kib_default"
+    )]
+    copy_buffer_kib: u32,
+    /// marks the archived logs read-only to protect them from edits and deletion by other tools
+    #[serde(
+        skip_serializing_if = "Output::is_read_only_default",
+        default = "Output::read_only_default"
+    )]
+    read_only: bool,
+    #[serde(
+        skip_serializing_if = "Output::is_dedupe_default",
+        default = "Output::dedupe_default"
+    )]
+    dedupe: Dedupe,
+    /// the lines matching any of the regexes are dropped on copying
+    #[serde(
+        skip_serializing_if = "Vec::is_empty",
+        default,
+        serialize_with = "serialize_regexes",
+        deserialize_with = "deserialize_regexes"
+    )]
+    filter_lines: Vec<Regex>,
+    /// the folders the renamed logs are also copied to like a synced cloud folder.
+    /// the source is moved only after all copies succeed
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    mirror_folders: Vec<ConfigPath>,
+}
+
+fn is_invalid_file_name_char(c: char) -> bool {
+    matches!(c, '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*') || c.is_control()
+}
+
+/// returns the character in the literals of the pattern which cannot be used in file name.
+/// `{namespace:name}` variables are not checked since they're replaced on formatting.
+/// `\` is allowed to separate folders
+pub fn invalid_pattern_char(pattern: &[Item<'static>]) -> Option<char> {
+    let mut literals = String::new();
+    for item in pattern {
+        match item {
+            Item::Literal(s) => literals.push_str(s),
+            Item::OwnedLiteral(s) => literals.push_str(s),
+            _ => literals.push('_'),
+        }
+    }
+    let mut rest = literals.as_str();
+    let mut checking = String::new();
+    while let Some(start) = rest.find('{') {
+        checking.push_str(&rest[..start]);
+        match rest[start..].find('}') {
+            Some(end) => rest = &rest[start + end + 1..],
+            None => {
+                rest = &rest[start..];
+                break;
+            }
+        }
+    }
+    checking.push_str(rest);
+    checking
+        .chars()
+        .find(|&c| c != '\\' && is_invalid_file_name_char(c))
+}
+
+fn serialize_pattern<S: serde::Serializer>(pattern: &Pattern, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(pattern.as_str())
+}
+
+fn deserialize_pattern<'de, D: Deserializer<'de>>(d: D) -> Result<Pattern, D::Error> {
+    let str = String::deserialize(d)?;
+    // previously, skip_serializing_if = "Output::is_pattern_default" is not working well.
+    static TRADITIONAL_DEFAULT: &str = "output_log_%0Y-%0m-%0d_%0H-%0M-%0S.txt";
+    if str == TRADITIONAL_DEFAULT {
+        return Ok(Output::pattern_default());
+    }
+    Pattern::parse(&str)
+        .map_err(|e| D::Error::custom(format!("'{}' is invalid log file pattern: {}", str, e)))
+}
+
+fn serialize_optional_pattern<S: serde::Serializer>(
+    pattern: &Option<Pattern>,
+    s: S,
+) -> Result<S::Ok, S::Error> {
+    serialize_pattern(pattern.as_ref().unwrap(), s)
+}
+
+fn deserialize_optional_pattern<'de, D: Deserializer<'de>>(
+    d: D,
+) -> Result<Option<Pattern>, D::Error> {
+    deserialize_pattern(d).map(Some)
+}
+
+impl Output {
+    default_fns!(
+        folder: ConfigPath = ConfigPath::from_path(local_low_appdata_path()
+            .join("VRChat")
+            .join("VRChat")
+            .join("logs"));
+        |x| x.raw()
+    );
+    default_fns!(
+        pattern: Pattern = Pattern::new("output_log_%Y-%m-%d_%H-%M-%S{regex:in_sec_num}.txt");
+        |x| x.as_str()
+    );
+    default_fns!(utc_time: bool = false);
+    default_fns!(file_ctime: bool = false);
+    default_fns!(replacement: char = '_');
+    default_fns!(split_worlds: bool = false);
+    default_fns!(compress: bool = false);
+    default_fns!(checksums: bool = false);
+    default_fns!(copy_buffer_kib: u32 = 1024);
+    default_fns!(read_only: bool = false);
+    default_fns!(dedupe: Dedupe = Dedupe::Off);
+
+    pub fn folder(&self) -> &PathBuf {
+        self.folder.path()
+    }
+
+    pub fn folder_raw(&self) -> &str {
+        self.folder.raw()
+    }
+
+    pub fn pattern(&self) -> &[Item<'static>] {
+        self.pattern.items()
+    }
+
+    pub fn pattern_as_string(&self) -> String {
+        self.pattern.as_str().to_owned()
+    }
+
+    pub fn utc_time(&self) -> bool {
+        self.utc_time
+    }
+
+    pub fn time_zone(&self) -> Option<&TimeZone> {
+        self.time_zone.as_ref()
+    }
+
+    pub fn file_ctime(&self) -> bool {
+        self.file_ctime
+    }
+
+    pub fn split_worlds(&self) -> bool {
+        self.split_worlds
+    }
+
+    pub fn compress(&self) -> bool {
+        self.compress
+    }
+
+    pub fn checksums(&self) -> bool {
+        self.checksums
+    }
+
+    pub fn copy_buffer_size(&self) -> usize {
+        self.copy_buffer_kib.max(1) as usize * 1024
+    }
+
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    pub fn dedupe(&self) -> Dedupe {
+        self.dedupe
+    }
+
+    pub fn filter_lines(&self) -> &[Regex] {
+        &self.filter_lines
+    }
+
+    pub fn mirror_folders(&self) -> impl Iterator<Item = &PathBuf> {
+        self.mirror_folders.iter().map(ConfigPath::path)
+    }
+
+    /// replaces characters cannot be used in file name with `replacement`
+    /// and removes trailing dots and spaces, which are removed by windows
+    pub fn sanitize_file_name(&self, name: &str) -> String {
+        let sanitized = name
+            .chars()
+            .map(|c| {
+                if is_invalid_file_name_char(c) {
+                    self.replacement
+                } else {
+                    c
+                }
+            })
+            .collect::<String>();
+        sanitized.trim_end_matches(&['.', ' '][..]).to_owned()
+    }
+
+    /// sanitizes each folder and file name in the path separated by `\`
+    pub fn sanitize_path(&self, path: &str) -> String {
+        path.split('\\')
+            .map(|name| self.sanitize_file_name(name))
+            .collect::<Vec<_>>()
+            .join("\\")
+    }
+
+    pub fn new(folder: ConfigPath, pattern: Pattern, utc_time: bool, file_ctime: bool) -> Self {
+        Self {
+            folder,
+            pattern,
+            utc_time,
+            time_zone: None,
+            file_ctime,
+            replacement: Self::replacement_default(),
+            split_worlds: Self::split_worlds_default(),
+            compress: Self::compress_default(),
+            checksums: Self::checksums_default(),
+            copy_buffer_kib: Self::copy_buffer_kib_default(),
+            read_only: Self::read_only_default(),
+            dedupe: Self::dedupe_default(),
+            filter_lines: Vec::new(),
+            mirror_folders: Vec::new(),
+        }
+    }
+}
+
+impl Default for Output {
+    fn default() -> Self {
+        Self {
+            folder: Self::folder_default(),
+            pattern: Self::pattern_default(),
+            utc_time: Self::utc_time_default(),
+            time_zone: None,
+            file_ctime: Self::file_ctime_default(),
+            replacement: Self::replacement_default(),
+            split_worlds: Self::split_worlds_default(),
+            compress: Self::compress_default(),
+            checksums: Self::checksums_default(),
+            copy_buffer_kib: Self::copy_buffer_kib_default(),
+            read_only: Self::read_only_default(),
+            dedupe: Self::dedupe_default(),
+            filter_lines: Vec::new(),
+            mirror_folders: Vec::new(),
+        }
+    }
+}
+
+/// the time zone to format the output names in instead of the local time
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum TimeZone {
+    Fixed(FixedOffset),
+    /// the IANA time zone id. converted by Windows since the time zone database is not bundled
+    Iana(String),
+}
+
+impl TimeZone {
+    /// parses `+09:00`, `-0500` or the IANA id like `Asia/Tokyo`. None if empty
+    pub fn parse(value: &str) -> io::Result<Option<TimeZone>> {
+        let invalid = || {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "'{}' is not an offset like +09:00 or a time zone like Asia/Tokyo",
+                    value
+                ),
+            )
+        };
+        if value.is_empty() {
+            return Ok(None);
+        }
+        if let Some(offset) = value.strip_prefix(&['+', '-'][..]) {
+            let offset = offset.replacen(':', "", 1);
+            if offset.len() != 4 || !offset.bytes().all(|x| x.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            let hours = offset[..2].parse::<i32>().unwrap();
+            let minutes = offset[2..].parse::<i32>().unwrap();
+            if minutes >= 60 {
+                return Err(invalid());
+            }
+            let seconds = (hours * 60 + minutes) * 60;
+            let seconds = if value.starts_with('-') {
+                -seconds
+            } else {
+                seconds
+            };
+            return FixedOffset::east_opt(seconds)
+                .map(|x| Some(TimeZone::Fixed(x)))
+                .ok_or_else(invalid);
+        }
+        let valid_char = |c: char| c.is_ascii_alphanumeric() || matches!(c, '/' | '_' | '-' | '+');
+        if !value.chars().all(valid_char) {
+            return Err(invalid());
+        }
+        Ok(Some(TimeZone::Iana(value.to_owned())))
+    }
+}
+
+impl std::fmt::Display for TimeZone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeZone::Fixed(offset) => write!(f, "{}", offset),
+            TimeZone::Iana(id) => f.write_str(id),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Notify {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    discord: Option<DiscordNotify>,
+}
+
+impl Notify {
+    fn is_empty(&self) -> bool {
+        self.discord.is_none()
+    }
+
+    pub fn discord(&self) -> Option<&DiscordNotify> {
+        self.discord.as_ref()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DiscordNotify {
+    webhook_url: String,
+}
+
+impl DiscordNotify {
+    pub fn webhook_url(&self) -> &str {
+        &self.webhook_url
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Hooks {
+    /// the command run before renaming. renaming is aborted if this fails
+    #[serde(skip_serializing_if = "Option::is_none")]
+    before_run: Option<String>,
+    /// the command run for each copied/moved file. `{src}` and `{dst}` are replaced with paths
+    #[serde(skip_serializing_if = "Option::is_none")]
+    after_file: Option<String>,
+    /// the command run after renaming, even if renaming is failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    after_run: Option<String>,
+}
+
+impl Hooks {
+    fn is_empty(&self) -> bool {
+        self.before_run.is_none() && self.after_file.is_none() && self.after_run.is_none()
+    }
 
-    pub fn new(source: Source, output: Output) -> Self {
-        Self { source, output }
+    pub fn before_run(&self) -> Option<&str> {
+        self.before_run.as_deref()
     }
-}
 
-impl ConfigFile {
-    pub fn source(&self) -> &Source {
-        &self.source
+    pub fn after_file(&self) -> Option<&str> {
+        self.after_file.as_deref()
     }
-    pub fn output(&self) -> &Output {
-        &self.output
+
+    pub fn after_run(&self) -> Option<&str> {
+        self.after_run.as_deref()
     }
 }
 
-#[derive(Serialize, Debug, Clone)]
-pub struct Source {
-    #[serde(
-        skip_serializing_if = "Source::is_folder_default",
-        default = "Source::folder_default"
-    )]
-    folder: PathBuf,
-    #[serde(
-        skip_serializing_if = "Source::is_pattern_default",
-        default = "Source::pattern_default",
-        serialize_with = "serialize_regex"
-    )]
-    pattern: Regex,
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum UploadKind {
+    WebDav,
+    S3,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Upload {
+    kind: UploadKind,
+    /// the base url of WebDAV folder or the endpoint of S3-compatible storage
+    url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bucket: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    region: Option<String>,
+    /// the user name for WebDAV or access key id for S3
+    #[serde(skip_serializing_if = "Option::is_none")]
+    username: Option<String>,
+    /// the password for WebDAV or secret access key for S3
+    #[serde(skip_serializing_if = "Option::is_none")]
+    password: Option<String>,
+    /// the target name of generic credential in Windows Credential Manager.
+    /// used if username and password are not specified
+    #[serde(skip_serializing_if = "Option::is_none")]
+    credential: Option<String>,
     #[serde(
-        skip_serializing_if = "Source::is_keep_old_default",
-        default = "Source::keep_old_default"
+        skip_serializing_if = "Upload::is_retry_default",
+        default = "Upload::retry_default"
     )]
-    keep_old: bool,
+    retry: u32,
 }
 
-fn serialize_regex<S: serde::Serializer>(regex: &Regex, s: S) -> Result<S::Ok, S::Error> {
-    s.serialize_str(regex.as_str())
-}
+impl Upload {
+    default_fns!(retry: u32 = 3);
+    /// the waits are 1, 2, 4, ... seconds up to a minute so this waits about 7 minutes at most
+    const MAX_RETRY: u32 = 12;
 
-impl Source {
-    pub(crate) fn read_from_file(&mut self, toml: &Value) -> io::Result<()> {
-        if let Some(Value::String(str)) = toml.get("folder") {
-            self.folder = PathBuf::from(str)
+    fn validate(&self) -> io::Result<()> {
+        if self.url.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "url is required for upload",
+            ));
         }
-        if let Some(Value::String(str)) = toml.get("pattern") {
-            self.pattern = Regex::new(str).map_err(|e| Error::new(ErrorKind::InvalidData, e))?
+        if self.kind == UploadKind::S3 && self.bucket.is_none() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "bucket is required for s3 upload",
+            ));
         }
-        if let Some(Value::Boolean(bool)) = toml.get("keep_old") {
-            self.keep_old = *bool;
+        self.validate_retry(ErrorKind::InvalidData)
+    }
+
+    fn validate_retry(&self, kind: ErrorKind) -> io::Result<()> {
+        if self.retry > Self::MAX_RETRY {
+            return Err(Error::new(
+                kind,
+                format!("upload.retry must be at most {}", Self::MAX_RETRY),
+            ));
         }
         Ok(())
     }
 
-    default_fns!(folder: PathBuf = local_low_appdata_path().join("VRChat").join("VRChat"));
-    default_fns!(pattern: Regex = Regex::new(r#"^output_log_(?:\d{4}-\d{2}-\d{2}_)?\d{2}-\d{2}-\d{2}(?P<in_sec_num>\d+)?\.txt$"#).unwrap(); |x| x.as_str());
-    default_fns!(keep_old: bool = true);
+    pub fn kind(&self) -> UploadKind {
+        self.kind
+    }
 
-    pub fn folder(&self) -> &PathBuf {
-        &self.folder
+    pub fn url(&self) -> &str {
+        &self.url
     }
-    pub fn pattern(&self) -> &Regex {
-        &self.pattern
+
+    pub fn bucket(&self) -> Option<&str> {
+        self.bucket.as_deref()
     }
-    pub fn keep_old(&self) -> bool {
-        self.keep_old
+
+    pub fn region(&self) -> Option<&str> {
+        self.region.as_deref()
+    }
+
+    pub fn username(&self) -> Option<&str> {
+        self.username.as_deref()
     }
 
-    pub fn new(folder: PathBuf, pattern: Regex, keep_old: bool) -> Self {
+    pub fn password(&self) -> Option<&str> {
+        self.password.as_deref()
+    }
+
+    pub fn credential(&self) -> Option<&str> {
+        self.credential.as_deref()
+    }
+
+    pub fn retry(&self) -> u32 {
+        self.retry
+    }
+}
+
+impl Default for Upload {
+    fn default() -> Self {
         Self {
-            folder,
-            pattern,
-            keep_old,
+            kind: UploadKind::WebDav,
+            url: String::new(),
+            bucket: None,
+            region: None,
+            username: None,
+            password: None,
+            credential: None,
+            retry: Self::retry_default(),
         }
     }
 }
 
-impl Default for Source {
+impl Default for ConfigFile {
     fn default() -> Self {
-        Source {
-            folder: Self::folder_default(),
-            pattern: Self::pattern_default(),
-            keep_old: Self::keep_old_default(),
+        Self {
+            unknown_keys: Self::unknown_keys_default(),
+            check_updates: Self::check_updates_default(),
+            low_priority: Self::low_priority_default(),
+            incremental: Self::incremental_default(),
+            theme: Self::theme_default(),
+            recent_folders: Vec::new(),
+            source: Default::default(),
+            sources: Vec::new(),
+            output: Default::default(),
+            notify: Default::default(),
+            hooks: Default::default(),
+            upload: None,
+            pictures: None,
+            crashes: None,
+            schedule: Default::default(),
         }
     }
 }
 
-#[derive(Serialize, Debug, Clone)]
-pub struct Output {
+/// the schedule of the task registered to the task scheduler
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Schedule {
+    /// the time of day to run the task at in `HH:MM`
     #[serde(
-        skip_serializing_if = "Output::is_folder_default",
-        default = "Output::folder_default"
+        skip_serializing_if = "Schedule::is_time_default",
+        default = "Schedule::time_default",
+        serialize_with = "serialize_time",
+        deserialize_with = "deserialize_time"
     )]
-    folder: PathBuf,
+    time: NaiveTime,
+    /// runs the task every this number of days
     #[serde(
-        skip_serializing_if = "Output::is_pattern_default",
-        default = "Output::pattern_default",
-        serialize_with = "serialize_pattern"
+        skip_serializing_if = "Schedule::is_interval_days_default",
+        default = "Schedule::interval_days_default",
+        deserialize_with = "deserialize_interval_days"
     )]
-    pattern: Vec<Item<'static>>,
+    interval_days: u16,
+    /// the path of the task in the task scheduler. folders are created if not exists
     #[serde(
-        skip_serializing_if = "Output::is_utc_time_default",
-        default = "Output::utc_time_default"
+        skip_serializing_if = "Schedule::is_task_path_default",
+        default = "Schedule::task_path_default",
+        deserialize_with = "deserialize_task_path"
     )]
-    utc_time: bool,
+    task_path: String,
+    /// the triggers to run the task
     #[serde(
-        skip_serializing_if = "Output::is_file_ctime_default",
-        default = "Output::file_ctime_default"
+        skip_serializing_if = "Schedule::is_triggers_default",
+        default = "Schedule::triggers_default"
     )]
-    file_ctime: bool,
+    triggers: Vec<Trigger>,
+    /// wakes the computer from sleep to run the task
+    #[serde(
+        skip_serializing_if = "Schedule::is_wake_to_run_default",
+        default = "Schedule::wake_to_run_default"
+    )]
+    wake_to_run: bool,
+    /// runs the task as soon as possible if the scheduled run is missed
+    #[serde(
+        skip_serializing_if = "Schedule::is_start_when_available_default",
+        default = "Schedule::start_when_available_default"
+    )]
+    start_when_available: bool,
+    /// how to run the renamer periodically
+    #[serde(
+        skip_serializing_if = "Schedule::is_installer_default",
+        default = "Schedule::installer_default"
+    )]
+    installer: Installer,
 }
 
-fn format_internal_format(fixed: &chrono::format::InternalFixed) -> Option<&'static str> {
-    use chrono::format::InternalFixed;
-    use once_cell::race::OnceBox;
-    type MappingType = [(InternalFixed, &'static str); 3];
-    static MAPPING: OnceBox<MappingType> = OnceBox::new();
-    fn init_mapping() -> Box<MappingType> {
-        fn fixed_internal(format: &str) -> (InternalFixed, &str) {
-            match StrftimeItems::new(format).next().unwrap() {
-                Item::Fixed(Fixed::Internal(fixed)) => (fixed, format),
-                _ => unreachable!("fixed_internal init failed"),
-            }
+impl Schedule {
+    default_fns!(time: NaiveTime = NaiveTime::from_hms_opt(0, 0, 0).unwrap());
+    default_fns!(interval_days: u16 = 1);
+    default_fns!(task_path: String = r"\anatawa12\VRC Log Renamer".to_owned());
+    default_fns!(triggers: Vec<Trigger> = vec![Trigger::Daily]);
+    default_fns!(wake_to_run: bool = false);
+    default_fns!(start_when_available: bool = false);
+    default_fns!(installer: Installer = Installer::TaskScheduler);
+
+    pub fn new(
+        time: NaiveTime,
+        interval_days: u16,
+        wake_to_run: bool,
+        start_when_available: bool,
+        installer: Installer,
+    ) -> Self {
+        Self {
+            time,
+            interval_days,
+            task_path: Self::task_path_default(),
+            triggers: Self::triggers_default(),
+            wake_to_run,
+            start_when_available,
+            installer,
         }
-        Box::new([
-            fixed_internal("%3f"),
-            fixed_internal("%6f"),
-            fixed_internal("%9f"),
-        ])
     }
-    MAPPING.get_or_init(init_mapping)
-        .iter()
-        .find(|(pat, _)| pat == fixed)
-        .map(|(_, a)| *a)
-}
-
-fn pattern_to_string(pattern: &Vec<Item<'static>>) -> Result<String, &'static str> {
-    let mut string = String::new();
-    for x in pattern {
-        match x {
-            Item::Literal(s) => string.push_str(s),
-            Item::OwnedLiteral(s) => string.push_str(s),
-            Item::Space(s) => string.push_str(s),
-            Item::OwnedSpace(s) => string.push_str(s),
-            Item::Numeric(n, p) => {
-                string.push('%');
-                match p {
-                    Pad::None => string.push('-'),
-                    Pad::Zero => string.push('0'),
-                    Pad::Space => string.push('_'),
-                }
-                // see https://docs.rs/chrono/latest/chrono/format/strftime/index.html
-                match n {
-                    // DATE SPECIFIERS:
-                    Numeric::Year => string.push('Y'),
-                    Numeric::YearDiv100 => string.push('C'),
-                    Numeric::YearMod100 => string.push('y'),
-
-                    Numeric::Month => string.push('m'),
-                    // month name 3 letters: b = h
-                    // month name N letters: B
-                    Numeric::Day => string.push('d'),
-                    // %e = %_d
-
-                    // weekday name 3 letters: a
-                    // weekday name N letters: A
-                    Numeric::WeekFromSun => string.push('w'),
-                    Numeric::WeekFromMon => string.push('u'),
-
-                    Numeric::NumDaysFromSun => string.push('U'),
-                    Numeric::WeekdayFromMon => string.push('W'),
-
-                    Numeric::IsoYear => string.push('G'),
-                    Numeric::IsoYearMod100 => string.push('g'),
-                    Numeric::IsoWeek => string.push('V'),
-
-                    Numeric::Ordinal => string.push('j'),
-
-                    Numeric::IsoYearDiv100 => string.push('g'), // unknown
-
-                    // time specifiers
-                    Numeric::Hour => string.push('H'),
-                    // %k = %_H
-                    Numeric::Hour12 => string.push('I'),
-                    // %l = %_I
-
-                    // am/pm: P
-                    // AM/PM: p
-                    Numeric::Minute => string.push('M'),
-                    Numeric::Second => string.push('S'),
-                    Numeric::Nanosecond => string.push('f'),
-                    // %.{3,6,9,}f and %{3,6,9}f
-
-                    // TIME ZONE SPECIFIERS and DATE & TIME SPECIFIERS
-                    Numeric::Timestamp => string.push('s'),
-
-                    Numeric::Internal(_) => return Err("internal format found"),
-                }
-            }
-            Item::Fixed(f) => match f {
-                Fixed::ShortMonthName => string.push_str("%b"),
-                Fixed::LongMonthName => string.push_str("%B"),
-                Fixed::ShortWeekdayName => string.push_str("%a"),
-                Fixed::LongWeekdayName => string.push_str("%A"),
-                Fixed::LowerAmPm => string.push_str("%P"),
-                Fixed::UpperAmPm => string.push_str("%p"),
-                Fixed::Nanosecond => string.push_str("%.f"),
-                Fixed::Nanosecond3 => string.push_str("%.3f"),
-                Fixed::Nanosecond6 => string.push_str("%.6f"),
-                Fixed::Nanosecond9 => string.push_str("%.9f"),
-                Fixed::TimezoneName => string.push_str("%Z"),
-                Fixed::TimezoneOffsetColon => string.push_str("%:z"),
-                Fixed::TimezoneOffset => string.push_str("%z"),
-                Fixed::RFC2822 => string.push_str("%c"),
-                Fixed::RFC3339 => string.push_str("%+"),
-                Fixed::TimezoneOffsetColonZ => return Err("internal format found"),
-                Fixed::TimezoneOffsetZ => return Err("internal format found"),
-                Fixed::Internal(format_in) => {
-                    string.push_str(format_internal_format(format_in).ok_or("internal format found")?);
-                }
-            },
-            Item::Error => return Err("format error found"),
+
+    fn is_empty(&self) -> bool {
+        Self::is_time_default(&self.time)
+            && Self::is_interval_days_default(&self.interval_days)
+            && Self::is_task_path_default(&self.task_path)
+            && Self::is_triggers_default(&self.triggers)
+            && Self::is_wake_to_run_default(&self.wake_to_run)
+            && Self::is_start_when_available_default(&self.start_when_available)
+            && Self::is_installer_default(&self.installer)
+    }
+
+    pub fn time(&self) -> NaiveTime {
+        self.time
+    }
+    pub fn time_as_string(&self) -> String {
+        self.time.format(TIME_FORMAT).to_string()
+    }
+    pub fn interval_days(&self) -> u16 {
+        self.interval_days
+    }
+    pub fn task_path(&self) -> &str {
+        &self.task_path
+    }
+    pub fn triggers(&self) -> &[Trigger] {
+        &self.triggers
+    }
+    pub fn wake_to_run(&self) -> bool {
+        self.wake_to_run
+    }
+    pub fn start_when_available(&self) -> bool {
+        self.start_when_available
+    }
+    pub fn installer(&self) -> Installer {
+        self.installer
+    }
+
+    pub fn parse_time(value: &str) -> io::Result<NaiveTime> {
+        NaiveTime::parse_from_str(value, TIME_FORMAT).map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "schedule.time must be in HH:MM format",
+            )
+        })
+    }
+
+    pub fn parse_interval_days(value: &str) -> io::Result<u16> {
+        value.parse().ok().filter(|&x| x != 0).ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                "schedule.interval_days must be a positive integer",
+            )
+        })
+    }
+
+    pub fn parse_task_path(value: &str) -> io::Result<String> {
+        let valid = value.starts_with('\\')
+            && value[1..]
+                .split('\\')
+                .all(|x| !x.is_empty() && x.trim() == x);
+        if !valid {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                r"schedule.task_path must be like '\folder\task name'",
+            ));
         }
+        Ok(value.to_owned())
     }
-    return Ok(string);
 }
 
-fn serialize_pattern<S: serde::Serializer>(
-    pattern: &Vec<Item<'static>>,
-    s: S,
-) -> Result<S::Ok, S::Error> {
-    s.serialize_str(&pattern_to_string(pattern).map_err(S::Error::custom)?)
+impl Default for Schedule {
+    fn default() -> Self {
+        Self {
+            time: Self::time_default(),
+            interval_days: Self::interval_days_default(),
+            task_path: Self::task_path_default(),
+            triggers: Self::triggers_default(),
+            wake_to_run: Self::wake_to_run_default(),
+            start_when_available: Self::start_when_available_default(),
+            installer: Self::installer_default(),
+        }
+    }
 }
 
-pub fn parse_pattern(str: &str) -> Option<Vec<Item<'static>>> {
-    fn own_strftime(item: Item) -> Item<'static> {
-        match item {
-            Item::Literal(s) => Item::OwnedLiteral(s.to_string().into_boxed_str()),
-            Item::Space(s) => Item::OwnedSpace(s.to_string().into_boxed_str()),
-            Item::OwnedLiteral(s) => Item::OwnedLiteral(s),
-            Item::OwnedSpace(s) => Item::OwnedSpace(s),
-            Item::Numeric(n, p) => {
-                if matches!(n, Numeric::Internal(_)) {
-                    // internal format is not allowed
-                    Item::Error
-                } else {
-                    Item::Numeric(n, p)
-                }
-            }
-            Item::Fixed(f) => {
-                match f {
-                    Fixed::Internal(internal) => {
-                        if format_internal_format(&internal).is_some() {
-                            Item::Fixed(Fixed::Internal(internal))
-                        } else {
-                            Item::Error
-                        }
-                    }
-                    Fixed::TimezoneOffset | Fixed::TimezoneOffsetZ => Item::Error,
-                    f => Item::Fixed(f)
-                }
-            }
-            Item::Error => Item::Error,
+/// how to run the renamer periodically
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Installer {
+    /// registers the task to the task scheduler
+    TaskScheduler,
+    /// runs at logon with the `Run` key in the registry of current user.
+    /// for computers task scheduler is restricted by the policy
+    RunKey,
+}
+
+impl Installer {
+    fn parse(str: &str) -> io::Result<Self> {
+        match str {
+            "task_scheduler" => Ok(Self::TaskScheduler),
+            "run_key" => Ok(Self::RunKey),
+            _ => Err(Error::new(
+                ErrorKind::InvalidInput,
+                "schedule.installer must be 'task_scheduler' or 'run_key'",
+            )),
         }
     }
-    let pattern: Vec<Item> = StrftimeItems::new(str).map(own_strftime).collect();
-    if pattern.iter().any(|x| matches!(x, Item::Error)) {
-        return None;
-    }
-    Some(pattern)
 }
 
-impl Output {
-    default_fns!(
-        folder: PathBuf = local_low_appdata_path()
-            .join("VRChat")
-            .join("VRChat")
-            .join("logs")
-    );
-    default_fns!(
-        pattern: Vec<Item<'static>> = StrftimeItems::new("output_log_%Y-%m-%d_%H-%M-%S{regex:in_sec_num}.txt").collect::<Vec<_>>();
-        |x| pattern_to_string(x)
-    );
-    default_fns!(utc_time: bool = false);
-    default_fns!(file_ctime: bool = false);
+/// the trigger of the scheduled task. written as `"daily"`, `"logon"` or `"hours:N"` in config
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(try_from = "String", into = "String")]
+pub enum Trigger {
+    /// runs at `schedule.time` every `schedule.interval_days` days
+    Daily,
+    /// runs when the user logs on
+    Logon,
+    /// runs every N hours from `schedule.time`. N must divide 24
+    Hours(u8),
+}
 
-    pub(crate) fn read_from_file(&mut self, toml: &Value) -> io::Result<()> {
-        if let Some(Value::String(str)) = toml.get("folder") {
-            self.folder = PathBuf::from(str)
-        }
-        if let Some(Value::String(str)) = toml.get("pattern") {
-            // previously, skip_serializing_if = "Output::is_pattern_default" is not working well.
-            static TRADITIONAL_DEFAULT: &str = "output_log_%0Y-%0m-%0d_%0H-%0M-%0S.txt";
-            if str != TRADITIONAL_DEFAULT {
-                self.pattern = parse_pattern(&str).ok_or_else(|| {
-                    Error::new(
-                        ErrorKind::InvalidData,
-                        format!("'{}' is invalid log file pattern", str),
-                    )
-                })?;
-            }
+impl Trigger {
+    fn parse(str: &str) -> io::Result<Self> {
+        match str {
+            "daily" => return Ok(Self::Daily),
+            "logon" => return Ok(Self::Logon),
+            _ => {}
         }
-        if let Some(Value::Boolean(bool)) = toml.get("utc_time") {
-            self.utc_time = *bool;
-        }
-        if let Some(Value::Boolean(bool)) = toml.get("file_ctime") {
-            self.file_ctime = *bool;
-        }
-        Ok(())
+        str.strip_prefix("hours:")
+            .and_then(|x| x.parse::<u8>().ok())
+            .filter(|&x| x != 0 && 24 % x == 0)
+            .map(Self::Hours)
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "'{}' is invalid trigger. must be 'daily', 'logon' or 'hours:N' where N divides 24",
+                        str
+                    ),
+                )
+            })
     }
+}
 
-    pub fn folder(&self) -> &PathBuf {
-        &self.folder
+impl std::fmt::Display for Trigger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Trigger::Daily => f.write_str("daily"),
+            Trigger::Logon => f.write_str("logon"),
+            Trigger::Hours(hours) => write!(f, "hours:{}", hours),
+        }
     }
+}
 
-    pub fn pattern(&self) -> &Vec<Item<'static>> {
-        &self.pattern
-    }
+impl TryFrom<String> for Trigger {
+    type Error = io::Error;
 
-    pub fn pattern_as_string(&self) -> String {
-        pattern_to_string(&self.pattern).unwrap()
+    fn try_from(value: String) -> io::Result<Self> {
+        Self::parse(&value)
     }
+}
 
-    pub fn utc_time(&self) -> bool {
-        self.utc_time
+impl From<Trigger> for String {
+    fn from(value: Trigger) -> Self {
+        value.to_string()
     }
+}
 
-    pub fn file_ctime(&self) -> bool {
-        self.file_ctime
+const TIME_FORMAT: &str = "%H:%M";
+
+fn serialize_time<S: serde::Serializer>(time: &NaiveTime, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(&time.format(TIME_FORMAT).to_string())
+}
+
+fn deserialize_time<'de, D: Deserializer<'de>>(d: D) -> Result<NaiveTime, D::Error> {
+    Schedule::parse_time(&String::deserialize(d)?).map_err(D::Error::custom)
+}
+
+fn deserialize_task_path<'de, D: Deserializer<'de>>(d: D) -> Result<String, D::Error> {
+    Schedule::parse_task_path(&String::deserialize(d)?).map_err(D::Error::custom)
+}
+
+fn deserialize_interval_days<'de, D: Deserializer<'de>>(d: D) -> Result<u16, D::Error> {
+    match u16::deserialize(d)? {
+        0 => Err(D::Error::custom("schedule.interval_days must be a positive integer")),
+        days => Ok(days),
     }
+}
 
-    pub fn new(
-        folder: PathBuf,
-        pattern: Vec<Item<'static>>,
-        utc_time: bool,
-        file_ctime: bool,
-    ) -> Self {
-        Self {
-            folder,
-            pattern,
-            utc_time,
-            file_ctime,
-        }
+/// the config to collect crash artifacts. crashes are not collected if this section does not exist
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Crashes {
+    /// the folder VRChat crash handler writes crash folders with `crash.dmp` and `error.log`
+    #[serde(
+        skip_serializing_if = "Crashes::is_folder_default",
+        default = "Crashes::folder_default"
+    )]
+    folder: ConfigPath,
+}
+
+impl Crashes {
+    default_fns!(folder: ConfigPath = ConfigPath::new(r"%TEMP%\VRChat\VRChat\Crashes".to_owned()).unwrap(); |x| x.raw());
+
+    pub fn folder(&self) -> &PathBuf {
+        self.folder.path()
     }
 }
 
-impl Default for Output {
+impl Default for Crashes {
     fn default() -> Self {
         Self {
             folder: Self::folder_default(),
-            pattern: Self::pattern_default(),
-            utc_time: Self::utc_time_default(),
-            file_ctime: Self::file_ctime_default(),
         }
     }
 }
 
-impl Default for ConfigFile {
+/// the config to rename VRChat pictures. pictures are not renamed if this section does not exist
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Pictures {
+    #[serde(
+        skip_serializing_if = "Pictures::is_folder_default",
+        default = "Pictures::folder_default"
+    )]
+    folder: ConfigPath,
+    #[serde(
+        skip_serializing_if = "Pictures::is_pattern_default",
+        default = "Pictures::pattern_default",
+        serialize_with = "serialize_regex",
+        deserialize_with = "deserialize_regex"
+    )]
+    pattern: Regex,
+    #[serde(
+        skip_serializing_if = "Pictures::is_keep_old_default",
+        default = "Pictures::keep_old_default"
+    )]
+    keep_old: bool,
+    #[serde(
+        skip_serializing_if = "Pictures::is_output_folder_default",
+        default = "Pictures::output_folder_default"
+    )]
+    output_folder: ConfigPath,
+    #[serde(
+        skip_serializing_if = "Pictures::is_output_pattern_default",
+        default = "Pictures::output_pattern_default",
+        serialize_with = "serialize_pattern",
+        deserialize_with = "deserialize_pattern"
+    )]
+    output_pattern: Pattern,
+}
+
+impl Pictures {
+    default_fns!(folder: ConfigPath = ConfigPath::new(r"{Pictures}\VRChat".to_owned()).unwrap(); |x| x.raw());
+    default_fns!(pattern: Regex = Regex::new(r#"^VRChat_(?P<time>\d{4}-\d{2}-\d{2}_\d{2}-\d{2}-\d{2}\.\d{3})_(?P<width>\d+)x(?P<height>\d+)\.png$"#).unwrap(); |x| x.as_str());
+    default_fns!(keep_old: bool = true);
+    default_fns!(output_folder: ConfigPath = ConfigPath::new(r"{Pictures}\VRChat\renamed".to_owned()).unwrap(); |x| x.raw());
+    default_fns!(
+        output_pattern: Pattern = Pattern::new("%Y-%m-%d_%H-%M-%S%.3f_{world}_{regex:width}x{regex:height}.png");
+        |x| x.as_str()
+    );
+
+    pub fn folder(&self) -> &PathBuf {
+        self.folder.path()
+    }
+
+    pub fn folder_raw(&self) -> &str {
+        self.folder.raw()
+    }
+
+    /// the pattern of picture file name. the capture named `time` is used as the time taken
+    pub fn pattern(&self) -> &Regex {
+        &self.pattern
+    }
+
+    pub fn keep_old(&self) -> bool {
+        self.keep_old
+    }
+
+    pub fn output_folder(&self) -> &PathBuf {
+        self.output_folder.path()
+    }
+
+    pub fn output_folder_raw(&self) -> &str {
+        self.output_folder.raw()
+    }
+
+    pub fn output_pattern(&self) -> &[Item<'static>] {
+        self.output_pattern.items()
+    }
+}
+
+impl Default for Pictures {
     fn default() -> Self {
         Self {
-            source: Default::default(),
-            output: Default::default(),
+            folder: Self::folder_default(),
+            pattern: Self::pattern_default(),
+            keep_old: Self::keep_old_default(),
+            output_folder: Self::output_folder_default(),
+            output_pattern: Self::output_pattern_default(),
         }
     }
 }
 
 pub fn read_config() -> io::Result<ConfigFile> {
-    let mut config = ConfigFile::default();
     match fs::read_to_string(config_file_path()) {
-        Ok(toml) => config.read_from_file(&toml::from_str::<Value>(&toml)?)?,
-        Err(ref e) if e.kind() == ErrorKind::NotFound => {}
-        Err(e) => return Err(e),
+        Ok(toml) => parse_config(&toml),
+        Err(ref e) if e.kind() == ErrorKind::NotFound => Ok(Default::default()),
+        Err(e) => Err(e),
     }
+}
 
+fn parse_config(toml: &str) -> io::Result<ConfigFile> {
+    let toml = toml::from_str::<Value>(toml)?;
+    let unknown_keys = match toml.get("unknown_keys") {
+        Some(Value::String(str)) => UnknownKeys::parse(str)?,
+        _ => ConfigFile::unknown_keys_default(),
+    };
+    check_unknown_keys(&toml, unknown_keys)?;
+    let config: ConfigFile = toml
+        .try_into()
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    config.validate()?;
     Ok(config)
 }
 
@@ -447,3 +2468,243 @@ pub fn save_config(config: &ConfigFile) -> io::Result<()> {
         toml::to_string(config).map_err(|e| Error::new(ErrorKind::InvalidData, e))?,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// saves and loads the config and checks the saved text is stable
+    fn round_trip(config: &ConfigFile) -> ConfigFile {
+        let toml = toml::to_string(config).unwrap();
+        let read = parse_config(&toml).unwrap();
+        assert_eq!(toml, toml::to_string(&read).unwrap());
+        read
+    }
+
+    #[test]
+    fn time_zone() {
+        let offset = |seconds| Some(TimeZone::Fixed(FixedOffset::east_opt(seconds).unwrap()));
+        assert_eq!(TimeZone::parse("+09:00").unwrap(), offset(9 * 3600));
+        assert_eq!(TimeZone::parse("-0530").unwrap(), offset(-(5 * 3600 + 30 * 60)));
+        assert_eq!(
+            TimeZone::parse("Asia/Tokyo").unwrap(),
+            Some(TimeZone::Iana("Asia/Tokyo".to_owned()))
+        );
+        assert_eq!(TimeZone::parse("").unwrap(), None);
+        assert!(TimeZone::parse("+9").is_err());
+        assert!(TimeZone::parse("+09:60").is_err());
+        assert!(TimeZone::parse("Asia Tokyo").is_err());
+    }
+
+    #[test]
+    fn default_round_trip() {
+        round_trip(&ConfigFile::default());
+    }
+
+    #[test]
+    fn customized_round_trip() {
+        let mut config = ConfigFile::default();
+        for (key, value) in [
+            ("unknown_keys", "warn"),
+            ("check_updates", "true"),
+            ("low_priority", "true"),
+            ("incremental", "false"),
+            ("theme", "dark"),
+            ("recent_folders", r#"['D:\VRChat', 'Z:\logs']"#),
+            ("source.folder", r"D:\VRChat"),
+            ("source.pattern", r"^output_log_.*\.txt$"),
+            ("source.exclude_pattern", r"^output_log_00-00-00\.txt$"),
+            ("source.keep_old", "false"),
+            ("source.hard_link", "true"),
+            ("source.leave_link", "true"),
+            ("source.capture_live", "true"),
+            ("source.recursive", "true"),
+            ("source.since", "2020-01-01"),
+            ("source.until", "2022-12-31"),
+            ("source.header_format", "%Y-%m-%d %H:%M:%S"),
+            ("source.launch_time", "['header', 'file_name', 'mtime']"),
+            ("source.in_use", "mtime"),
+            ("source.locked_retry", "3"),
+            ("source.locked_retry_delay_ms", "500"),
+            ("source.unparsable", "quarantine"),
+            ("source.output.folder", r"E:\vrchat"),
+            ("source.output.pattern", "%F_%e-%k-%M-%S.txt"),
+            ("output.folder", r"E:\logs"),
+            ("output.pattern", "vrchat_%0Y-%m-%_d_%H-%M-%S{regex:in_sec_num}.txt"),
+            ("output.utc_time", "true"),
+            ("output.time_zone", "+09:00"),
+            ("output.file_ctime", "true"),
+            ("output.replacement", "-"),
+            ("output.split_worlds", "true"),
+            ("output.compress", "true"),
+            ("output.checksums", "true"),
+            ("output.copy_buffer_kib", "8192"),
+            ("output.read_only", "true"),
+            ("output.dedupe", "link"),
+            ("output.filter_lines", r#"['^\s*$', 'Shader']"#),
+            ("output.mirror_folders", r#"['D:\OneDrive\logs']"#),
+            ("notify.discord.webhook_url", "https://discord.com/api/webhooks/0/token"),
+            ("hooks.before_run", r"net use Z: \\nas\logs"),
+            ("hooks.after_file", "gzip {dst}"),
+            ("hooks.after_run", "net use Z: /delete"),
+            ("upload.url", "https://s3.example.com"),
+            ("upload.bucket", "logs"),
+            ("upload.kind", "s3"),
+            ("upload.retry", "5"),
+            ("pictures.keep_old", "false"),
+            ("crashes.folder", r"%TEMP%\Crashes"),
+            ("schedule.time", "04:30"),
+            ("schedule.interval_days", "2"),
+            ("schedule.task_path", r"\VRC Log Renamer"),
+            ("schedule.triggers", r#"["hours:4", "logon"]"#),
+            ("schedule.wake_to_run", "true"),
+            ("schedule.start_when_available", "true"),
+            ("schedule.installer", "run_key"),
+            ("pictures.output_pattern", "%Y-%m-%d_%H-%M-%S%.3f_{world}.png"),
+        ] {
+            config.set_value(key, value).unwrap();
+        }
+        let read = round_trip(&config);
+        for key in CONFIG_KEYS {
+            assert_eq!(
+                config.get_value(key).unwrap(),
+                read.get_value(key).unwrap(),
+                "{}",
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn traditional_default_pattern() {
+        let config =
+            parse_config("[output]\npattern = \"output_log_%0Y-%0m-%0d_%0H-%0M-%0S.txt\"\n")
+                .unwrap();
+        assert_eq!(
+            config.output().pattern_as_string(),
+            ConfigFile::default().output().pattern_as_string()
+        );
+    }
+
+    #[test]
+    fn unknown_keys() {
+        let error = parse_config("[source]\nkeep_original = false\n").unwrap_err();
+        assert!(error.to_string().contains("did you mean 'keep_old'?"));
+
+        let config = parse_config("unknown_keys = \"warn\"\n[source]\nkeep_original = false\n");
+        assert!(config.unwrap().source().keep_old());
+    }
+
+    #[test]
+    fn per_source_output() {
+        let config = parse_config(concat!(
+            "[output]\n",
+            "folder = 'E:\\logs'\n",
+            "[[sources]]\n",
+            "folder = 'D:\\ChilloutVR'\n",
+            "pattern = '^log\\.txt$'\n",
+            "[sources.output]\n",
+            "folder = 'E:\\cvr'\n",
+        ))
+        .unwrap();
+        let sources = config.sources().collect::<Vec<_>>();
+        assert_eq!(sources.len(), 2);
+        let output = config.output_for(sources[0]);
+        assert_eq!(output.folder(), &PathBuf::from(r"E:\logs"));
+        let output = config.output_for(sources[1]);
+        assert_eq!(output.folder(), &PathBuf::from(r"E:\cvr"));
+        assert_eq!(output.pattern_as_string(), config.output().pattern_as_string());
+
+        let error = parse_config("[[sources]]\nkeep_original = false\n").unwrap_err();
+        assert!(error.to_string().contains("sources[0].keep_original"));
+    }
+
+    #[test]
+    fn file_name_sanitization() {
+        let output = Output::default();
+        assert_eq!(output.sanitize_file_name("a:b?c*.txt"), "a_b_c_.txt");
+        assert_eq!(output.sanitize_file_name("log. . "), "log");
+        assert_eq!(output.sanitize_path(r"2022\10\a:b.txt"), r"2022\10\a_b.txt");
+
+        let mut config = ConfigFile::default();
+        assert!(config.set_value("output.pattern", "%H:%M.txt").is_err());
+        assert!(config
+            .set_value("output.pattern", "%H-%M{regex:in_sec_num}.txt")
+            .is_ok());
+        assert!(config
+            .set_value("output.pattern", r"%Y\%m\%H-%M_{world}.txt")
+            .is_ok());
+        config.set_value("output.replacement", "?").unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn recent_folders() {
+        let mut config = ConfigFile::default();
+        config.add_recent_folders([r"D:\VRChat", r"E:\logs"]);
+        config.add_recent_folders([r"Z:\logs", "", r"d:\vrchat"]);
+        assert_eq!(config.recent_folders(), [r"d:\vrchat", r"Z:\logs", r"E:\logs"]);
+
+        config.add_recent_folders((0..10).map(|_| r"F:\logs"));
+        config.add_recent_folders(["1", "2", "3", "4", "5", "6", "7"]);
+        assert_eq!(config.recent_folders().len(), MAX_RECENT_FOLDERS);
+        assert_eq!(config.recent_folders().last().unwrap(), r"F:\logs");
+    }
+
+    #[test]
+    fn exclude_pattern() {
+        let mut config = ConfigFile::default();
+        config
+            .set_value("source.exclude_pattern", r"^output_log_00-.*\.txt$")
+            .unwrap();
+        assert!(config.source().is_excluded("output_log_00-12-34.txt"));
+        assert!(!config.source().is_excluded("output_log_12-34-56.txt"));
+
+        config.set_value("source.exclude_pattern", "").unwrap();
+        assert!(config.source().exclude_pattern().is_none());
+        assert!(config.set_value("source.exclude_pattern", "(").is_err());
+    }
+
+    #[test]
+    fn date_range() {
+        let date = |value| NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap();
+        let mut config = ConfigFile::default();
+        config.set_value("source.since", "2022-01-01").unwrap();
+        assert!(!config.source().is_in_date_range(date("2021-12-31")));
+        assert!(config.source().is_in_date_range(date("2022-01-01")));
+
+        config.set_date_range(None, Some(date("2022-06-30")));
+        assert!(config.source().is_in_date_range(date("2022-06-30")));
+        assert!(!config.source().is_in_date_range(date("2022-07-01")));
+        assert_eq!(
+            config.get_value("source.since").unwrap(),
+            Some(Value::String("2022-01-01".to_owned()))
+        );
+        assert!(config.set_value("source.until", "2022/06/30").is_err());
+        assert!(config
+            .set_value("source.header_format", "%Y.%m.%d %Q")
+            .is_err());
+    }
+
+    #[test]
+    fn upload_retry_limit() {
+        let mut config = ConfigFile::default();
+        config.set_value("upload.retry", "12").unwrap();
+        assert!(config.set_value("upload.retry", "64").is_err());
+        let toml = "[upload]\nkind = 'webdav'\nurl = 'https://dav.example.com'\nretry = ";
+        parse_config(&format!("{}12\n", toml)).unwrap();
+        let error = parse_config(&format!("{}100\n", toml)).unwrap_err();
+        assert!(error.to_string().contains("retry"), "{}", error);
+    }
+
+    #[test]
+    fn locked_retry_limit() {
+        let mut config = ConfigFile::default();
+        config.set_value("source.locked_retry", "10").unwrap();
+        assert!(config.set_value("source.locked_retry", "32").is_err());
+        assert!(config
+            .set_value("source.locked_retry_delay_ms", "86400000")
+            .is_err());
+        assert!(parse_config("[[sources]]\nlocked_retry = 100\n").is_err());
+    }
+}
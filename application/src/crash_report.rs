@@ -0,0 +1,85 @@
+// VRC Log Renamer - the tool to rename logs of VRChat to have date info
+// Copyright (C) 2022 anatawa12
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Writes the report of panics to a file instead of disappearing silently as windows subsystem app.
+
+use crate::i18n::Message::*;
+use crate::{local_low_appdata_path, BUILD_TARGET, GIT_HASH};
+use chrono::Local;
+use std::backtrace::Backtrace;
+use std::fmt::Display;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use winsafe::co::{DLGID, MB};
+use winsafe::prelude::*;
+use winsafe::HWND;
+
+/// true if the dialog can be shown. i18n must be initialized for dialogs
+static SHOW_DIALOG: AtomicBool = AtomicBool::new(false);
+
+/// installs the panic hook writing crash report
+pub(crate) fn install() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        default_hook(info);
+        match write_report(info) {
+            Ok(path) => {
+                eprintln!("crash report written to {}", path.display());
+                if SHOW_DIALOG.load(Ordering::Relaxed) {
+                    show_dialog(&path);
+                }
+            }
+            Err(e) => eprintln!("error writing crash report: {}", e),
+        }
+    }));
+}
+
+/// enables the error dialog on panic. called in GUI mode after i18n is initialized
+pub(crate) fn enable_dialog() {
+    SHOW_DIALOG.store(true, Ordering::Relaxed);
+}
+
+fn write_report(info: &impl Display) -> std::io::Result<PathBuf> {
+    let folder = local_low_appdata_path().join("vrc-log-renamer");
+    fs::create_dir_all(&folder)?;
+    let now = Local::now();
+    let path = folder.join(format!("crash-{}.txt", now.format("%Y-%m-%d_%H-%M-%S")));
+    let report = format!(
+        "VRC Log Renamer {} ({} {}) crashed at {}\n\n{}\n\nbacktrace:\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        GIT_HASH,
+        BUILD_TARGET,
+        now.to_rfc3339(),
+        info,
+        Backtrace::force_capture(),
+    );
+    fs::write(&path, report)?;
+    Ok(path)
+}
+
+fn show_dialog(path: &PathBuf) {
+    let message = format!("{}\n{}", m!(CrashedText), path.display());
+    let open = HWND::GetDesktopWindow().MessageBox(
+        &message,
+        m!(CrashedCaption),
+        MB::YESNO | MB::ICONERROR,
+    );
+    if let Ok(DLGID::YES) = open {
+        // opens the report with the default app
+        let _ = std::process::Command::new("explorer").arg(path).spawn();
+    }
+}
@@ -0,0 +1,81 @@
+// VRC Log Renamer - the tool to rename logs of VRChat to have date info
+// Copyright (C) 2022 anatawa12
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Collects crash artifacts of VRChat next to the log of the crashed session.
+
+use crate::config::Crashes;
+use crate::{copy_file, copy_file_times};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use std::{fs, io};
+
+/// the crash handler may write the dump a while after the log is last written
+const CRASH_DELAY: Duration = Duration::from_secs(5 * 60);
+
+/// copies crash folders written during the session of the log to `<log name>_crash_<folder name>`.
+/// returns the paths of the copied folders
+pub(crate) fn collect_crashes(config: &Crashes, log: &Path) -> io::Result<Vec<PathBuf>> {
+    let metadata = fs::metadata(log)?;
+    let started = metadata.created()?;
+    let ended = metadata.modified()? + CRASH_DELAY;
+    let entries = match fs::read_dir(config.folder()) {
+        Ok(entries) => entries,
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let log_stem = log.file_stem().unwrap_or_default().to_string_lossy();
+    let mut collected = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() || !is_in(&entry.metadata()?, started, ended)? {
+            continue;
+        }
+        let dst = log.with_file_name(format!(
+            "{}_crash_{}",
+            log_stem,
+            entry.file_name().to_string_lossy()
+        ));
+        if dst.exists() {
+            continue;
+        }
+        info!("collecting crash {}", entry.path().display());
+        copy_folder(&entry.path(), &dst)?;
+        collected.push(dst);
+    }
+    Ok(collected)
+}
+
+fn is_in(metadata: &fs::Metadata, started: SystemTime, ended: SystemTime) -> io::Result<bool> {
+    let modified = metadata.modified()?;
+    Ok(started <= modified && modified <= ended)
+}
+
+fn copy_folder(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let dst = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_folder(&entry.path(), &dst)?;
+        } else {
+            copy_file(&entry.path(), &dst, &mut |_, _| true)?;
+            let dst_file = fs::File::options().write(true).open(&dst)?;
+            copy_file_times(&entry.metadata()?, &dst_file)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,122 @@
+// VRC Log Renamer - the tool to rename logs of VRChat to have date info
+// Copyright (C) 2022 anatawa12
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Finds the folder VRChat writes output logs to, for users who moved the install folder.
+//! The standard LocalLow folder, the Steam libraries and the Oculus library are probed in order.
+
+use crate::local_low_appdata_path;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use windows::core::HSTRING;
+use windows::Win32::Foundation::ERROR_SUCCESS;
+use windows::Win32::System::Registry::{
+    RegGetValueW, HKEY, HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE, RRF_RT_REG_SZ,
+};
+
+const STEAM_KEY: &str = r"Software\Valve\Steam";
+const OCULUS_KEY: &str = r"SOFTWARE\WOW6432Node\Oculus VR, LLC\Oculus";
+
+/// returns the first candidate folder which contains files matching the pattern
+pub(crate) fn detect_source_folder(pattern: &Regex) -> Option<PathBuf> {
+    candidate_folders()
+        .into_iter()
+        .inspect(|folder| info!("probing {}", folder.display()))
+        .find(|folder| contains_log(folder, pattern))
+}
+
+fn candidate_folders() -> Vec<PathBuf> {
+    let mut folders = vec![local_low_appdata_path().join("VRChat").join("VRChat")];
+    for library in steam_libraries() {
+        folders.push(library.join(r"steamapps\common\VRChat"));
+    }
+    if let Some(base) = registry_string(HKEY_LOCAL_MACHINE, OCULUS_KEY, "Base") {
+        let base = PathBuf::from(base);
+        folders.push(base.join(r"Software\Software\vrchat-vrchat"));
+        folders.push(base.join(r"Software\vrchat-vrchat"));
+    }
+    folders
+}
+
+/// the steam folder and the library folders listed in libraryfolders.vdf
+fn steam_libraries() -> Vec<PathBuf> {
+    let steam = match registry_string(HKEY_CURRENT_USER, STEAM_KEY, "SteamPath") {
+        Some(steam) => PathBuf::from(steam),
+        None => return vec![],
+    };
+    let mut libraries = vec![steam.clone()];
+    let vdf = steam.join(r"steamapps\libraryfolders.vdf");
+    if let Ok(vdf) = std::fs::read_to_string(&vdf) {
+        let path = Regex::new(r#""path"\s+"((?:[^"\\]|\\.)*)""#).unwrap();
+        for captures in path.captures_iter(&vdf) {
+            let library = PathBuf::from(captures[1].replace(r"\\", r"\"));
+            if !libraries.contains(&library) {
+                libraries.push(library);
+            }
+        }
+    }
+    libraries
+}
+
+fn contains_log(folder: &Path, pattern: &Regex) -> bool {
+    let entries = match std::fs::read_dir(folder) {
+        Ok(entries) => entries,
+        Err(_) => return false,
+    };
+    entries
+        .filter_map(Result::ok)
+        .any(|entry| pattern.is_match(&entry.file_name().to_string_lossy()))
+}
+
+fn registry_string(key: HKEY, sub_key: &str, name: &str) -> Option<String> {
+    let sub_key = HSTRING::from(sub_key);
+    let name = HSTRING::from(name);
+    let mut size = 0u32;
+    let result = unsafe {
+        RegGetValueW(
+            key,
+            &sub_key,
+            &name,
+            RRF_RT_REG_SZ,
+            None,
+            None,
+            Some(&mut size),
+        )
+    };
+    if result != ERROR_SUCCESS {
+        return None;
+    }
+    let mut buffer = vec![0u16; size as usize / 2];
+    let result = unsafe {
+        RegGetValueW(
+            key,
+            &sub_key,
+            &name,
+            RRF_RT_REG_SZ,
+            None,
+            Some(buffer.as_mut_ptr().cast()),
+            Some(&mut size),
+        )
+    };
+    if result != ERROR_SUCCESS {
+        return None;
+    }
+    buffer.truncate(size as usize / 2);
+    // the size includes the terminating null
+    while buffer.last() == Some(&0) {
+        buffer.pop();
+    }
+    Some(String::from_utf16_lossy(&buffer))
+}
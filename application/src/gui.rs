@@ -14,32 +14,64 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use std::cell::UnsafeCell;
+use std::cell::{Cell, RefCell, UnsafeCell};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::mpsc;
 
-use crate::config::{parse_pattern, read_config, save_config, ConfigFile, Output, Source};
+use crate::config::{
+    invalid_pattern_char, read_config, save_config, ConfigFile, ConfigPath, Output,
+    Installer, Pictures, Schedule, Source,
+};
+use crate::detect;
 use crate::i18n::init_i18n;
 use crate::i18n::Message::*;
-use crate::task_managers::{register_task_manager, unregister_task_manager};
-use crate::{config_file_path, rename_main};
+use crate::impact::{self, format_bytes, Impact};
+use crate::ledger::Ledger;
+use crate::logger;
+use crate::pattern::{self, Collision, Pattern};
+use crate::task_managers::{install, uninstall, TaskSchedulerError};
+use crate::theme;
+use crate::tooltip;
+use crate::ui_state::{UiState, WindowPosition};
+use crate::{
+    config_file_path, crash_report, is_vrchat_running, rename_main, update, update_stats,
+    RenameSummary, BUILD_DATE, BUILD_TARGET, GIT_HASH, LICENSES_TXT, STATS_WEEKS, STATS_WORLDS,
+};
 use anyhow::{anyhow, bail, Result};
+use chrono::Local;
 use regex::Regex;
 use winsafe::co::FOS;
 use winsafe::co::{DLGID, MB};
+use winsafe::guard::DestroyAcceleratorTableGuard;
+use winsafe::msg::{em, lvm, wm};
 use winsafe::prelude::*;
 use winsafe::prelude::{user_Hwnd, GuiNativeControlEvents, GuiWindow};
 use winsafe::{AnyResult, IBindCtx, IShellItem, SHCreateItemFromParsingName};
 use winsafe::{co, CoCreateInstance, IFileOpenDialog};
-use winsafe::{gui, HWND, POINT, SIZE};
+use winsafe::{
+    gui, AccelMenuCtrl, AccelMenuCtrlData, EmptyClipboard, HwndPlace, IdPos, SetClipboardData,
+    WString, ACCEL, HACCEL, HGLOBAL, HWND, LVITEM, POINT, RECT, SIZE, WINDOWPLACEMENT,
+};
 
 pub fn gui_main() -> Result<()> {
     init_i18n();
+    crash_report::enable_dialog();
 
     let config = read_config_with_error_dialog()?;
 
     println!("config loaded.");
 
+    theme::init_theme(config.theme());
+
+    let ui_state = UiState::load().unwrap_or_else(|e| {
+        eprintln!("error loading ui state: {}", e);
+        Default::default()
+    });
+
     let gui = MainGUI::new();
-    gui.lazy_load_config(config);
+    gui.lazy_load_config(config, ui_state);
     gui.run().map_err(|e| anyhow!(e))?;
 
     Ok(())
@@ -96,21 +128,79 @@ struct MainGUI {
     run_renamer: gui::Button,
     install: gui::Button,
     uninstall: gui::Button,
+    check_for_updates: gui::Button,
+    statistics: gui::Button,
+    about: gui::Button,
+    next_run: gui::Label,
+    /// shows the result of saving for a while instead of message box
+    status: gui::Label,
+    /// tells why the newest log is not renamed
+    vrchat_running: gui::Label,
 }
 
 #[derive(Clone)]
 struct GUIInputs {
     source_folder: FileSelectBlock,
+    /// finds the folder with logs for users who moved the install folder
+    source_detect: gui::Button,
     source_pattern: TextInputBlock,
+    /// fills the folder and the pattern for VRChat or other social VR platforms
+    source_preset: gui::ComboBox,
+    /// the header format of the source, which is set by the preset
+    source_header_format: Rc<RefCell<String>>,
+    source_exclude_pattern: TextInputBlock,
     source_keep_original: gui::CheckBox,
     output_folder: FileSelectBlock,
     output_pattern: TextInputBlock,
+    output_pattern_preset: gui::ComboBox,
     output_use_utc: gui::CheckBox,
     output_use_ctime: gui::CheckBox,
+    pictures_enabled: gui::CheckBox,
+    pictures_folder: FileSelectBlock,
+    pictures_output_folder: FileSelectBlock,
+    schedule_time: TextInputBlock,
+    schedule_interval_days: TextInputBlock,
+    schedule_wake_to_run: gui::CheckBox,
+    schedule_start_when_available: gui::CheckBox,
+    schedule_use_run_key: gui::CheckBox,
+    /// the config last loaded to inputs. keeps values not editable in GUI
+    loaded_config: Rc<RefCell<ConfigFile>>,
+    /// the values of inputs last loaded from or saved to config file
+    saved_state: Rc<RefCell<Vec<String>>>,
 }
 
 const TEXT_HEIGHT: i32 = 18;
 
+/// the timer to clear the status label
+const STATUS_TIMER: usize = 1;
+const STATUS_DURATION_MS: u32 = 5000;
+
+/// the timer to show the messages of the renamer running in the background
+const CONSOLE_TIMER: usize = 2;
+const CONSOLE_INTERVAL_MS: u32 = 100;
+
+/// the timer to update whether VRChat is running
+const VRCHAT_TIMER: usize = 3;
+const VRCHAT_INTERVAL_MS: u32 = 5000;
+
+/// the command ids of keyboard shortcuts
+const ACCEL_SAVE: u16 = 1001;
+const ACCEL_EXECUTE: u16 = 1002;
+
+fn accel_table() -> Option<DestroyAcceleratorTableGuard> {
+    let shortcut = |key, cmd| ACCEL {
+        fVirt: co::ACCELF::VIRTKEY | co::ACCELF::ALT,
+        key,
+        cmd,
+    };
+    HACCEL::CreateAcceleratorTable(&mut [
+        shortcut(co::VK::CHAR_S, ACCEL_SAVE),
+        shortcut(co::VK::CHAR_E, ACCEL_EXECUTE),
+    ])
+    .map_err(|e| eprintln!("error creating keyboard shortcuts: {}", e))
+    .ok()
+}
+
 #[inline(always)]
 fn check_state(checked: bool) -> gui::CheckState {
     if checked {
@@ -126,20 +216,29 @@ impl MainGUI {
             // instantiate the window manager
             gui::WindowMainOpts {
                 title: "VRC Log Renamer".to_owned(),
-                size: (400, 348),
+                size: (400, 727),
+                class_bg_brush: theme::background_brush(),
+                accel_table: accel_table(),
                 ..Default::default() // leave all other options as default
             },
         );
+        theme::set_control_colors(&window);
 
         let mut y_pos = 10;
         let space = 7;
 
-        let source_folder = FileSelectBlock::new(
+        let source_folder =
+            FileSelectBlock::new(&window, m!(PathToVrcLogFolder).to_owned(), (10, y_pos), 310);
+
+        let source_detect = gui::Button::new(
             &window,
-            m!(PathToVrcLogFolder).to_owned(),
-            String::new(),
-            (10, y_pos),
-            380,
+            gui::ButtonOpts {
+                text: m!(DetectButton).to_owned(),
+                position: (330, y_pos + TEXT_HEIGHT),
+                width: 60,
+                height: 23,
+                ..Default::default()
+            },
         );
         y_pos += FileSelectBlock::HEIGHT + space;
 
@@ -148,6 +247,28 @@ impl MainGUI {
             m!(VrcLogFilePattern).to_owned(),
             String::new(),
             (10, y_pos),
+            280,
+        );
+
+        let source_preset = gui::ComboBox::new(
+            &window,
+            gui::ComboBoxOpts {
+                position: (300, y_pos + TEXT_HEIGHT),
+                width: 90,
+                items: source_presets()
+                    .iter()
+                    .map(|preset| preset.name.to_owned())
+                    .collect(),
+                ..Default::default()
+            },
+        );
+        y_pos += TextInputBlock::HEIGHT + space;
+
+        let source_exclude_pattern = TextInputBlock::new(
+            &window,
+            m!(VrcLogExcludePattern).to_owned(),
+            String::new(),
+            (10, y_pos),
             380,
         );
         y_pos += TextInputBlock::HEIGHT + space;
@@ -163,13 +284,8 @@ impl MainGUI {
         );
         y_pos += TEXT_HEIGHT + space * 2;
 
-        let output_folder = FileSelectBlock::new(
-            &window,
-            m!(CopyMoveLogFileTo).to_owned(),
-            String::new(),
-            (10, y_pos),
-            380,
-        );
+        let output_folder =
+            FileSelectBlock::new(&window, m!(CopyMoveLogFileTo).to_owned(), (10, y_pos), 380);
         y_pos += FileSelectBlock::HEIGHT + space;
 
         let output_pattern = TextInputBlock::new(
@@ -177,7 +293,20 @@ impl MainGUI {
             m!(OutputFilePattern).to_owned(),
             String::new(),
             (10, y_pos),
-            380,
+            280,
+        );
+
+        let output_pattern_preset = gui::ComboBox::new(
+            &window,
+            gui::ComboBoxOpts {
+                position: (300, y_pos + TEXT_HEIGHT),
+                width: 90,
+                items: output_pattern_presets()
+                    .iter()
+                    .map(|(name, _)| name.to_string())
+                    .collect(),
+                ..Default::default()
+            },
         );
         y_pos += TextInputBlock::HEIGHT + space;
 
@@ -201,6 +330,78 @@ impl MainGUI {
                 ..Default::default()
             },
         );
+        y_pos += TEXT_HEIGHT + space * 2;
+
+        let pictures_enabled = gui::CheckBox::new(
+            &window,
+            gui::CheckBoxOpts {
+                text: m!(RenamePictures).to_owned(),
+                check_state: gui::CheckState::Indeterminate,
+                position: (10, y_pos),
+                ..Default::default()
+            },
+        );
+        y_pos += TEXT_HEIGHT + space;
+
+        let pictures_folder = FileSelectBlock::new(
+            &window,
+            m!(PathToVrcPicturesFolder).to_owned(),
+            (10, y_pos),
+            380,
+        );
+        y_pos += FileSelectBlock::HEIGHT + space;
+
+        let pictures_output_folder =
+            FileSelectBlock::new(&window, m!(CopyMovePicturesTo).to_owned(), (10, y_pos), 380);
+        y_pos += FileSelectBlock::HEIGHT + space;
+
+        let schedule_time = TextInputBlock::new(
+            &window,
+            m!(ScheduleTime).to_owned(),
+            String::new(),
+            (10, y_pos),
+            185,
+        );
+
+        let schedule_interval_days = TextInputBlock::new(
+            &window,
+            m!(ScheduleIntervalDays).to_owned(),
+            String::new(),
+            (205, y_pos),
+            185,
+        );
+        y_pos += TextInputBlock::HEIGHT + space;
+
+        let schedule_wake_to_run = gui::CheckBox::new(
+            &window,
+            gui::CheckBoxOpts {
+                text: m!(WakeToRun).to_owned(),
+                check_state: gui::CheckState::Indeterminate,
+                position: (10, y_pos),
+                ..Default::default()
+            },
+        );
+
+        let schedule_start_when_available = gui::CheckBox::new(
+            &window,
+            gui::CheckBoxOpts {
+                text: m!(StartWhenAvailable).to_owned(),
+                check_state: gui::CheckState::Indeterminate,
+                position: (205, y_pos),
+                ..Default::default()
+            },
+        );
+        y_pos += TEXT_HEIGHT + space;
+
+        let schedule_use_run_key = gui::CheckBox::new(
+            &window,
+            gui::CheckBoxOpts {
+                text: m!(RunAtLogonWithRegistry).to_owned(),
+                check_state: gui::CheckState::Indeterminate,
+                position: (10, y_pos),
+                ..Default::default()
+            },
+        );
         y_pos += TEXT_HEIGHT + space;
 
         let save_config = gui::Button::new(
@@ -210,6 +411,7 @@ impl MainGUI {
                 position: (10, y_pos),
                 width: 120,
                 height: 23,
+                button_style: gui::ButtonOpts::default().button_style | co::BS::DEFPUSHBUTTON,
                 ..Default::default()
             },
         );
@@ -238,6 +440,28 @@ impl MainGUI {
 
         y_pos += 23 + space;
 
+        let status = gui::Label::new(
+            &window,
+            gui::LabelOpts {
+                text: String::new(),
+                position: (10, y_pos),
+                size: (380, TEXT_HEIGHT as u32),
+                ..Default::default()
+            },
+        );
+        y_pos += TEXT_HEIGHT + space;
+
+        let vrchat_running = gui::Label::new(
+            &window,
+            gui::LabelOpts {
+                text: String::new(),
+                position: (10, y_pos),
+                size: (380, TEXT_HEIGHT as u32),
+                ..Default::default()
+            },
+        );
+        y_pos += TEXT_HEIGHT + space;
+
         let install = gui::Button::new(
             &window,
             gui::ButtonOpts {
@@ -260,22 +484,89 @@ impl MainGUI {
             },
         );
 
+        y_pos += 23 + space;
+
+        let next_run = gui::Label::new(
+            &window,
+            gui::LabelOpts {
+                text: String::new(),
+                position: (10, y_pos),
+                size: (380, TEXT_HEIGHT as u32),
+                ..Default::default()
+            },
+        );
+        y_pos += TEXT_HEIGHT + space;
+
+        let check_for_updates = gui::Button::new(
+            &window,
+            gui::ButtonOpts {
+                text: m!(CheckForUpdates).to_owned(),
+                position: (10, y_pos),
+                width: 120,
+                height: 23,
+                ..Default::default()
+            },
+        );
+
+        let statistics = gui::Button::new(
+            &window,
+            gui::ButtonOpts {
+                text: m!(StatisticsButton).to_owned(),
+                position: (140, y_pos),
+                width: 120,
+                height: 23,
+                ..Default::default()
+            },
+        );
+
+        let about = gui::Button::new(
+            &window,
+            gui::ButtonOpts {
+                text: m!(AboutButton).to_owned(),
+                position: (270, y_pos),
+                width: 120,
+                height: 23,
+                ..Default::default()
+            },
+        );
+
         let new_self = Self {
             window,
             inputs: GUIInputs {
                 source_folder,
+                source_detect,
                 source_pattern,
+                source_preset,
+                source_header_format: Default::default(),
+                source_exclude_pattern,
                 source_keep_original,
                 output_folder,
                 output_pattern,
+                output_pattern_preset,
                 output_use_utc,
                 output_use_ctime,
+                pictures_enabled,
+                pictures_folder,
+                pictures_output_folder,
+                schedule_time,
+                schedule_interval_days,
+                schedule_wake_to_run,
+                schedule_start_when_available,
+                schedule_use_run_key,
+                loaded_config: Default::default(),
+                saved_state: Default::default(),
             },
             save_config,
             reset_to_default,
             run_renamer,
             install,
             uninstall,
+            check_for_updates,
+            statistics,
+            about,
+            next_run,
+            status,
+            vrchat_running,
         };
         new_self.events(); // attach our events
         new_self
@@ -289,32 +580,119 @@ impl MainGUI {
         self.window.run_main(None) // simply let the window manager do the hard work
     }
 
-    fn lazy_load_config(&self, config: ConfigFile) {
+    fn lazy_load_config(&self, config: ConfigFile, ui_state: UiState) {
+        self.window.on().wm_create({
+            let window = self.window.clone();
+            let check_updates = config.check_updates();
+            let tab_order = self.tab_order();
+            let tooltips = self.inputs.tooltips();
+            let vrchat_running = self.vrchat_running.clone();
+            move |_| {
+                theme::apply_dark_styles(window.hwnd());
+                update_vrchat_running(&vrchat_running);
+                window
+                    .hwnd()
+                    .SetTimer(VRCHAT_TIMER, VRCHAT_INTERVAL_MS, None)?;
+                set_tab_order(&tab_order)?;
+                if let Err(e) = tooltip::add_tooltips(window.hwnd(), &tooltips) {
+                    eprintln!("error creating tooltips: {}", e);
+                }
+                if let Some(position) = ui_state.window() {
+                    restore_window_position(window.hwnd(), position)?;
+                }
+                if check_updates {
+                    check_update_in_background(&window, true);
+                }
+                Ok(0)
+            }
+        });
         self.window.on().wm_activate({
             let optional = UnsafeCell::new(Some((config, self.inputs.clone())));
+            let save_config = self.save_config.clone();
             move |_| {
                 if let Some((config, inputs)) = unsafe { (*optional.get()).take() } {
                     inputs.load_values_from_config(&config);
+                    inputs.mark_saved();
+                    update_save_button(&save_config, &inputs);
                 }
                 Ok(())
             }
         });
     }
 
+    /// the controls in the order focused with Tab key
+    fn tab_order(&self) -> Vec<Box<dyn GuiWindow>> {
+        let mut controls = self.inputs.tab_order();
+        for button in [
+            &self.save_config,
+            &self.reset_to_default,
+            &self.run_renamer,
+            &self.install,
+            &self.uninstall,
+            &self.check_for_updates,
+            &self.statistics,
+            &self.about,
+        ] {
+            controls.push(Box::new(button.clone()));
+        }
+        controls
+    }
+
     fn events(&self) {
         self.inputs.events(&self.window);
+        // Enter and Esc are sent as IDOK and IDCANCEL
+        self.window.on().wm_command_accel_menu(DLGID::OK.raw(), {
+            let save_config = self.save_config.clone();
+            move || {
+                save_config.trigger_click();
+                Ok(())
+            }
+        });
+        self.window.on().wm_command_accel_menu(DLGID::CANCEL.raw(), {
+            let window = self.window.clone();
+            move || {
+                window.hwnd().SendMessage(wm::Close {});
+                Ok(())
+            }
+        });
+        self.window.on().wm_command_accel_menu(ACCEL_SAVE, {
+            let save_config = self.save_config.clone();
+            move || {
+                save_config.trigger_click();
+                Ok(())
+            }
+        });
+        self.window.on().wm_command_accel_menu(ACCEL_EXECUTE, {
+            let run_renamer = self.run_renamer.clone();
+            move || {
+                run_renamer.trigger_click();
+                Ok(())
+            }
+        });
+        self.inputs.on_change({
+            let save_config = self.save_config.clone();
+            let inputs = self.inputs.clone();
+            move || {
+                update_save_button(&save_config, &inputs);
+                Ok(())
+            }
+        });
         self.window.on().wm_close({
             let window = self.window.clone();
             let inputs = self.inputs.clone();
             move || {
-                if window.hwnd().MessageBox(
-                    m!(SaveBeforeCloseText),
-                    m!(SaveBeforeCloseCaption),
-                    MB::YESNO,
-                )? == DLGID::YES
+                if inputs.is_dirty()
+                    && window.hwnd().MessageBox(
+                        m!(SaveBeforeCloseText),
+                        m!(SaveBeforeCloseCaption),
+                        MB::YESNO,
+                    )? == DLGID::YES
                 {
                     inputs.create_save_config(window.hwnd())?;
                 }
+                if let Err(e) = save_window_position(window.hwnd()) {
+                    eprintln!("error saving window position: {}", e);
+                }
                 window.hwnd().DestroyWindow()?;
                 Ok(())
             }
@@ -322,20 +700,38 @@ impl MainGUI {
         self.save_config.on().bn_clicked({
             let window = self.window.clone();
             let inputs = self.inputs.clone();
+            let save_config = self.save_config.clone();
+            let status = self.status.clone();
             move || {
                 if let Some(Some(_)) = inputs.create_save_config(window.hwnd()).ok() {
-                    window.hwnd().MessageBox(
-                        m!(ConfigSavedText),
-                        m!(ConfigSavedCaption),
-                        MB::OK,
-                    )?;
+                    update_save_button(&save_config, &inputs);
+                    let saved_at = Local::now().format("%H:%M");
+                    status.set_text(&format!("{} {}", m!(ConfigSavedAt), saved_at));
+                    window.hwnd().SetTimer(STATUS_TIMER, STATUS_DURATION_MS, None)?;
                 }
                 Ok(())
             }
         });
+        self.window.on().wm_timer(STATUS_TIMER as u32, {
+            let window = self.window.clone();
+            let status = self.status.clone();
+            move || {
+                window.hwnd().KillTimer(STATUS_TIMER)?;
+                status.set_text("");
+                Ok(())
+            }
+        });
+        self.window.on().wm_timer(VRCHAT_TIMER as u32, {
+            let vrchat_running = self.vrchat_running.clone();
+            move || {
+                update_vrchat_running(&vrchat_running);
+                Ok(())
+            }
+        });
         self.reset_to_default.on().bn_clicked({
             let window = self.window.clone();
             let inputs = self.inputs.clone();
+            let save_config = self.save_config.clone();
             move || {
                 if window.hwnd().MessageBox(
                     m!(ResetConfirmText),
@@ -344,6 +740,7 @@ impl MainGUI {
                 )? == DLGID::OK
                 {
                     inputs.load_values_from_config(&Default::default());
+                    update_save_button(&save_config, &inputs);
                 }
                 Ok(())
             }
@@ -351,11 +748,22 @@ impl MainGUI {
         self.install.on().bn_clicked({
             let window = self.window.clone();
             let inputs = self.inputs.clone();
+            let save_config = self.save_config.clone();
+            let next_run = self.next_run.clone();
             move || {
-                if let Some(Some(_)) = inputs.create_save_config(window.hwnd()).ok() {
-                    register_task_manager()?;
+                if let Some(Some(config)) = inputs.create_save_config(window.hwnd()).ok() {
+                    update_save_button(&save_config, &inputs);
+                    let installer = config.schedule().installer();
+                    let next_run_time = match (install(config.schedule()), installer) {
+                        (Ok(Some(time)), _) => time.format("%Y-%m-%d %H:%M").to_string(),
+                        (Ok(None), Installer::TaskScheduler) => m!(NextRunUnknown).to_owned(),
+                        (Ok(None), Installer::RunKey) => m!(NextRunAtLogon).to_owned(),
+                        (Err(e), _) => return show_install_error(window.hwnd(), installer, &e),
+                    };
+                    let next_run_text = format!("{}: {}", m!(NextRun), next_run_time);
+                    next_run.set_text(&next_run_text);
                     window.hwnd().MessageBox(
-                        m!(InstallSucceedText),
+                        &format!("{}\n{}", m!(InstallSucceedText), next_run_text),
                         m!(InstallSucceedCaption),
                         MB::OK,
                     )?;
@@ -366,9 +774,14 @@ impl MainGUI {
         self.uninstall.on().bn_clicked({
             let window = self.window.clone();
             let inputs = self.inputs.clone();
+            let save_config = self.save_config.clone();
             move || {
-                if let Some(Some(_)) = inputs.create_save_config(window.hwnd()).ok() {
-                    unregister_task_manager()?;
+                if let Some(Some(config)) = inputs.create_save_config(window.hwnd()).ok() {
+                    update_save_button(&save_config, &inputs);
+                    if let Err(e) = uninstall(config.schedule()) {
+                        let installer = config.schedule().installer();
+                        return show_install_error(window.hwnd(), installer, &e);
+                    }
                     window.hwnd().MessageBox(
                         m!(UninstallSucceedText),
                         m!(UninstallSucceedCaption),
@@ -378,60 +791,1011 @@ impl MainGUI {
                 Ok(())
             }
         });
+        self.check_for_updates.on().bn_clicked({
+            let window = self.window.clone();
+            move || {
+                check_update_in_background(&window, false);
+                Ok(())
+            }
+        });
+        self.statistics.on().bn_clicked({
+            let window = self.window.clone();
+            move || show_statistics(&window)
+        });
+        self.about.on().bn_clicked({
+            let window = self.window.clone();
+            move || show_about(&window)
+        });
         self.run_renamer.on().bn_clicked({
             let window = self.window.clone();
             let inputs = self.inputs.clone();
+            let save_config = self.save_config.clone();
             move || {
                 if let Some(Some(new_config)) = inputs.create_save_config(window.hwnd()).ok() {
-                    if let Some(e) = rename_main(&new_config).err() {
-                        eprintln!("error during rename: {:?}", e);
-                        window.hwnd().MessageBox(
-                            &format!("{}: {}", m!(ErrorInRenameText), e),
-                            m!(ErrorInRenameCaption),
-                            MB::OK,
-                        )?;
-                    } else {
-                        window.hwnd().MessageBox(
-                            m!(RenameSucceedText),
-                            m!(RenameSucceedCaption),
-                            MB::OK,
-                        )?;
-                    }
+                    update_save_button(&save_config, &inputs);
+                    let selection = match select_files(&window, &new_config)? {
+                        Some(selection) => selection,
+                        None => return Ok(()),
+                    };
+                    show_rename_console(&window, new_config, selection)?;
+                }
+                Ok(())
+            }
+        });
+    }
+}
+
+fn update_vrchat_running(label: &gui::Label) {
+    let text = if is_vrchat_running() {
+        m!(VrchatRunning)
+    } else {
+        ""
+    };
+    label.set_text(text);
+}
+
+/// shows the files to be copied or moved with check boxes and the free space of the destinations.
+/// returns the checked files, or None if cancelled
+fn select_files(
+    window: &gui::WindowMain,
+    config: &ConfigFile,
+) -> AnyResult<Option<HashSet<PathBuf>>> {
+    let impact = match impact::estimate(config) {
+        Ok(impact) => Rc::new(impact),
+        Err(e) => {
+            eprintln!("error estimating impact: {:?}", e);
+            let details = format!("{}:\n{:?}", m!(ErrorInRenameText), e);
+            show_error_details(window, &details)?;
+            return Ok(None);
+        }
+    };
+    let selection = Rc::new(RefCell::new(None));
+    // the summary is not updated while checking all items on load
+    let loading = Rc::new(Cell::new(true));
+
+    let modal = modal_window(window, m!(ConfirmRunCaption), (560, 435));
+    let summary = gui::Label::new(
+        &modal,
+        gui::LabelOpts {
+            text: impact_summary(&impact),
+            position: (10, 10),
+            size: (540, TEXT_HEIGHT as u32 * 4),
+            ..Default::default()
+        },
+    );
+    let list = gui::ListView::new(
+        &modal,
+        gui::ListViewOpts {
+            position: (10, 20 + TEXT_HEIGHT * 4),
+            size: (540, 300),
+            list_view_ex_style: co::LVS_EX::CHECKBOXES | co::LVS_EX::FULLROWSELECT,
+            columns: vec![
+                (m!(FileColumn).to_owned(), 410),
+                (m!(SizeColumn).to_owned(), 110),
+            ],
+            ..Default::default()
+        },
+    );
+    let execute = gui::Button::new(
+        &modal,
+        gui::ButtonOpts {
+            text: m!(ExecuteNow).to_owned(),
+            position: (320, 402),
+            width: 110,
+            height: 23,
+            button_style: gui::ButtonOpts::default().button_style | co::BS::DEFPUSHBUTTON,
+            ..Default::default()
+        },
+    );
+    let cancel = gui::Button::new(
+        &modal,
+        gui::ButtonOpts {
+            text: m!(CancelButton).to_owned(),
+            position: (440, 402),
+            width: 110,
+            height: 23,
+            ..Default::default()
+        },
+    );
+
+    // replaces the handler registered by modal_window
+    modal.on().wm_create({
+        let modal = modal.clone();
+        let list = list.clone();
+        let impact = impact.clone();
+        let loading = loading.clone();
+        move |_| {
+            theme::apply_dark_styles(modal.hwnd());
+            for file in &impact.files {
+                let texts = [file.path.display().to_string(), format_bytes(file.size)];
+                list.items().add(&texts, None);
+            }
+            set_list_checked(&list, None, true)?;
+            loading.set(false);
+            Ok(0)
+        }
+    });
+    list.on().lvn_item_changed({
+        let list = list.clone();
+        let impact = impact.clone();
+        let loading = loading.clone();
+        move |p| {
+            let state_changed = p.uNewState ^ p.uOldState;
+            if !loading.get() && state_changed.has(co::LVIS::STATEIMAGEMASK) {
+                let checked = checked_files(&list, &impact);
+                summary.set_text(&impact_summary(&impact.selected(&checked)));
+            }
+            Ok(())
+        }
+    });
+    execute.on().bn_clicked({
+        let modal = modal.clone();
+        let list = list.clone();
+        let selection = selection.clone();
+        move || {
+            let checked = checked_files(&list, &impact);
+            if let Some((destination, required)) = impact.selected(&checked).lacking_space() {
+                let message = format!(
+                    "{}\n\n{} ({}): {}\n{} ({}): {}",
+                    m!(NotEnoughSpaceText),
+                    m!(RequiredSpace),
+                    destination.volume.display(),
+                    format_bytes(required),
+                    m!(FreeSpace),
+                    destination.volume.display(),
+                    format_bytes(destination.free),
+                );
+                modal.hwnd().MessageBox(
+                    &message,
+                    m!(NotEnoughSpaceCaption),
+                    MB::OK | MB::ICONERROR,
+                )?;
+                return Ok(());
+            }
+            *selection.borrow_mut() = Some(checked);
+            modal.hwnd().SendMessage(wm::Close {});
+            Ok(())
+        }
+    });
+    cancel.on().bn_clicked({
+        let modal = modal.clone();
+        move || {
+            modal.hwnd().SendMessage(wm::Close {});
+            Ok(())
+        }
+    });
+    // Enter runs instead of closing
+    modal.on().wm_command_accel_menu(DLGID::OK.raw(), {
+        let execute = execute.clone();
+        move || {
+            execute.trigger_click();
+            Ok(())
+        }
+    });
+    modal.show_modal()?;
+    let selection = selection.borrow_mut().take();
+    Ok(selection)
+}
+
+fn impact_summary(impact: &Impact) -> String {
+    let mut summary = format!(
+        "{}\n{}: {}, {}: {}",
+        m!(ConfirmRunText),
+        m!(MatchingFiles),
+        impact.files.len(),
+        m!(TotalSize),
+        format_bytes(impact.bytes()),
+    );
+    for destination in &impact.destinations {
+        summary.push_str(&format!(
+            "\n{} ({}): {}",
+            m!(FreeSpace),
+            destination.volume.display(),
+            format_bytes(destination.free)
+        ));
+    }
+    summary
+}
+
+/// checks or unchecks the item of the list view with check boxes. all items if index is None
+fn set_list_checked(list: &gui::ListView, index: Option<u32>, checked: bool) -> AnyResult<()> {
+    let mut item = LVITEM::default();
+    item.stateMask = co::LVIS::STATEIMAGEMASK;
+    // the state image 1 is unchecked and 2 is checked
+    item.state = unsafe { co::LVIS::from_raw(if checked { 2 << 12 } else { 1 << 12 }) };
+    list.hwnd().SendMessage(lvm::SetItemState {
+        index,
+        lvitem: &item,
+    })?;
+    Ok(())
+}
+
+/// the files of the checked items. the items are in the same order as the files
+fn checked_files(list: &gui::ListView, impact: &Impact) -> HashSet<PathBuf> {
+    impact
+        .files
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| {
+            let state = list.hwnd().SendMessage(lvm::GetItemState {
+                index: *index as u32,
+                mask: co::LVIS::STATEIMAGEMASK,
+            });
+            state.raw() >> 12 == 2
+        })
+        .map(|(_, file)| file.path.clone())
+        .collect()
+}
+
+/// runs the renamer in the background and shows its messages while renaming
+fn show_rename_console(
+    window: &gui::WindowMain,
+    config: ConfigFile,
+    selection: HashSet<PathBuf>,
+) -> AnyResult<()> {
+    let (log_sender, log_receiver) = mpsc::channel::<String>();
+    let (result_sender, result_receiver) = mpsc::channel();
+    let running = Rc::new(Cell::new(true));
+
+    let modal = modal_window(window, m!(RenameLogCaption), (560, 400));
+    let console = text_viewer(&modal, "", (10, 10), (540, 347));
+    let copy = gui::Button::new(
+        &modal,
+        gui::ButtonOpts {
+            text: m!(CopyDetails).to_owned(),
+            position: (320, 367),
+            width: 110,
+            height: 23,
+            ..Default::default()
+        },
+    );
+    let close = close_button(&modal, (440, 367));
+    copy.on().bn_clicked({
+        let modal = modal.clone();
+        let console = console.clone();
+        move || {
+            copy_to_clipboard(modal.hwnd(), &console.text())?;
+            Ok(())
+        }
+    });
+    // the modal cannot be closed until the renamer finishes
+    for id in [DLGID::OK, DLGID::CANCEL] {
+        modal.on().wm_command_accel_menu(id.raw(), {
+            let modal = modal.clone();
+            let running = running.clone();
+            move || {
+                if !running.get() {
+                    modal.hwnd().SendMessage(wm::Close {});
+                }
+                Ok(())
+            }
+        });
+    }
+    modal.on().wm_create({
+        let modal = modal.clone();
+        let console = console.clone();
+        let close = close.clone();
+        move |_| {
+            theme::apply_dark_styles(modal.hwnd());
+            console.limit_text(None);
+            set_closable(&modal, &close, false)?;
+            modal
+                .hwnd()
+                .SetTimer(CONSOLE_TIMER, CONSOLE_INTERVAL_MS, None)?;
+            Ok(0)
+        }
+    });
+    modal.on().wm_timer(CONSOLE_TIMER as u32, {
+        let modal = modal.clone();
+        let console = console.clone();
+        let close = close.clone();
+        move || {
+            for line in log_receiver.try_iter() {
+                append_line(&console, &line);
+            }
+            let result = match result_receiver.try_recv() {
+                Ok(result) => result,
+                Err(mpsc::TryRecvError::Empty) => return Ok(()),
+                // the renamer thread panicked. the panic is reported by the crash report
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    logger::set_sink(None);
+                    Err(anyhow!("the renamer stopped unexpectedly"))
                 }
+            };
+            // the messages sent just before the result
+            for line in log_receiver.try_iter() {
+                append_line(&console, &line);
+            }
+            modal.hwnd().KillTimer(CONSOLE_TIMER)?;
+            append_line(&console, &rename_result_text(&result));
+            running.set(false);
+            set_closable(&modal, &close, true)?;
+            Ok(())
+        }
+    });
+
+    logger::set_sink(Some(log_sender));
+    std::thread::spawn(move || {
+        let result = rename_main(&config, Some(&selection));
+        logger::set_sink(None);
+        let _ = result_sender.send(result);
+    });
+    modal.show_modal()?;
+    Ok(())
+}
+
+/// the message shown at the end of the rename log
+fn rename_result_text(result: &Result<RenameSummary>) -> String {
+    match result {
+        Err(e) => {
+            eprintln!("error during rename: {:?}", e);
+            format!("{}:\n{:?}", m!(ErrorInRenameText), e)
+        }
+        Ok(summary) if !summary.errors.is_empty() => {
+            let mut details = format!("{}:", m!(SomeFilesFailedText));
+            for error in &summary.errors {
+                details.push_str(&format!("\n{}: {}", error.path.display(), error.error));
+            }
+            details
+        }
+        Ok(_) => m!(RenameSucceedText).to_owned(),
+    }
+}
+
+/// appends the lines to the end of the multiline edit, which scrolls to them
+fn append_line(edit: &gui::Edit, line: &str) {
+    let length = edit.hwnd().SendMessage(wm::GetTextLength {});
+    edit.hwnd().SendMessage(em::SetSel {
+        start: Some(length),
+        end: Some(length),
+    });
+    let text = format!("{}\r\n", line.lines().collect::<Vec<_>>().join("\r\n"));
+    edit.hwnd().SendMessage(em::ReplaceSel {
+        can_be_undone: false,
+        replacement_text: WString::from_str(&text),
+    });
+}
+
+/// enables or disables the close button and the close menu of the modal window
+fn set_closable(modal: &gui::WindowModal, close: &gui::Button, closable: bool) -> AnyResult<()> {
+    close.hwnd().EnableWindow(closable);
+    if let Some(menu) = modal.hwnd().GetSystemMenu(false) {
+        menu.EnableMenuItem(IdPos::Id(co::SC::CLOSE.raw() as u16), closable)?;
+    }
+    Ok(())
+}
+
+/// shows the error details in a scrollable dialog with a button to copy them
+fn show_error_details(window: &gui::WindowMain, details: &str) -> AnyResult<()> {
+    let modal = modal_window(window, m!(ErrorInRenameCaption), (480, 320));
+    let _details = text_viewer(&modal, details, (10, 10), (460, 267));
+    let copy = gui::Button::new(
+        &modal,
+        gui::ButtonOpts {
+            text: m!(CopyDetails).to_owned(),
+            position: (230, 287),
+            width: 120,
+            height: 23,
+            ..Default::default()
+        },
+    );
+    let _close = close_button(&modal, (360, 287));
+    copy.on().bn_clicked({
+        let modal = modal.clone();
+        let details = details.to_owned();
+        move || {
+            copy_to_clipboard(modal.hwnd(), &details)?;
+            Ok(())
+        }
+    });
+    modal.show_modal()?;
+    Ok(())
+}
+
+/// shows the play time per week and the most visited worlds in the archived logs
+fn show_statistics(window: &gui::WindowMain) -> AnyResult<()> {
+    let summary = update_stats(&Ledger::load()?)?.summary(STATS_WEEKS, STATS_WORLDS);
+    let mut text = format!(
+        "{}: {}\n{}: {:.1}\n\n{}:\n",
+        m!(StatisticsSessions),
+        summary.sessions,
+        m!(StatisticsTotalHours),
+        summary.total_hours,
+        m!(StatisticsHoursPerWeek),
+    );
+    for week in &summary.weeks {
+        text.push_str(&format!(
+            "{}: {:.1}\n",
+            week.week.format("%Y-%m-%d"),
+            week.hours
+        ));
+    }
+    text.push_str(&format!("\n{}:\n", m!(StatisticsMostVisitedWorlds)));
+    for world in &summary.worlds {
+        text.push_str(&format!("{}: {}\n", world.world_name, world.visits));
+    }
+    let modal = modal_window(window, m!(StatisticsCaption), (480, 400));
+    let _summary = text_viewer(&modal, &text, (10, 10), (460, 347));
+    let _close = close_button(&modal, (360, 367));
+    modal.show_modal()?;
+    Ok(())
+}
+
+/// shows the version, the repository and the licenses of the app and dependencies
+fn show_about(window: &gui::WindowMain) -> AnyResult<()> {
+    let modal = modal_window(window, m!(AboutCaption), (480, 400));
+    let _version = gui::Label::new(
+        &modal,
+        gui::LabelOpts {
+            text: format!("VRC Log Renamer v{}", env!("CARGO_PKG_VERSION")),
+            position: (10, 10),
+            size: (460, TEXT_HEIGHT as u32),
+            ..Default::default()
+        },
+    );
+    // to identify the exe in bug reports
+    let _build = gui::Label::new(
+        &modal,
+        gui::LabelOpts {
+            text: format!(
+                "{}: {}  {}: {}  {}: {}",
+                m!(BuildCommitLabel),
+                GIT_HASH,
+                m!(BuildDateLabel),
+                BUILD_DATE,
+                m!(BuildTargetLabel),
+                BUILD_TARGET,
+            ),
+            position: (10, 30),
+            size: (460, TEXT_HEIGHT as u32),
+            ..Default::default()
+        },
+    );
+    let _repository = gui::Label::new(
+        &modal,
+        gui::LabelOpts {
+            text: env!("CARGO_PKG_REPOSITORY").to_owned(),
+            position: (10, 56),
+            size: (330, TEXT_HEIGHT as u32),
+            ..Default::default()
+        },
+    );
+    let open_repository = gui::Button::new(
+        &modal,
+        gui::ButtonOpts {
+            text: m!(OpenRepository).to_owned(),
+            position: (350, 53),
+            width: 120,
+            height: 23,
+            ..Default::default()
+        },
+    );
+    let _licenses_label = gui::Label::new(
+        &modal,
+        gui::LabelOpts {
+            text: m!(LicensesLabel).to_owned(),
+            position: (10, 86),
+            size: (460, TEXT_HEIGHT as u32),
+            ..Default::default()
+        },
+    );
+    let _licenses = text_viewer(&modal, LICENSES_TXT, (10, 86 + TEXT_HEIGHT), (460, 258));
+    let _close = close_button(&modal, (360, 367));
+    open_repository.on().bn_clicked({
+        let modal = modal.clone();
+        move || {
+            let repository = env!("CARGO_PKG_REPOSITORY");
+            let shown = co::SW::SHOWNORMAL;
+            if let Err(e) = modal
+                .hwnd()
+                .ShellExecute("open", repository, None, None, shown)
+            {
+                eprintln!("error opening repository: {:?}", e);
+            }
+            Ok(())
+        }
+    });
+    modal.show_modal()?;
+    Ok(())
+}
+
+/// creates the modal window with the theme applied
+fn modal_window(window: &gui::WindowMain, title: &str, size: (u32, u32)) -> gui::WindowModal {
+    let modal = gui::WindowModal::new(
+        window,
+        gui::WindowModalOpts {
+            title: title.to_owned(),
+            size,
+            class_bg_brush: theme::background_brush(),
+            ..Default::default()
+        },
+    );
+    theme::set_control_colors(&modal);
+    // Enter and Esc are sent as IDOK and IDCANCEL
+    for id in [DLGID::OK, DLGID::CANCEL] {
+        modal.on().wm_command_accel_menu(id.raw(), {
+            let modal = modal.clone();
+            move || {
+                modal.hwnd().SendMessage(wm::Close {});
                 Ok(())
             }
         });
     }
+    modal.on().wm_create({
+        let modal = modal.clone();
+        move |_| {
+            theme::apply_dark_styles(modal.hwnd());
+            Ok(0)
+        }
+    });
+    modal
+}
+
+/// creates read-only multiline edit with the vertical scroll bar to show long text
+fn text_viewer(
+    parent: &impl GuiParent,
+    text: &str,
+    position: (i32, i32),
+    size: (u32, u32),
+) -> gui::Edit {
+    gui::Edit::new(
+        parent,
+        gui::EditOpts {
+            // multiline edit requires CRLF to break lines
+            text: text.lines().collect::<Vec<_>>().join("\r\n"),
+            position,
+            width: size.0,
+            height: size.1,
+            edit_style: co::ES::MULTILINE | co::ES::AUTOVSCROLL | co::ES::READONLY,
+            window_style: gui::EditOpts::default().window_style | co::WS::VSCROLL,
+            ..Default::default()
+        },
+    )
+}
+
+/// creates the button to close the modal window
+fn close_button(modal: &gui::WindowModal, position: (i32, i32)) -> gui::Button {
+    let close = gui::Button::new(
+        modal,
+        gui::ButtonOpts {
+            text: m!(CloseButton).to_owned(),
+            position,
+            width: 110,
+            height: 23,
+            ..Default::default()
+        },
+    );
+    close.on().bn_clicked({
+        let modal = modal.clone();
+        move || {
+            modal.hwnd().SendMessage(wm::Close {});
+            Ok(())
+        }
+    });
+    close
+}
+
+/// replaces the clipboard contents with the text
+fn copy_to_clipboard(hwnd: &HWND, text: &str) -> AnyResult<()> {
+    let data = text.encode_utf16().chain(Some(0)).collect::<Vec<_>>();
+    let _clipboard = hwnd.OpenClipboard()?;
+    EmptyClipboard()?;
+    let mut memory = HGLOBAL::GlobalAlloc(Some(co::GMEM::MOVEABLE), data.len() * 2)?;
+    {
+        let (buffer, _unlock) = memory.GlobalLock()?;
+        for (dst, unit) in buffer.chunks_exact_mut(2).zip(data) {
+            dst.copy_from_slice(&unit.to_ne_bytes());
+        }
+    }
+    unsafe { SetClipboardData(co::CF::UNICODETEXT, memory.ptr() as _)? };
+    // the clipboard owns the memory now
+    let _ = memory.leak();
+    Ok(())
+}
+
+/// moves the window to the saved position. the size is kept since it's determined by the layout
+fn restore_window_position(hwnd: &HWND, position: WindowPosition) -> AnyResult<()> {
+    let mut placement = WINDOWPLACEMENT::default();
+    hwnd.GetWindowPlacement(&mut placement)?;
+    let rect = placement.rcNormalPosition;
+    placement.rcNormalPosition = RECT {
+        left: position.x,
+        top: position.y,
+        right: position.x + (rect.right - rect.left),
+        bottom: position.y + (rect.bottom - rect.top),
+    };
+    // the window is shown by run_main later.
+    // SetWindowPlacement moves the window back to the screen if it's out of all monitors
+    placement.showCmd = co::SW::HIDE;
+    hwnd.SetWindowPlacement(&placement)?;
+    Ok(())
+}
+
+/// saves the position of the window to the UI state
+fn save_window_position(hwnd: &HWND) -> AnyResult<()> {
+    let mut placement = WINDOWPLACEMENT::default();
+    hwnd.GetWindowPlacement(&mut placement)?;
+    let mut ui_state = UiState::load()?;
+    ui_state.set_window(WindowPosition {
+        x: placement.rcNormalPosition.left,
+        y: placement.rcNormalPosition.top,
+    });
+    ui_state.save()?;
+    Ok(())
+}
+
+/// places the controls in the z-order, which is the order focused with Tab key
+fn set_tab_order(controls: &[Box<dyn GuiWindow>]) -> AnyResult<()> {
+    for pair in controls.windows(2) {
+        pair[1].hwnd().SetWindowPos(
+            HwndPlace::Hwnd(unsafe { pair[0].hwnd().raw_copy() }),
+            POINT::default(),
+            SIZE::default(),
+            co::SWP::NOMOVE | co::SWP::NOSIZE | co::SWP::NOACTIVATE,
+        )?;
+    }
+    Ok(())
+}
+
+/// enables save button only if there are unsaved changes
+fn update_save_button(save_config: &gui::Button, inputs: &GUIInputs) {
+    save_config.hwnd().EnableWindow(inputs.is_dirty());
+}
+
+/// shows the error of installing. suggests alternatives if access to task scheduler is denied
+fn show_install_error(hwnd: &HWND, installer: Installer, error: &anyhow::Error) -> AnyResult<()> {
+    eprintln!("error installing: {:?}", error);
+    let denied = error
+        .downcast_ref::<TaskSchedulerError>()
+        .is_some_and(TaskSchedulerError::is_access_denied);
+    let (text, caption) = match installer {
+        _ if denied => (m!(TaskSchedulerDeniedText), m!(TaskSchedulerErrorCaption)),
+        Installer::TaskScheduler => (m!(TaskSchedulerErrorText), m!(TaskSchedulerErrorCaption)),
+        Installer::RunKey => (m!(RunKeyErrorText), m!(RunKeyErrorCaption)),
+    };
+    hwnd.MessageBox(
+        &format!("{}\n\n{:#}", text, error),
+        caption,
+        MB::OK | MB::ICONERROR,
+    )?;
+    Ok(())
+}
+
+/// checks the update in other thread and offers downloading if found.
+/// if silent, nothing is shown when there's no update or failed to check, like offline
+fn check_update_in_background(window: &gui::WindowMain, silent: bool) {
+    window.spawn_new_thread({
+        let window = window.clone();
+        move || {
+            let result = update::check_update();
+            window.run_ui_thread({
+                let window = window.clone();
+                move || {
+                    let release = match result {
+                        Ok(Some(release)) => release,
+                        Ok(None) if silent => return Ok(()),
+                        Ok(None) => {
+                            window.hwnd().MessageBox(
+                                m!(NoUpdateText),
+                                m!(NoUpdateCaption),
+                                MB::OK,
+                            )?;
+                            return Ok(());
+                        }
+                        Err(e) => {
+                            eprintln!("error checking update: {:?}", e);
+                            if !silent {
+                                window.hwnd().MessageBox(
+                                    &format!("{}: {}", m!(ErrorCheckingUpdateText), e),
+                                    m!(ErrorCheckingUpdateCaption),
+                                    MB::OK,
+                                )?;
+                            }
+                            return Ok(());
+                        }
+                    };
+                    let message = format!("{}: {}", m!(UpdateAvailableText), release.version());
+                    if window.hwnd().MessageBox(
+                        &message,
+                        m!(UpdateAvailableCaption),
+                        MB::YESNO,
+                    )? == DLGID::YES
+                    {
+                        download_update_in_background(&window, release);
+                    }
+                    Ok(())
+                }
+            });
+            Ok(())
+        }
+    });
+}
+
+fn download_update_in_background(window: &gui::WindowMain, release: update::Release) {
+    window.spawn_new_thread({
+        let window = window.clone();
+        move || {
+            let result = update::download(&release);
+            window.run_ui_thread({
+                let window = window.clone();
+                move || {
+                    match result {
+                        Ok(path) => window.hwnd().MessageBox(
+                            &format!("{}: {}", m!(UpdateDownloadedText), path.display()),
+                            m!(UpdateDownloadedCaption),
+                            MB::OK,
+                        )?,
+                        Err(e) => window.hwnd().MessageBox(
+                            &format!("{}: {}", m!(ErrorCheckingUpdateText), e),
+                            m!(ErrorCheckingUpdateCaption),
+                            MB::OK,
+                        )?,
+                    };
+                    Ok(())
+                }
+            });
+            Ok(())
+        }
+    });
 }
 
 impl GUIInputs {
     pub(crate) fn events(&self, window: &(impl GuiParent + Clone + 'static)) {
         self.source_folder
             .events(window, m!(SourceFolderChooserCaption));
+        self.source_detect.on().bn_clicked({
+            let window = window.clone();
+            let folder = self.source_folder.combo.clone();
+            let pattern = self.source_pattern.edit.clone();
+            move || {
+                let pattern = Regex::new(&pattern.text())
+                    .unwrap_or_else(|_| Source::default().pattern().clone());
+                match detect::detect_source_folder(&pattern) {
+                    Some(detected) => {
+                        folder.set_text(&detected.to_string_lossy());
+                        notify_edit_change(&folder);
+                        println!("folder detected: {}", detected.display());
+                    }
+                    None => {
+                        window.hwnd().MessageBox(
+                            m!(SourceFolderNotFoundText),
+                            m!(SourceFolderNotFoundCaption),
+                            MB::OK | MB::ICONWARNING,
+                        )?;
+                    }
+                }
+                Ok(())
+            }
+        });
         self.source_pattern.events();
+        self.source_preset.on().cbn_sel_change({
+            let preset = self.source_preset.clone();
+            let folder = self.source_folder.combo.clone();
+            let pattern = self.source_pattern.edit.clone();
+            let header_format = self.source_header_format.clone();
+            move || {
+                if let Some(index) = preset.items().selected_index() {
+                    let preset = &source_presets()[index as usize];
+                    *header_format.borrow_mut() = preset.header_format.to_owned();
+                    folder.set_text(preset.folder);
+                    notify_edit_change(&folder);
+                    pattern.set_text(preset.pattern);
+                }
+                Ok(())
+            }
+        });
+        self.source_exclude_pattern.events();
         self.output_folder
             .events(window, m!(OutputFolderChooserCaption));
         self.output_pattern.events();
+        self.output_pattern_preset.on().cbn_sel_change({
+            let preset = self.output_pattern_preset.clone();
+            let edit = self.output_pattern.edit.clone();
+            move || {
+                if let Some(index) = preset.items().selected_index() {
+                    edit.set_text(output_pattern_presets()[index as usize].1);
+                }
+                Ok(())
+            }
+        });
+        self.schedule_time.events();
+        self.schedule_interval_days.events();
+        self.pictures_folder
+            .events(window, m!(PicturesFolderChooserCaption));
+        self.pictures_output_folder
+            .events(window, m!(PicturesOutputFolderChooserCaption));
+    }
+
+    /// the input controls in the order focused with Tab key
+    fn tab_order(&self) -> Vec<Box<dyn GuiWindow>> {
+        let folder = |block: &FileSelectBlock| -> [Box<dyn GuiWindow>; 2] {
+            [Box::new(block.combo.clone()), Box::new(block.select.clone())]
+        };
+        let mut controls = Vec::<Box<dyn GuiWindow>>::new();
+        controls.extend(folder(&self.source_folder));
+        controls.push(Box::new(self.source_detect.clone()));
+        controls.push(Box::new(self.source_pattern.edit.clone()));
+        controls.push(Box::new(self.source_preset.clone()));
+        controls.push(Box::new(self.source_exclude_pattern.edit.clone()));
+        controls.push(Box::new(self.source_keep_original.clone()));
+        controls.extend(folder(&self.output_folder));
+        controls.push(Box::new(self.output_pattern.edit.clone()));
+        controls.push(Box::new(self.output_pattern_preset.clone()));
+        controls.push(Box::new(self.output_use_utc.clone()));
+        controls.push(Box::new(self.output_use_ctime.clone()));
+        controls.push(Box::new(self.pictures_enabled.clone()));
+        controls.extend(folder(&self.pictures_folder));
+        controls.extend(folder(&self.pictures_output_folder));
+        controls.push(Box::new(self.schedule_time.edit.clone()));
+        controls.push(Box::new(self.schedule_interval_days.edit.clone()));
+        controls.push(Box::new(self.schedule_wake_to_run.clone()));
+        controls.push(Box::new(self.schedule_start_when_available.clone()));
+        controls.push(Box::new(self.schedule_use_run_key.clone()));
+        controls
+    }
+
+    /// the input controls with the text explaining them
+    fn tooltips(&self) -> Vec<(Box<dyn GuiWindow>, &'static str)> {
+        fn tip<T: GuiWindow + Clone + 'static>(
+            control: &T,
+            text: &'static str,
+        ) -> (Box<dyn GuiWindow>, &'static str) {
+            (Box::new(control.clone()), text)
+        }
+        vec![
+            tip(&self.source_folder.combo, m!(TooltipSourceFolder)),
+            tip(&self.source_detect, m!(TooltipDetect)),
+            tip(&self.source_pattern.edit, m!(TooltipSourcePattern)),
+            tip(&self.source_preset, m!(TooltipSourcePreset)),
+            tip(&self.source_exclude_pattern.edit, m!(TooltipExcludePattern)),
+            tip(&self.source_keep_original, m!(TooltipKeepOriginal)),
+            tip(&self.output_folder.combo, m!(TooltipOutputFolder)),
+            tip(&self.output_pattern.edit, m!(TooltipOutputPattern)),
+            tip(&self.output_pattern_preset, m!(TooltipOutputPatternPreset)),
+            tip(&self.output_use_utc, m!(TooltipUseUtc)),
+            tip(&self.output_use_ctime, m!(TooltipUseCreationTime)),
+            tip(&self.pictures_enabled, m!(TooltipRenamePictures)),
+            tip(&self.pictures_folder.combo, m!(TooltipPicturesFolder)),
+            tip(
+                &self.pictures_output_folder.combo,
+                m!(TooltipPicturesOutputFolder),
+            ),
+            tip(&self.schedule_time.edit, m!(TooltipScheduleTime)),
+            tip(
+                &self.schedule_interval_days.edit,
+                m!(TooltipScheduleIntervalDays),
+            ),
+            tip(&self.schedule_wake_to_run, m!(TooltipWakeToRun)),
+            tip(
+                &self.schedule_start_when_available,
+                m!(TooltipStartWhenAvailable),
+            ),
+            tip(&self.schedule_use_run_key, m!(TooltipRunAtLogon)),
+        ]
+    }
+
+    fn folder_blocks(&self) -> [&FileSelectBlock; 4] {
+        [
+            &self.source_folder,
+            &self.output_folder,
+            &self.pictures_folder,
+            &self.pictures_output_folder,
+        ]
+    }
+
+    /// the raw values of inputs to detect unsaved changes
+    fn input_state(&self) -> Vec<String> {
+        let checked = |check_box: &gui::CheckBox| check_box.is_checked().to_string();
+        vec![
+            self.source_folder.text(),
+            self.source_pattern.text(),
+            self.source_header_format.borrow().clone(),
+            self.source_exclude_pattern.text(),
+            checked(&self.source_keep_original),
+            self.output_folder.text(),
+            self.output_pattern.text(),
+            checked(&self.output_use_utc),
+            checked(&self.output_use_ctime),
+            checked(&self.pictures_enabled),
+            self.pictures_folder.text(),
+            self.pictures_output_folder.text(),
+            self.schedule_time.text(),
+            self.schedule_interval_days.text(),
+            checked(&self.schedule_wake_to_run),
+            checked(&self.schedule_start_when_available),
+            checked(&self.schedule_use_run_key),
+        ]
+    }
+
+    /// returns true if any input is changed since last load or save
+    pub(crate) fn is_dirty(&self) -> bool {
+        self.input_state() != *self.saved_state.borrow()
+    }
+
+    /// marks current inputs as saved to the config file
+    pub(crate) fn mark_saved(&self) {
+        *self.saved_state.borrow_mut() = self.input_state();
+    }
+
+    /// calls the function when any input is changed by the user
+    pub(crate) fn on_change(&self, f: impl Fn() -> AnyResult<()> + Clone + 'static) {
+        for folder in self.folder_blocks() {
+            folder.on_change(f.clone());
+        }
+        for edit in [
+            &self.source_pattern.edit,
+            &self.source_exclude_pattern.edit,
+            &self.output_pattern.edit,
+            &self.schedule_time.edit,
+            &self.schedule_interval_days.edit,
+        ] {
+            edit.on().en_change(f.clone());
+        }
+        for check_box in [
+            &self.source_keep_original,
+            &self.output_use_utc,
+            &self.output_use_ctime,
+            &self.pictures_enabled,
+            &self.schedule_wake_to_run,
+            &self.schedule_start_when_available,
+            &self.schedule_use_run_key,
+        ] {
+            check_box.on().bn_clicked(f.clone());
+        }
     }
 
     pub fn load_values_from_config(&self, config: &ConfigFile) {
-        self.source_folder
-            .set_text(config.source().folder().to_string_lossy().as_ref());
+        for folder in self.folder_blocks() {
+            folder.set_recent_folders(config.recent_folders());
+        }
+        self.source_folder.set_text(config.source().folder_raw());
         self.source_pattern
             .set_text(config.source().pattern().as_str());
+        *self.source_header_format.borrow_mut() = config.source().header_format().to_owned();
+        self.source_exclude_pattern
+            .set_text(config.source().exclude_pattern().map_or("", |x| x.as_str()));
         self.source_keep_original
             .set_check_state(check_state(config.source().keep_old()));
-        self.output_folder
-            .set_text(config.output().folder().to_string_lossy().as_ref());
+        self.output_folder.set_text(config.output().folder_raw());
         self.output_pattern
             .set_text(config.output().pattern_as_string().as_str());
         self.output_use_utc
             .set_check_state(check_state(config.output().utc_time()));
         self.output_use_ctime
             .set_check_state(check_state(config.output().file_ctime()));
+        let default_pictures = Pictures::default();
+        let pictures = config.pictures().unwrap_or(&default_pictures);
+        self.pictures_enabled
+            .set_check_state(check_state(config.pictures().is_some()));
+        self.pictures_folder.set_text(pictures.folder_raw());
+        self.pictures_output_folder
+            .set_text(pictures.output_folder_raw());
+        self.schedule_time
+            .set_text(&config.schedule().time_as_string());
+        self.schedule_interval_days
+            .set_text(&config.schedule().interval_days().to_string());
+        self.schedule_wake_to_run
+            .set_check_state(check_state(config.schedule().wake_to_run()));
+        self.schedule_start_when_available
+            .set_check_state(check_state(config.schedule().start_when_available()));
+        self.schedule_use_run_key.set_check_state(check_state(
+            config.schedule().installer() == Installer::RunKey,
+        ));
+        *self.loaded_config.borrow_mut() = config.clone();
     }
 
     pub fn create_config(&self, window: &HWND) -> Result<Option<ConfigFile>, co::ERROR> {
+        let source_folder = match ConfigPath::new(self.source_folder.text()) {
+            Ok(path) => path,
+            Err(e) => {
+                window.MessageBox(
+                    &format!("{}: {}", m!(InvalidSourceFolderText), e),
+                    m!(InvalidSourceFolderCaption),
+                    MB::OK,
+                )?;
+                return Ok(None);
+            }
+        };
         let source_pattern = match Regex::new(&self.source_pattern.text()) {
             Ok(pat) => pat,
             Err(_) => {
@@ -443,9 +1807,21 @@ impl GUIInputs {
                 return Ok(None);
             }
         };
-        let output_pattern = match parse_pattern(&self.output_pattern.text()) {
-            Some(pat) => pat,
-            None => {
+        let source_exclude_pattern =
+            match Source::parse_exclude_pattern(&self.source_exclude_pattern.text()) {
+                Ok(pat) => pat,
+                Err(_) => {
+                    window.MessageBox(
+                        m!(InvalidExcludePatternText),
+                        m!(InvalidExcludePatternCaption),
+                        MB::OK,
+                    )?;
+                    return Ok(None);
+                }
+            };
+        let output_pattern = match Pattern::parse(&self.output_pattern.text()) {
+            Ok(pat) if invalid_pattern_char(pat.items()).is_none() => pat,
+            Ok(_) => {
                 window.MessageBox(
                     m!(InvalidOutputPatternText),
                     m!(InvalidOutputPatternCaption),
@@ -453,25 +1829,140 @@ impl GUIInputs {
                 )?;
                 return Ok(None);
             }
+            Err(e) => {
+                let message = format!(
+                    "{}\n{}: {} ({}: {})",
+                    m!(InvalidOutputPatternText),
+                    m!(UnsupportedSpecifier),
+                    e.specifier,
+                    m!(SpecifierPosition),
+                    e.position,
+                );
+                window.MessageBox(&message, m!(InvalidOutputPatternCaption), MB::OK)?;
+                return Ok(None);
+            }
         };
-        Ok(Some(ConfigFile::new(
-            Source::new(
-                self.source_folder.text().into(),
-                source_pattern,
-                self.source_keep_original.is_checked(),
-            ),
-            Output::new(
-                self.output_folder.text().into(),
-                output_pattern,
-                self.output_use_utc.is_checked(),
-                self.output_use_ctime.is_checked(),
-            ),
-        )))
+        // warns only when the pattern is changed, not to ask every time saving
+        let changed =
+            output_pattern.as_str() != self.loaded_config.borrow().output().pattern_as_string();
+        if let Some(collision) = pattern::collision(output_pattern.items()).filter(|_| changed) {
+            let message = format!(
+                "{}\n{}",
+                match collision {
+                    Collision::Always => m!(CollisionAlways),
+                    Collision::SameDay => m!(CollisionSameDay),
+                    Collision::SameHour => m!(CollisionSameHour),
+                    Collision::SameMinute => m!(CollisionSameMinute),
+                },
+                m!(OutputPatternCollisionText),
+            );
+            if window.MessageBox(&message, m!(OutputPatternCollisionCaption), MB::OKCANCEL)?
+                != DLGID::OK
+            {
+                return Ok(None);
+            }
+        }
+        let output_folder = match ConfigPath::new(self.output_folder.text()) {
+            Ok(path) => path,
+            Err(e) => {
+                window.MessageBox(
+                    &format!("{}: {}", m!(InvalidOutputFolderText), e),
+                    m!(InvalidOutputFolderCaption),
+                    MB::OK,
+                )?;
+                return Ok(None);
+            }
+        };
+        let pictures_folders = if self.pictures_enabled.is_checked() {
+            let folders = ConfigPath::new(self.pictures_folder.text()).and_then(|folder| {
+                Ok((folder, ConfigPath::new(self.pictures_output_folder.text())?))
+            });
+            match folders {
+                Ok(folders) => Some(folders),
+                Err(e) => {
+                    window.MessageBox(
+                        &format!("{}: {}", m!(InvalidPicturesFolderText), e),
+                        m!(InvalidPicturesFolderCaption),
+                        MB::OK,
+                    )?;
+                    return Ok(None);
+                }
+            }
+        } else {
+            None
+        };
+        let schedule = Schedule::parse_time(&self.schedule_time.text()).and_then(|time| {
+            Ok(Schedule::new(
+                time,
+                Schedule::parse_interval_days(&self.schedule_interval_days.text())?,
+                self.schedule_wake_to_run.is_checked(),
+                self.schedule_start_when_available.is_checked(),
+                if self.schedule_use_run_key.is_checked() {
+                    Installer::RunKey
+                } else {
+                    Installer::TaskScheduler
+                },
+            ))
+        });
+        let schedule = match schedule {
+            Ok(schedule) => schedule,
+            Err(_) => {
+                window.MessageBox(
+                    m!(InvalidScheduleText),
+                    m!(InvalidScheduleCaption),
+                    MB::OK,
+                )?;
+                return Ok(None);
+            }
+        };
+        let mut config = self.loaded_config.borrow().clone();
+        config.set_source(Source::new(
+            source_folder,
+            source_pattern,
+            source_exclude_pattern,
+            self.source_header_format.borrow().clone(),
+            self.source_keep_original.is_checked(),
+        ));
+        config.set_output(Output::new(
+            output_folder,
+            output_pattern,
+            self.output_use_utc.is_checked(),
+            self.output_use_ctime.is_checked(),
+        ));
+        config.set_pictures_folders(pictures_folders);
+        config.set_schedule(schedule);
+        // warns only when the overlap is new like the pattern collision
+        let overlaps = |config: &ConfigFile| {
+            let source = config.source();
+            crate::output_overlaps(source, &config.output_for(source))
+        };
+        if overlaps(&config)
+            && !overlaps(&self.loaded_config.borrow())
+            && window.MessageBox(
+                m!(OutputOverlapsSourceText),
+                m!(OutputOverlapsSourceCaption),
+                MB::OKCANCEL,
+            )? != DLGID::OK
+        {
+            return Ok(None);
+        }
+        Ok(Some(config))
     }
 
     pub(crate) fn create_save_config(&self, hwnd: &HWND) -> Result<Option<ConfigFile>, co::ERROR> {
-        if let Some(new_config) = self.create_config(hwnd)? {
+        if let Some(mut new_config) = self.create_config(hwnd)? {
+            let mut folders = vec![self.source_folder.text(), self.output_folder.text()];
+            if self.pictures_enabled.is_checked() {
+                folders.push(self.pictures_folder.text());
+                folders.push(self.pictures_output_folder.text());
+            }
+            new_config.add_recent_folders(folders.iter().map(String::as_str));
             if let Some(_) = save_config_with_error_dialog(&new_config).ok() {
+                for folder in self.folder_blocks() {
+                    folder.set_recent_folders(new_config.recent_folders());
+                }
+                *self.loaded_config.borrow_mut() = new_config.clone();
+                self.mark_saved();
                 return Ok(Some(new_config));
             }
         }
@@ -479,6 +1970,57 @@ impl GUIInputs {
     }
 }
 
+/// the name and output pattern of presets. the pattern can be edited after choosing preset
+/// the log folder, file name and header of the platforms whose logs are like VRChat
+struct SourcePreset {
+    name: &'static str,
+    folder: &'static str,
+    pattern: &'static str,
+    header_format: &'static str,
+}
+
+fn source_presets() -> [SourcePreset; 4] {
+    const FROOX_ENGINE_LOG: &str = r"^.+ - [\d.]+ - \d{4}-\d{2}-\d{2} \d{2}_\d{2}_\d{2}\.log$";
+    [
+        SourcePreset {
+            name: "VRChat",
+            folder: r"{LocalLow}\VRChat\VRChat",
+            pattern: concat!(
+                r"^output_log_(?:\d{4}-\d{2}-\d{2}_)?\d{2}-\d{2}-\d{2}",
+                r"(?P<in_sec_num>\d+)?\.txt$",
+            ),
+            header_format: "%Y.%m.%d %H:%M:%S",
+        },
+        SourcePreset {
+            name: "ChilloutVR",
+            folder: r"{LocalLow}\Alpha Blend Interactive\ChilloutVR",
+            pattern: r"^Player(?:-prev)?\.log$",
+            header_format: "%Y.%m.%d %H:%M:%S",
+        },
+        SourcePreset {
+            name: "NeosVR",
+            folder: r"%ProgramFiles(x86)%\Steam\steamapps\common\NeosVR\Logs",
+            pattern: FROOX_ENGINE_LOG,
+            header_format: "%Y-%m-%d %H:%M:%S",
+        },
+        SourcePreset {
+            name: "Resonite",
+            folder: r"%ProgramFiles(x86)%\Steam\steamapps\common\Resonite\Logs",
+            pattern: FROOX_ENGINE_LOG,
+            header_format: "%Y-%m-%d %H:%M:%S",
+        },
+    ]
+}
+
+fn output_pattern_presets() -> [(&'static str, &'static str); 4] {
+    [
+        (m!(PresetIsoDate), "%Y-%m-%d_%H-%M-%S.txt"),
+        (m!(PresetDateAndWorld), "%Y-%m-%d_%H-%M-%S_{world}.txt"),
+        (m!(PresetYearMonthFolders), r"%Y\%m\output_log_%Y-%m-%d_%H-%M-%S.txt"),
+        (m!(PresetKeepOriginalSuffix), "output_log_%Y-%m-%d_%H-%M-%S{regex:in_sec_num}.txt"),
+    ]
+}
+
 fn add_point(a: (i32, i32), b: (i32, i32)) -> (i32, i32) {
     (a.0 + b.0, a.1 + b.1)
 }
@@ -486,7 +2028,7 @@ fn add_point(a: (i32, i32), b: (i32, i32)) -> (i32, i32) {
 #[derive(Clone)]
 struct FileSelectBlock {
     _label: gui::Label,
-    edit: gui::Edit,
+    combo: gui::ComboBox,
     select: gui::Button,
 }
 
@@ -496,7 +2038,6 @@ impl FileSelectBlock {
     fn new(
         window: &impl GuiParent,
         name: String,
-        initial: String,
         origin: (i32, i32),
         width: u32,
     ) -> FileSelectBlock {
@@ -509,13 +2050,14 @@ impl FileSelectBlock {
                     ..Default::default()
                 },
             ),
-            edit: gui::Edit::new(
+            combo: gui::ComboBox::new(
                 window,
-                gui::EditOpts {
-                    text: initial,
+                gui::ComboBoxOpts {
                     position: add_point(origin, (0, TEXT_HEIGHT)),
                     width: width - 100,
-                    height: 23,
+                    combo_box_style: co::CBS::DROPDOWN | co::CBS::AUTOHSCROLL,
+                    window_ex_style: gui::ComboBoxOpts::default().window_ex_style
+                        | co::WS_EX::ACCEPTFILES,
                     ..Default::default()
                 },
             ),
@@ -533,17 +2075,40 @@ impl FileSelectBlock {
     }
 
     fn text(&self) -> String {
-        self.edit.text()
+        self.combo.text()
     }
 
     pub(crate) fn set_text(&self, text: &str) {
-        self.edit.set_text(text)
+        self.combo.set_text(text)
+    }
+
+    /// replaces the folders in the dropdown. the current text is kept
+    pub(crate) fn set_recent_folders(&self, folders: &[String]) {
+        let text = self.text();
+        self.combo.items().delete_all();
+        self.combo.items().add(folders);
+        self.set_text(&text);
+    }
+
+    /// calls the function when the text is edited or chosen from the dropdown
+    pub(crate) fn on_change(&self, f: impl Fn() -> AnyResult<()> + Clone + 'static) {
+        self.combo.on().cbn_edit_change(f.clone());
+        self.combo.on().cbn_sel_change({
+            let combo = self.combo.clone();
+            move || {
+                // the text is not updated yet when the selection is changed
+                if let Some(text) = combo.items().selected_text() {
+                    combo.set_text(&text);
+                }
+                f()
+            }
+        });
     }
 
     pub(crate) fn events(&self, window: &(impl GuiParent + Clone + 'static), title: &'static str) {
         self.select.on().bn_clicked({
             let window = window.clone();
-            let edit = self.edit.clone();
+            let combo = self.combo.clone();
             move || {
                 let obj = CoCreateInstance::<IFileOpenDialog>(
                     &co::CLSID::FileOpenDialog,
@@ -551,19 +2116,50 @@ impl FileSelectBlock {
                     co::CLSCTX::INPROC_SERVER,
                 )?;
                 obj.SetTitle(&title)?;
-                if let Some(item) = SHCreateItemFromParsingName::<IShellItem>(&edit.text(), Option::<&IBindCtx>::None).ok() {
+                if let Some(item) = SHCreateItemFromParsingName::<IShellItem>(&combo.text(), Option::<&IBindCtx>::None).ok() {
                     obj.SetFolder(&item)?;
                 }
-                obj.SetFileName(&edit.text())?;
+                obj.SetFileName(&combo.text())?;
                 obj.SetOptions(FOS::PICKFOLDERS)?;
                 if obj.Show(window.hwnd())? {
                     let path = obj.GetResult()?.GetDisplayName(co::SIGDN::FILESYSPATH)?;
-                    edit.set_text(&path);
+                    combo.set_text(&path);
+                    notify_edit_change(&combo);
                     println!("folder chosen: {}", path);
                 }
                 Ok(())
             }
         });
+        self.combo.on_subclass().wm_drop_files({
+            let combo = self.combo.clone();
+            move |mut p| {
+                // if a file is dropped, the folder containing it is used
+                if let Some(path) = p.hdrop.iter()?.next() {
+                    let path = PathBuf::from(path?);
+                    let folder = match path.parent() {
+                        Some(parent) if !path.is_dir() => parent,
+                        _ => &path,
+                    };
+                    combo.set_text(&folder.to_string_lossy());
+                    notify_edit_change(&combo);
+                    println!("folder dropped: {}", folder.display());
+                }
+                Ok(())
+            }
+        });
+    }
+}
+
+/// sends CBN_EDITCHANGE to the parent since setting the text doesn't notify it
+fn notify_edit_change(combo: &gui::ComboBox) {
+    if let Ok(parent) = combo.hwnd().GetParent() {
+        parent.SendMessage(wm::Command {
+            event: AccelMenuCtrl::Ctrl(AccelMenuCtrlData {
+                notif_code: co::CBN::EDITCHANGE.into(),
+                ctrl_id: combo.ctrl_id(),
+                ctrl_hwnd: unsafe { combo.hwnd().raw_copy() },
+            }),
+        });
     }
 }
 
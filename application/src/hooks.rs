@@ -0,0 +1,192 @@
+// VRC Log Renamer - the tool to rename logs of VRChat to have date info
+// Copyright (C) 2022 anatawa12
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::config::ConfigFile;
+use crate::{MatchingIter, RenameSummary};
+use anyhow::{Context, Result};
+use std::borrow::Cow;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+/// runs `hooks.before_run`. the error of this hook aborts renaming
+pub(crate) fn run_before_run(config: &ConfigFile) -> Result<()> {
+    if let Some(command) = config.hooks().before_run() {
+        run_hook(command, |_| None, &run_envs(config))
+            .with_context(|| format!("running before_run hook '{}'", command))?;
+    }
+    Ok(())
+}
+
+/// runs `hooks.after_run` with environment variables describing the result of the run.
+/// failure of the hook is reported to stderr.
+pub(crate) fn run_after_run(config: &ConfigFile, summary: &Result<RenameSummary>) {
+    if let Some(command) = config.hooks().after_run() {
+        let mut envs = run_envs(config);
+        match summary {
+            Ok(summary) => {
+                envs.push(("VRC_LOG_RENAMER_RESULT", summary.result_name().to_owned()));
+                envs.push(("VRC_LOG_RENAMER_RENAMED", summary.renamed.len().to_string()));
+                envs.push(("VRC_LOG_RENAMER_SKIPPED", summary.skipped.to_string()));
+                envs.push(("VRC_LOG_RENAMER_ERRORS", summary.errors.len().to_string()));
+            }
+            Err(e) => {
+                envs.push(("VRC_LOG_RENAMER_RESULT", "failed".to_owned()));
+                envs.push(("VRC_LOG_RENAMER_ERROR", e.to_string()));
+            }
+        }
+        if let Some(e) = run_hook(command, |_| None, &envs).err() {
+            warn!("error running after_run hook: {}", e);
+        }
+    }
+}
+
+fn run_envs(config: &ConfigFile) -> Vec<(&'static str, String)> {
+    // the output folders of all sources, separated by `;` like PATH
+    let mut output_folders = Vec::<String>::new();
+    for source in config.sources() {
+        let output = config.output_for(source);
+        let folder = output.folder().to_string_lossy().into_owned();
+        if !output_folders.contains(&folder) {
+            output_folders.push(folder);
+        }
+    }
+    vec![
+        (
+            "VRC_LOG_RENAMER_SOURCE_FOLDER",
+            config.source().folder().to_string_lossy().into_owned(),
+        ),
+        (
+            "VRC_LOG_RENAMER_OUTPUT_FOLDER",
+            config.output().folder().to_string_lossy().into_owned(),
+        ),
+        ("VRC_LOG_RENAMER_OUTPUT_FOLDERS", output_folders.join(";")),
+    ]
+}
+
+/// runs `hooks.after_file` for the copied/moved file.
+/// failure of the hook is reported to stderr and does not fail renaming.
+pub(crate) fn run_after_file(config: &ConfigFile, src: &Path, dst: &Path) {
+    if let Some(command) = config.hooks().after_file() {
+        let src = src.to_string_lossy();
+        let dst = dst.to_string_lossy();
+        let result = run_hook(
+            command,
+            |name| match name {
+                "src" => Some(&src),
+                "dst" => Some(&dst),
+                _ => None,
+            },
+            &[],
+        );
+        if let Some(e) = result.err() {
+            warn!("error running after_file hook for '{}': {}", dst, e);
+        }
+    }
+}
+
+fn run_hook<'a>(
+    command: &str,
+    variables: impl Fn(&str) -> Option<&'a str>,
+    envs: &[(&str, String)],
+) -> io::Result<()> {
+    // placeholders are expanded after splitting so paths with spaces are kept as one argument
+    let mut args = split_command_line(command)
+        .into_iter()
+        .map(|arg| expand_placeholders(&arg, &variables));
+    let program = args
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "hook command is empty"))?;
+    info!("running hook: {}", command);
+    let status = Command::new(program)
+        .args(args)
+        .envs(envs.iter().cloned())
+        .status()?;
+    if !status.success() {
+        return Err(io::Error::other(format!("hook exited with {}", status)));
+    }
+    Ok(())
+}
+
+/// expands `{name}` in the argument like the output pattern, with the modifiers like `|lower`.
+/// unknown names are kept as is
+fn expand_placeholders<'a>(arg: &str, variables: impl Fn(&str) -> Option<&'a str>) -> String {
+    let expander = MatchingIter::new(std::iter::empty(), |name| {
+        variables(name).map(|value| Cow::Owned(value.to_owned()))
+    });
+    expander.process_lit(arg).unwrap_or_else(|| arg.to_owned())
+}
+
+/// splits command line with whitespaces. parts quoted with '"' are kept as one argument
+fn split_command_line(command: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut quoted = false;
+    for c in command.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                in_arg = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_quoted_arguments() {
+        let args = split_command_line(r#"gzip -k "C:\VRChat logs\{dst}" """#);
+        assert_eq!(args, ["gzip", "-k", r"C:\VRChat logs\{dst}", ""]);
+        assert_eq!(split_command_line(r#"a"b c"d  e"#), ["ab cd", "e"]);
+        assert!(split_command_line("  ").is_empty());
+    }
+
+    #[test]
+    fn placeholders() {
+        let variables = |name: &str| match name {
+            "src" => Some(r"C:\logs\output_log.txt"),
+            "dst" => Some(r"D:\archive\Output.txt"),
+            _ => None,
+        };
+        let expand = |arg| expand_placeholders(arg, variables);
+        assert_eq!(expand("--src={src}"), r"--src=C:\logs\output_log.txt");
+        let both = r"C:\logs\output_log.txt>d:\archive\output.txt";
+        assert_eq!(expand("{src}>{dst|lower}"), both);
+        let unknown = r"{unknown}_C:\logs\output_log.txt";
+        assert_eq!(expand("{unknown}_{src}"), unknown);
+        assert_eq!(expand("{src}_{dst"), r"C:\logs\output_log.txt_{dst");
+        assert_eq!(expand("{dst"), "{dst");
+        assert_eq!(expand(""), "");
+    }
+}
@@ -46,8 +46,7 @@ pub enum Message {
     SaveBeforeCloseText,
     SaveBeforeCloseCaption,
 
-    ConfigSavedText,
-    ConfigSavedCaption,
+    ConfigSavedAt,
 
     ResetConfirmText,
     ResetConfirmCaption,
@@ -62,13 +61,131 @@ pub enum Message {
     ErrorInRenameCaption,
 
     RenameSucceedText,
-    RenameSucceedCaption,
 
     InvalidSourcePatternText,
     InvalidSourcePatternCaption,
 
     InvalidOutputPatternText,
     InvalidOutputPatternCaption,
+
+    InvalidSourceFolderText,
+    InvalidSourceFolderCaption,
+
+    InvalidOutputFolderText,
+    InvalidOutputFolderCaption,
+
+    RenamePictures,
+    PathToVrcPicturesFolder,
+    CopyMovePicturesTo,
+    PicturesFolderChooserCaption,
+    PicturesOutputFolderChooserCaption,
+
+    InvalidPicturesFolderText,
+    InvalidPicturesFolderCaption,
+
+    CheckForUpdates,
+
+    UpdateAvailableText,
+    UpdateAvailableCaption,
+
+    NoUpdateText,
+    NoUpdateCaption,
+
+    ErrorCheckingUpdateText,
+    ErrorCheckingUpdateCaption,
+
+    UpdateDownloadedText,
+    UpdateDownloadedCaption,
+
+    CrashedText,
+    CrashedCaption,
+
+    ScheduleTime,
+    ScheduleIntervalDays,
+    NextRun,
+    NextRunUnknown,
+    InvalidScheduleText,
+    InvalidScheduleCaption,
+    WakeToRun,
+    StartWhenAvailable,
+
+    TaskSchedulerErrorText,
+    TaskSchedulerDeniedText,
+    TaskSchedulerErrorCaption,
+
+    RunAtLogonWithRegistry,
+    NextRunAtLogon,
+    RunKeyErrorText,
+    RunKeyErrorCaption,
+
+    PresetIsoDate,
+    PresetDateAndWorld,
+    PresetYearMonthFolders,
+    PresetKeepOriginalSuffix,
+    SomeFilesFailedText,
+    CopyDetails,
+    CloseButton,
+    AboutButton,
+    AboutCaption,
+    OpenRepository,
+    LicensesLabel,
+    TooltipSourceFolder,
+    TooltipSourcePattern,
+    TooltipKeepOriginal,
+    TooltipOutputFolder,
+    TooltipOutputPattern,
+    TooltipOutputPatternPreset,
+    TooltipUseUtc,
+    TooltipUseCreationTime,
+    TooltipRenamePictures,
+    TooltipPicturesFolder,
+    TooltipPicturesOutputFolder,
+    TooltipScheduleTime,
+    TooltipScheduleIntervalDays,
+    TooltipWakeToRun,
+    TooltipStartWhenAvailable,
+    TooltipRunAtLogon,
+    ConfirmRunText,
+    ConfirmRunCaption,
+    MatchingFiles,
+    TotalSize,
+    FreeSpace,
+    RequiredSpace,
+    NotEnoughSpaceText,
+    NotEnoughSpaceCaption,
+    FileColumn,
+    SizeColumn,
+    CancelButton,
+    VrcLogExcludePattern,
+    InvalidExcludePatternText,
+    InvalidExcludePatternCaption,
+    TooltipExcludePattern,
+    RenameLogCaption,
+    DetectButton,
+    SourceFolderNotFoundText,
+    SourceFolderNotFoundCaption,
+    TooltipDetect,
+    VrchatRunning,
+    StatisticsButton,
+    StatisticsCaption,
+    StatisticsSessions,
+    StatisticsTotalHours,
+    StatisticsHoursPerWeek,
+    StatisticsMostVisitedWorlds,
+    UnsupportedSpecifier,
+    SpecifierPosition,
+    CollisionAlways,
+    CollisionSameDay,
+    CollisionSameHour,
+    CollisionSameMinute,
+    OutputPatternCollisionText,
+    OutputPatternCollisionCaption,
+    TooltipSourcePreset,
+    OutputOverlapsSourceText,
+    OutputOverlapsSourceCaption,
+    BuildCommitLabel,
+    BuildDateLabel,
+    BuildTargetLabel,
 }
 
 macro_rules! m {
@@ -91,6 +208,10 @@ pub fn init_i18n() {
         .unwrap_or(locale.as_str())
     {
         "ja" => localization_ja(&mut mapping),
+        "zh" if is_traditional_chinese(&locale) => localization_zh_hant(&mut mapping),
+        "zh" => localization_zh_hans(&mut mapping),
+        "ko" => localization_ko(&mut mapping),
+        "de" => localization_de(&mut mapping),
         _ => {}
     }
 
@@ -99,6 +220,21 @@ pub fn init_i18n() {
     }
 }
 
+/// returns true if the chinese locale uses traditional chinese.
+/// script subtag (zh-Hant-TW, zh-Hans-HK) takes precedence over region (zh-TW, zh-HK, zh-MO)
+fn is_traditional_chinese(locale: &str) -> bool {
+    let mut traditional = false;
+    for subtag in locale.split('-').skip(1) {
+        match subtag {
+            "Hant" => return true,
+            "Hans" => return false,
+            "TW" | "HK" | "MO" => traditional = true,
+            _ => {}
+        }
+    }
+    traditional
+}
+
 fn get_current_locale() -> String {
     use windows::Win32::Globalization::GetUserDefaultLocaleName;
     use windows::Win32::System::SystemServices::LOCALE_NAME_MAX_LENGTH;
@@ -147,8 +283,7 @@ pub fn get_message(message: Message) -> &'static str {
         SaveBeforeCloseText => "Save Config before Close?",
         SaveBeforeCloseCaption => "Save?",
 
-        ConfigSavedText => "Config Saved!",
-        ConfigSavedCaption => "Config Saved!",
+        ConfigSavedAt => "Saved",
 
         ResetConfirmText => {
             "Are you sure want to reset config to default?\nYou cannot undo this operation"
@@ -165,13 +300,135 @@ pub fn get_message(message: Message) -> &'static str {
         ErrorInRenameCaption => "Error!",
 
         RenameSucceedText => "Renaming Log Succeed!",
-        RenameSucceedCaption => "Succeed!",
 
         InvalidSourcePatternText => "Cannot save the config: Log file Pattern is not valid",
         InvalidSourcePatternCaption => "Error",
 
         InvalidOutputPatternText => "Cannot save the config: Output File Pattern is not valid",
         InvalidOutputPatternCaption => "Error",
+
+        InvalidSourceFolderText => "Cannot save the config: Path to VRC Log Folder is not valid",
+        InvalidSourceFolderCaption => "Error",
+
+        InvalidOutputFolderText => "Cannot save the config: Output folder is not valid",
+        InvalidOutputFolderCaption => "Error",
+
+        RenamePictures => "Rename VRChat Pictures",
+        PathToVrcPicturesFolder => "Path to VRChat Pictures Folder:",
+        CopyMovePicturesTo => "Copy/Move Pictures to:",
+        PicturesFolderChooserCaption => "VRChat Pictures Folder",
+        PicturesOutputFolderChooserCaption => "Pictures Output Folder",
+
+        InvalidPicturesFolderText => "Cannot save the config: Pictures folder is not valid",
+        InvalidPicturesFolderCaption => "Error",
+
+        CheckForUpdates => "Check for Updates",
+
+        UpdateAvailableText => "New version is available. Download it?\nNew version",
+        UpdateAvailableCaption => "Update Available",
+
+        NoUpdateText => "You are using the latest version.",
+        NoUpdateCaption => "No Update",
+
+        ErrorCheckingUpdateText => "Error updating VRC Log Renamer",
+        ErrorCheckingUpdateCaption => "Error",
+
+        UpdateDownloadedText => "New version is downloaded to",
+        UpdateDownloadedCaption => "Downloaded!",
+
+        CrashedText => {
+            "VRC Log Renamer crashed unexpectedly.\nDo you want to open the crash report?"
+        }
+        CrashedCaption => "Crashed",
+
+        ScheduleTime => "Run the task at (HH:MM)",
+        ScheduleIntervalDays => "Run the task every N days",
+        NextRun => "Next run",
+        NextRunUnknown => "unknown",
+        InvalidScheduleText => {
+            "Schedule time must be HH:MM and interval must be a positive number of days"
+        }
+        InvalidScheduleCaption => "Invalid Schedule",
+        WakeToRun => "Wake the computer to run",
+        StartWhenAvailable => "Run missed schedules",
+
+        TaskSchedulerErrorText => "Error accessing Task Scheduler",
+        TaskSchedulerDeniedText => "Access to Task Scheduler is denied. Task Scheduler may be restricted by the policy of this computer.\nYou can run the renamer at logon instead of Task Scheduler.",
+        TaskSchedulerErrorCaption => "Task Scheduler Error",
+
+        RunAtLogonWithRegistry => "Run at logon with registry instead of Task Scheduler",
+        NextRunAtLogon => "at logon",
+        RunKeyErrorText => "Error writing the Run key in the registry",
+        RunKeyErrorCaption => "Registry Error",
+
+        PresetIsoDate => "ISO date",
+        PresetDateAndWorld => "Date + world name",
+        PresetYearMonthFolders => "Year/Month folders",
+        PresetKeepOriginalSuffix => "Keep original suffix",
+        SomeFilesFailedText => "Some log files could not be renamed",
+        CopyDetails => "Copy details",
+        CloseButton => "Close",
+        AboutButton => "About",
+        AboutCaption => "About VRC Log Renamer",
+        OpenRepository => "Open in Browser",
+        LicensesLabel => "Licenses of this app and its dependencies",
+        TooltipSourceFolder => "The folder VRChat writes log files to.\nExample: %LOCALAPPDATA%Low\\VRChat\\VRChat",
+        TooltipSourcePattern => "Regular expression matching the names of log files to rename.\nExample: ^output_log_\\d{2}-\\d{2}-\\d{2}\\.txt$",
+        TooltipKeepOriginal => "Copy the files instead of moving them, so the original files are kept where they are.",
+        TooltipOutputFolder => "The folder renamed log files are copied or moved to.\nExample: %USERPROFILE%\\Documents\\VRChat Logs",
+        TooltipOutputPattern => "The name of renamed files in chrono's strftime format. Use \\ to make folders.\n%Y-%m-%d_%H-%M-%S: the date and time, {world}: the world name, {regex:name}: the group of the log file pattern\nExample: output_log_%Y-%m-%d_%H-%M-%S.txt",
+        TooltipOutputPatternPreset => "Fill the output file pattern with a commonly used one.",
+        TooltipUseUtc => "Use the time in UTC instead of the local time for the names of renamed files.\nExample: 12:00 in Japan (UTC+9) becomes 03-00-00",
+        TooltipUseCreationTime => "Use the creation time of the file instead of the time in the file name.",
+        TooltipRenamePictures => "Also rename the pictures taken in VRChat, adding the world name.",
+        TooltipPicturesFolder => "The folder VRChat saves pictures to.\nExample: %USERPROFILE%\\Pictures\\VRChat",
+        TooltipPicturesOutputFolder => "The folder renamed pictures are copied or moved to.\nExample: %USERPROFILE%\\Pictures\\VRChat\\renamed",
+        TooltipScheduleTime => "The time of day the scheduled task runs, in 24-hour format.\nExample: 04:30",
+        TooltipScheduleIntervalDays => "How often the scheduled task runs, in days.\nExample: 7 to run weekly",
+        TooltipWakeToRun => "Wake the computer from sleep to run the scheduled task.",
+        TooltipStartWhenAvailable => "Run the task as soon as possible if the scheduled time was missed, e.g. because the computer was off.",
+        TooltipRunAtLogon => "Run the renamer each time you sign in using the Run key of the registry. Use this if Task Scheduler is not available.",
+        ConfirmRunText => "The checked files will be copied or moved. Uncheck the files to skip.",
+        ConfirmRunCaption => "Confirm",
+        MatchingFiles => "Matching files",
+        TotalSize => "Total size",
+        FreeSpace => "Free space",
+        RequiredSpace => "Required space",
+        NotEnoughSpaceText => "Cannot start renaming: there is not enough free space on the destination drive.",
+        NotEnoughSpaceCaption => "Not Enough Space",
+        FileColumn => "File",
+        SizeColumn => "Size",
+        CancelButton => "Cancel",
+        VrcLogExcludePattern => "Exclude Log Files Matching (regex, optional):",
+        InvalidExcludePatternText => "Cannot save the config: Exclude Pattern is not valid",
+        InvalidExcludePatternCaption => "Error",
+        TooltipExcludePattern => "Log files matching this regular expression are not renamed even if they match the log file pattern. Leave empty to rename all of them.\nExample: ^output_log_00-00-00\\.txt$",
+        RenameLogCaption => "Renaming",
+        DetectButton => "Detect",
+        SourceFolderNotFoundText => "No folder with VRChat logs was found in the standard folder, the Steam libraries or the Oculus library.\nPlease select the folder manually.",
+        SourceFolderNotFoundCaption => "Not Found",
+        TooltipDetect => "Finds the folder with VRChat logs in the standard LocalLow folder, the Steam libraries and the Oculus library.",
+        VrchatRunning => "VRChat is currently running — newest log will be skipped",
+        StatisticsButton => "Statistics",
+        StatisticsCaption => "Play Time Statistics",
+        StatisticsSessions => "Sessions",
+        StatisticsTotalHours => "Total hours",
+        StatisticsHoursPerWeek => "Hours per week",
+        StatisticsMostVisitedWorlds => "Most visited worlds",
+        UnsupportedSpecifier => "Unsupported specifier",
+        SpecifierPosition => "position",
+        CollisionAlways => "All logs will get the same file name since Output File Pattern has no date or time.",
+        CollisionSameDay => "The logs launched on the same day will get the same file name.",
+        CollisionSameHour => "The logs launched in the same hour will get the same file name.",
+        CollisionSameMinute => "The logs launched in the same minute will get the same file name.",
+        OutputPatternCollisionText => "Add {seq} or finer specifiers like %S to the pattern. Save anyway?",
+        OutputPatternCollisionCaption => "Warning",
+        TooltipSourcePreset => "Fill the log folder and file pattern for VRChat or other social VR platforms.",
+        OutputOverlapsSourceText => "The renamed logs are put in the log folder with names matching the log file pattern, so they will be found as logs again. Save anyway?",
+        OutputOverlapsSourceCaption => "Output folder overlaps",
+        BuildCommitLabel => "Commit",
+        BuildDateLabel => "Build date",
+        BuildTargetLabel => "Target",
     }
 }
 
@@ -215,8 +472,7 @@ fn localization_ja(mapping: &mut HashMap<Message, &str>) {
     mapping.insert(SaveBeforeCloseText, "閉じる前に保存しますか");
     mapping.insert(SaveBeforeCloseCaption, "閉じる前に保存しますか");
 
-    mapping.insert(ConfigSavedText, "コンフィグが保存されました");
-    mapping.insert(ConfigSavedCaption, "コンフィグが保存されました");
+    mapping.insert(ConfigSavedAt, "保存しました");
 
     mapping.insert(ResetConfirmText, "本当に初期化しますか");
     mapping.insert(ResetConfirmCaption, "確認");
@@ -234,7 +490,6 @@ fn localization_ja(mapping: &mut HashMap<Message, &str>) {
     mapping.insert(ErrorInRenameCaption, "エラー");
 
     mapping.insert(RenameSucceedText, "成功しました");
-    mapping.insert(RenameSucceedCaption, "成功");
 
     mapping.insert(
         InvalidSourcePatternText,
@@ -247,4 +502,1293 @@ fn localization_ja(mapping: &mut HashMap<Message, &str>) {
         "設定の保存に失敗しました: ログファイルの出力形式が不正です",
     );
     mapping.insert(InvalidOutputPatternCaption, "エラー");
+
+    mapping.insert(
+        InvalidSourceFolderText,
+        "設定の保存に失敗しました: VRCのログフォルダのパスが不正です",
+    );
+    mapping.insert(InvalidSourceFolderCaption, "エラー");
+
+    mapping.insert(
+        InvalidOutputFolderText,
+        "設定の保存に失敗しました: ログファイルの移動先が不正です",
+    );
+    mapping.insert(InvalidOutputFolderCaption, "エラー");
+
+    mapping.insert(RenamePictures, "VRChatの写真もリネームする");
+    mapping.insert(PathToVrcPicturesFolder, "VRChatの写真フォルダのパス:");
+    mapping.insert(CopyMovePicturesTo, "写真の移動先:");
+    mapping.insert(PicturesFolderChooserCaption, "VRChatの写真フォルダ");
+    mapping.insert(PicturesOutputFolderChooserCaption, "写真の移動先");
+    mapping.insert(
+        InvalidPicturesFolderText,
+        "設定の保存に失敗しました: 写真フォルダのパスが不正です",
+    );
+    mapping.insert(InvalidPicturesFolderCaption, "エラー");
+
+    mapping.insert(CheckForUpdates, "アップデートを確認");
+
+    mapping.insert(
+        UpdateAvailableText,
+        "新しいバージョンがあります。ダウンロードしますか?\n新しいバージョン",
+    );
+    mapping.insert(UpdateAvailableCaption, "アップデート");
+
+    mapping.insert(NoUpdateText, "最新バージョンを使用しています。");
+    mapping.insert(NoUpdateCaption, "アップデートなし");
+
+    mapping.insert(ErrorCheckingUpdateText, "アップデートに失敗しました");
+    mapping.insert(ErrorCheckingUpdateCaption, "エラー");
+
+    mapping.insert(
+        UpdateDownloadedText,
+        "新しいバージョンをダウンロードしました",
+    );
+    mapping.insert(UpdateDownloadedCaption, "ダウンロード完了");
+
+    mapping.insert(
+        CrashedText,
+        "VRC Log Renamerが予期せず終了しました。\nクラッシュレポートを開きますか?",
+    );
+    mapping.insert(CrashedCaption, "クラッシュ");
+
+    mapping.insert(ScheduleTime, "実行時刻 (HH:MM)");
+    mapping.insert(ScheduleIntervalDays, "実行間隔 (日)");
+    mapping.insert(NextRun, "次回実行");
+    mapping.insert(NextRunUnknown, "不明");
+    mapping.insert(
+        InvalidScheduleText,
+        "実行時刻はHH:MM形式、実行間隔は正の日数で指定してください",
+    );
+    mapping.insert(InvalidScheduleCaption, "スケジュールが不正です");
+    mapping.insert(WakeToRun, "スリープから復帰して実行");
+    mapping.insert(StartWhenAvailable, "実行されなかった場合すぐ実行");
+
+    mapping.insert(
+        TaskSchedulerErrorText,
+        "Task Schedulerへのアクセス中にエラーが発生しました",
+    );
+    mapping.insert(TaskSchedulerDeniedText, "Task Schedulerへのアクセスが拒否されました。このコンピューターのポリシーでTask Schedulerが制限されている可能性があります。\nTask Schedulerの代わりにログオン時に実行することができます。");
+    mapping.insert(TaskSchedulerErrorCaption, "Task Schedulerエラー");
+
+    mapping.insert(
+        RunAtLogonWithRegistry,
+        "Task Schedulerの代わりにレジストリでログオン時に実行",
+    );
+    mapping.insert(NextRunAtLogon, "ログオン時");
+    mapping.insert(
+        RunKeyErrorText,
+        "レジストリのRunキーへの書き込み中にエラーが発生しました",
+    );
+    mapping.insert(RunKeyErrorCaption, "レジストリエラー");
+
+    mapping.insert(PresetIsoDate, "ISO日付");
+    mapping.insert(PresetDateAndWorld, "日付 + ワールド名");
+    mapping.insert(PresetYearMonthFolders, "年/月フォルダ");
+    mapping.insert(PresetKeepOriginalSuffix, "元の接尾辞を保持");
+    mapping.insert(
+        SomeFilesFailedText,
+        "一部のログファイルの処理に失敗しました",
+    );
+    mapping.insert(CopyDetails, "詳細をコピー");
+    mapping.insert(CloseButton, "閉じる");
+    mapping.insert(AboutButton, "このアプリについて");
+    mapping.insert(AboutCaption, "VRC Log Renamerについて");
+    mapping.insert(OpenRepository, "ブラウザで開く");
+    mapping.insert(LicensesLabel, "このアプリと依存ライブラリのライセンス");
+    mapping.insert(
+        TooltipSourceFolder,
+        "VRChatがログファイルを書き込むフォルダです。\n例: %LOCALAPPDATA%Low\\VRChat\\VRChat",
+    );
+    mapping.insert(TooltipSourcePattern, "名前を変更するログファイルの名前にマッチする正規表現です。\n例: ^output_log_\\d{2}-\\d{2}-\\d{2}\\.txt$");
+    mapping.insert(
+        TooltipKeepOriginal,
+        "ファイルを移動せずにコピーし、元のファイルをそのまま残します。",
+    );
+    mapping.insert(TooltipOutputFolder, "名前を変更したログファイルのコピー先または移動先のフォルダです。\n例: %USERPROFILE%\\Documents\\VRChat Logs");
+    mapping.insert(TooltipOutputPattern, "chronoのstrftime形式で書いた変更後のファイル名です。\\でフォルダを作れます。\n%Y-%m-%d_%H-%M-%S: 日時, {world}: ワールド名, {regex:name}: ログファイルパターンのグループ\n例: output_log_%Y-%m-%d_%H-%M-%S.txt");
+    mapping.insert(
+        TooltipOutputPatternPreset,
+        "よく使われるパターンを出力ファイルパターンに入力します。",
+    );
+    mapping.insert(TooltipUseUtc, "変更後のファイル名にローカル時刻ではなくUTCの時刻を使います。\n例: 日本時間(UTC+9)の12:00は03-00-00になります");
+    mapping.insert(
+        TooltipUseCreationTime,
+        "ファイル名の時刻ではなく、ファイルの作成日時を使います。",
+    );
+    mapping.insert(
+        TooltipRenamePictures,
+        "VRChatで撮影した写真もワールド名を付けて名前を変更します。",
+    );
+    mapping.insert(
+        TooltipPicturesFolder,
+        "VRChatが写真を保存するフォルダです。\n例: %USERPROFILE%\\Pictures\\VRChat",
+    );
+    mapping.insert(TooltipPicturesOutputFolder, "名前を変更した写真のコピー先または移動先のフォルダです。\n例: %USERPROFILE%\\Pictures\\VRChat\\renamed");
+    mapping.insert(
+        TooltipScheduleTime,
+        "スケジュールされたタスクを実行する時刻です。24時間表記で入力します。\n例: 04:30",
+    );
+    mapping.insert(
+        TooltipScheduleIntervalDays,
+        "スケジュールされたタスクを何日ごとに実行するかです。\n例: 毎週実行するには7",
+    );
+    mapping.insert(
+        TooltipWakeToRun,
+        "スケジュールされたタスクを実行するためにコンピューターをスリープから復帰させます。",
+    );
+    mapping.insert(TooltipStartWhenAvailable, "コンピューターの電源が切れていたなどで予定時刻を逃した場合、できるだけ早くタスクを実行します。");
+    mapping.insert(TooltipRunAtLogon, "レジストリのRunキーを使って、サインインするたびにリネーマーを実行します。タスクスケジューラが使えない場合に使ってください。");
+    mapping.insert(
+        ConfirmRunText,
+        "チェックしたファイルをコピーまたは移動します。スキップするファイルのチェックを外してください。",
+    );
+    mapping.insert(ConfirmRunCaption, "確認");
+    mapping.insert(MatchingFiles, "対象のファイル");
+    mapping.insert(TotalSize, "合計サイズ");
+    mapping.insert(FreeSpace, "空き容量");
+    mapping.insert(RequiredSpace, "必要な容量");
+    mapping.insert(
+        NotEnoughSpaceText,
+        "名前の変更を開始できません: 出力先のドライブの空き容量が足りません。",
+    );
+    mapping.insert(NotEnoughSpaceCaption, "空き容量不足");
+    mapping.insert(FileColumn, "ファイル");
+    mapping.insert(SizeColumn, "サイズ");
+    mapping.insert(CancelButton, "キャンセル");
+    mapping.insert(
+        VrcLogExcludePattern,
+        "除外するログファイルのパターン (正規表現, 任意):",
+    );
+    mapping.insert(
+        InvalidExcludePatternText,
+        "設定を保存できません: 除外パターンが不正です",
+    );
+    mapping.insert(InvalidExcludePatternCaption, "エラー");
+    mapping.insert(TooltipExcludePattern, "この正規表現にマッチするログファイルは、ログファイルパターンにマッチしても名前を変更しません。空欄にするとすべて変更します。\n例: ^output_log_00-00-00\\.txt$");
+    mapping.insert(RenameLogCaption, "名前変更中");
+    mapping.insert(DetectButton, "検出");
+    mapping.insert(SourceFolderNotFoundText, "標準のフォルダ、Steamライブラリ、Oculusライブラリの中にVRChatのログのあるフォルダが見つかりませんでした。\n手動でフォルダを選択してください。");
+    mapping.insert(SourceFolderNotFoundCaption, "見つかりません");
+    mapping.insert(TooltipDetect, "標準のLocalLowフォルダ、Steamライブラリ、OculusライブラリからVRChatのログのあるフォルダを探します。");
+    mapping.insert(
+        VrchatRunning,
+        "VRChatが起動中です — 最新のログはスキップされます",
+    );
+    mapping.insert(StatisticsButton, "統計");
+    mapping.insert(StatisticsCaption, "プレイ時間の統計");
+    mapping.insert(StatisticsSessions, "セッション数");
+    mapping.insert(StatisticsTotalHours, "合計時間");
+    mapping.insert(StatisticsHoursPerWeek, "週ごとの時間");
+    mapping.insert(StatisticsMostVisitedWorlds, "よく訪れたワールド");
+    mapping.insert(UnsupportedSpecifier, "使用できない指定子");
+    mapping.insert(SpecifierPosition, "位置");
+    mapping.insert(
+        CollisionAlways,
+        "出力形式に日付も時刻もないため、すべてのログが同じファイル名になります。",
+    );
+    mapping.insert(
+        CollisionSameDay,
+        "同じ日に起動したログは同じファイル名になります。",
+    );
+    mapping.insert(
+        CollisionSameHour,
+        "同じ時間帯に起動したログは同じファイル名になります。",
+    );
+    mapping.insert(
+        CollisionSameMinute,
+        "同じ分に起動したログは同じファイル名になります。",
+    );
+    mapping.insert(
+        OutputPatternCollisionText,
+        "{seq} や %S のようなより細かい指定子を追加してください。このまま保存しますか？",
+    );
+    mapping.insert(OutputPatternCollisionCaption, "警告");
+    mapping.insert(
+        TooltipSourcePreset,
+        "VRChatや他のソーシャルVRのログフォルダとファイル形式を入力します。",
+    );
+    mapping.insert(OutputOverlapsSourceText, "リネームされたログはログのフォルダに置かれ、ログファイルのパターンに一致するため、再びログとして見つかります。このまま保存しますか？");
+    mapping.insert(OutputOverlapsSourceCaption, "出力フォルダの重複");
+    mapping.insert(BuildCommitLabel, "コミット");
+    mapping.insert(BuildDateLabel, "ビルド日");
+    mapping.insert(BuildTargetLabel, "ターゲット");
+}
+
+fn localization_zh_hans(mapping: &mut HashMap<Message, &str>) {
+    mapping.insert(ErrorReadingConfigFile, "读取配置文件时出错");
+    mapping.insert(ClickOKToDiscordAndContinue, "点击确定以放弃配置并继续。");
+    mapping.insert(ErrorLoadingConfigFileCaption, "错误");
+
+    mapping.insert(ErrorWritingConfigFileText, "写入配置文件时出错");
+    mapping.insert(ErrorWritingConfigFileCaption, "错误");
+
+    mapping.insert(PathToVrcLogFolder, "VRC 日志文件夹路径:");
+    mapping.insert(VrcLogFilePattern, "VRC 日志文件名模式(正则表达式):");
+    mapping.insert(KeepOriginal, "保留原文件");
+    mapping.insert(CopyMoveLogFileTo, "日志文件复制/移动到:");
+    mapping.insert(OutputFilePattern, "输出文件名格式(chrono 的 strftime):");
+    mapping.insert(UseUcForFileName, "日志文件名使用 UTC 时间");
+    mapping.insert(UseFileCreationTime, "使用文件创建时间");
+    mapping.insert(SaveConfig, "保存配置");
+    mapping.insert(ResetConfig, "重置配置");
+    mapping.insert(ExecuteNow, "立即执行");
+    mapping.insert(InstallToTaskScheduler, "注册到任务计划程序");
+    mapping.insert(UninstallFromTaskScheduler, "从任务计划程序中移除");
+    mapping.insert(SelectInGuiButtonText, "选择文件夹");
+
+    mapping.insert(SourceFolderChooserCaption, "VRC 日志文件夹");
+    mapping.insert(OutputFolderChooserCaption, "输出文件夹");
+
+    mapping.insert(SaveBeforeCloseText, "关闭前保存配置吗?");
+    mapping.insert(SaveBeforeCloseCaption, "保存?");
+
+    mapping.insert(ConfigSavedAt, "已保存");
+
+    mapping.insert(
+        ResetConfirmText,
+        "确定要将配置重置为默认值吗?\n此操作无法撤销",
+    );
+    mapping.insert(ResetConfirmCaption, "确认");
+
+    mapping.insert(InstallSucceedText, "已成功注册到任务计划程序!");
+    mapping.insert(InstallSucceedCaption, "成功");
+
+    mapping.insert(UninstallSucceedText, "已成功从任务计划程序中移除!");
+    mapping.insert(UninstallSucceedCaption, "成功");
+
+    mapping.insert(ErrorInRenameText, "重命名日志时出错");
+    mapping.insert(ErrorInRenameCaption, "错误");
+
+    mapping.insert(RenameSucceedText, "日志重命名成功!");
+
+    mapping.insert(
+        InvalidSourcePatternText,
+        "无法保存配置: VRC 日志文件名模式无效",
+    );
+    mapping.insert(InvalidSourcePatternCaption, "错误");
+
+    mapping.insert(InvalidOutputPatternText, "无法保存配置: 输出文件名格式无效");
+    mapping.insert(InvalidOutputPatternCaption, "错误");
+
+    mapping.insert(
+        InvalidSourceFolderText,
+        "无法保存配置: VRC 日志文件夹路径无效",
+    );
+    mapping.insert(InvalidSourceFolderCaption, "错误");
+
+    mapping.insert(InvalidOutputFolderText, "无法保存配置: 输出文件夹无效");
+    mapping.insert(InvalidOutputFolderCaption, "错误");
+
+    mapping.insert(RenamePictures, "同时重命名 VRChat 照片");
+    mapping.insert(PathToVrcPicturesFolder, "VRChat 照片文件夹路径:");
+    mapping.insert(CopyMovePicturesTo, "照片复制/移动到:");
+    mapping.insert(PicturesFolderChooserCaption, "VRChat 照片文件夹");
+    mapping.insert(PicturesOutputFolderChooserCaption, "照片输出文件夹");
+    mapping.insert(InvalidPicturesFolderText, "无法保存配置: 照片文件夹无效");
+    mapping.insert(InvalidPicturesFolderCaption, "错误");
+
+    mapping.insert(CheckForUpdates, "检查更新");
+
+    mapping.insert(UpdateAvailableText, "有新版本可用。是否下载?\n新版本");
+    mapping.insert(UpdateAvailableCaption, "有可用更新");
+
+    mapping.insert(NoUpdateText, "您正在使用最新版本。");
+    mapping.insert(NoUpdateCaption, "无更新");
+
+    mapping.insert(ErrorCheckingUpdateText, "更新 VRC Log Renamer 时出错");
+    mapping.insert(ErrorCheckingUpdateCaption, "错误");
+
+    mapping.insert(UpdateDownloadedText, "新版本已下载到");
+    mapping.insert(UpdateDownloadedCaption, "下载完成!");
+
+    mapping.insert(CrashedText, "VRC Log Renamer 意外崩溃。\n是否打开崩溃报告?");
+    mapping.insert(CrashedCaption, "崩溃");
+
+    mapping.insert(ScheduleTime, "运行时间 (HH:MM)");
+    mapping.insert(ScheduleIntervalDays, "运行间隔 (天)");
+    mapping.insert(NextRun, "下次运行");
+    mapping.insert(NextRunUnknown, "未知");
+    mapping.insert(
+        InvalidScheduleText,
+        "运行时间必须为 HH:MM 格式, 间隔必须为正整数天",
+    );
+    mapping.insert(InvalidScheduleCaption, "计划无效");
+    mapping.insert(WakeToRun, "唤醒计算机运行");
+    mapping.insert(StartWhenAvailable, "错过时尽快运行");
+
+    mapping.insert(TaskSchedulerErrorText, "访问任务计划程序时出错");
+    mapping.insert(
+        TaskSchedulerDeniedText,
+        "访问任务计划程序被拒绝。此计算机的策略可能限制了任务计划程序。\n您可以改为在登录时运行。",
+    );
+    mapping.insert(TaskSchedulerErrorCaption, "任务计划程序错误");
+
+    mapping.insert(
+        RunAtLogonWithRegistry,
+        "使用注册表在登录时运行, 而不是任务计划程序",
+    );
+    mapping.insert(NextRunAtLogon, "登录时");
+    mapping.insert(RunKeyErrorText, "写入注册表 Run 键时出错");
+    mapping.insert(RunKeyErrorCaption, "注册表错误");
+
+    mapping.insert(PresetIsoDate, "ISO 日期");
+    mapping.insert(PresetDateAndWorld, "日期 + 世界名称");
+    mapping.insert(PresetYearMonthFolders, "年/月文件夹");
+    mapping.insert(PresetKeepOriginalSuffix, "保留原始后缀");
+    mapping.insert(SomeFilesFailedText, "部分日志文件重命名失败");
+    mapping.insert(CopyDetails, "复制详细信息");
+    mapping.insert(CloseButton, "关闭");
+    mapping.insert(AboutButton, "关于");
+    mapping.insert(AboutCaption, "关于 VRC Log Renamer");
+    mapping.insert(OpenRepository, "在浏览器中打开");
+    mapping.insert(LicensesLabel, "本应用及其依赖项的许可证");
+    mapping.insert(
+        TooltipSourceFolder,
+        "VRChat 写入日志文件的文件夹。\n示例: %LOCALAPPDATA%Low\\VRChat\\VRChat",
+    );
+    mapping.insert(
+        TooltipSourcePattern,
+        "匹配要重命名的日志文件名的正则表达式。\n示例: ^output_log_\\d{2}-\\d{2}-\\d{2}\\.txt$",
+    );
+    mapping.insert(
+        TooltipKeepOriginal,
+        "复制文件而不是移动文件，保留原始文件。",
+    );
+    mapping.insert(
+        TooltipOutputFolder,
+        "重命名后的日志文件复制或移动到的文件夹。\n示例: %USERPROFILE%\\Documents\\VRChat Logs",
+    );
+    mapping.insert(TooltipOutputPattern, "使用 chrono 的 strftime 格式的重命名后文件名。使用 \\ 创建文件夹。\n%Y-%m-%d_%H-%M-%S: 日期和时间, {world}: 世界名称, {regex:name}: 日志文件模式的分组\n示例: output_log_%Y-%m-%d_%H-%M-%S.txt");
+    mapping.insert(TooltipOutputPatternPreset, "用常用模式填写输出文件模式。");
+    mapping.insert(
+        TooltipUseUtc,
+        "重命名后的文件名使用 UTC 时间而不是本地时间。\n示例: 北京时间 (UTC+8) 12:00 变为 04-00-00",
+    );
+    mapping.insert(
+        TooltipUseCreationTime,
+        "使用文件的创建时间而不是文件名中的时间。",
+    );
+    mapping.insert(
+        TooltipRenamePictures,
+        "同时重命名在 VRChat 中拍摄的照片，并添加世界名称。",
+    );
+    mapping.insert(
+        TooltipPicturesFolder,
+        "VRChat 保存照片的文件夹。\n示例: %USERPROFILE%\\Pictures\\VRChat",
+    );
+    mapping.insert(
+        TooltipPicturesOutputFolder,
+        "重命名后的照片复制或移动到的文件夹。\n示例: %USERPROFILE%\\Pictures\\VRChat\\renamed",
+    );
+    mapping.insert(
+        TooltipScheduleTime,
+        "计划任务每天运行的时间，使用 24 小时制。\n示例: 04:30",
+    );
+    mapping.insert(
+        TooltipScheduleIntervalDays,
+        "计划任务每隔几天运行一次。\n示例: 7 表示每周运行",
+    );
+    mapping.insert(TooltipWakeToRun, "唤醒睡眠中的计算机以运行计划任务。");
+    mapping.insert(
+        TooltipStartWhenAvailable,
+        "如果因计算机关机等原因错过了计划时间，则尽快运行任务。",
+    );
+    mapping.insert(
+        TooltipRunAtLogon,
+        "使用注册表的 Run 键在每次登录时运行重命名工具。在无法使用任务计划程序时使用。",
+    );
+    mapping.insert(
+        ConfirmRunText,
+        "将复制或移动选中的文件。取消选中要跳过的文件。",
+    );
+    mapping.insert(ConfirmRunCaption, "确认");
+    mapping.insert(MatchingFiles, "匹配的文件");
+    mapping.insert(TotalSize, "总大小");
+    mapping.insert(FreeSpace, "可用空间");
+    mapping.insert(RequiredSpace, "所需空间");
+    mapping.insert(
+        NotEnoughSpaceText,
+        "无法开始重命名: 目标驱动器的可用空间不足。",
+    );
+    mapping.insert(NotEnoughSpaceCaption, "空间不足");
+    mapping.insert(FileColumn, "文件");
+    mapping.insert(SizeColumn, "大小");
+    mapping.insert(CancelButton, "取消");
+    mapping.insert(
+        VrcLogExcludePattern,
+        "排除的日志文件模式 (正则表达式, 可选):",
+    );
+    mapping.insert(InvalidExcludePatternText, "无法保存配置: 排除模式无效");
+    mapping.insert(InvalidExcludePatternCaption, "错误");
+    mapping.insert(TooltipExcludePattern, "匹配此正则表达式的日志文件即使匹配日志文件模式也不会重命名。留空则全部重命名。\n示例: ^output_log_00-00-00\\.txt$");
+    mapping.insert(RenameLogCaption, "正在重命名");
+    mapping.insert(DetectButton, "检测");
+    mapping.insert(
+        SourceFolderNotFoundText,
+        "在标准文件夹、Steam 库或 Oculus 库中未找到包含 VRChat 日志的文件夹。\n请手动选择文件夹。",
+    );
+    mapping.insert(SourceFolderNotFoundCaption, "未找到");
+    mapping.insert(
+        TooltipDetect,
+        "在标准 LocalLow 文件夹、Steam 库和 Oculus 库中查找包含 VRChat 日志的文件夹。",
+    );
+    mapping.insert(VrchatRunning, "VRChat 正在运行 — 将跳过最新的日志");
+    mapping.insert(StatisticsButton, "统计");
+    mapping.insert(StatisticsCaption, "游玩时间统计");
+    mapping.insert(StatisticsSessions, "会话数");
+    mapping.insert(StatisticsTotalHours, "总时长（小时）");
+    mapping.insert(StatisticsHoursPerWeek, "每周时长（小时）");
+    mapping.insert(StatisticsMostVisitedWorlds, "最常访问的世界");
+    mapping.insert(UnsupportedSpecifier, "不支持的格式说明符");
+    mapping.insert(SpecifierPosition, "位置");
+    mapping.insert(
+        CollisionAlways,
+        "输出文件格式中没有日期和时间，所有日志将得到相同的文件名。",
+    );
+    mapping.insert(CollisionSameDay, "同一天启动的日志将得到相同的文件名。");
+    mapping.insert(
+        CollisionSameHour,
+        "同一小时内启动的日志将得到相同的文件名。",
+    );
+    mapping.insert(
+        CollisionSameMinute,
+        "同一分钟内启动的日志将得到相同的文件名。",
+    );
+    mapping.insert(
+        OutputPatternCollisionText,
+        "请在格式中添加 {seq} 或 %S 等更精细的说明符。仍要保存吗？",
+    );
+    mapping.insert(OutputPatternCollisionCaption, "警告");
+    mapping.insert(
+        TooltipSourcePreset,
+        "填写 VRChat 或其他社交 VR 平台的日志文件夹和文件模式。",
+    );
+    mapping.insert(OutputOverlapsSourceText, "重命名后的日志会放在日志文件夹中，且名称与日志文件模式匹配，因此会再次被当作日志。仍要保存吗？");
+    mapping.insert(OutputOverlapsSourceCaption, "输出文件夹重叠");
+    mapping.insert(BuildCommitLabel, "提交");
+    mapping.insert(BuildDateLabel, "构建日期");
+    mapping.insert(BuildTargetLabel, "目标平台");
+}
+
+fn localization_zh_hant(mapping: &mut HashMap<Message, &str>) {
+    mapping.insert(ErrorReadingConfigFile, "讀取設定檔時發生錯誤");
+    mapping.insert(ClickOKToDiscordAndContinue, "按下確定以捨棄設定並繼續。");
+    mapping.insert(ErrorLoadingConfigFileCaption, "錯誤");
+
+    mapping.insert(ErrorWritingConfigFileText, "寫入設定檔時發生錯誤");
+    mapping.insert(ErrorWritingConfigFileCaption, "錯誤");
+
+    mapping.insert(PathToVrcLogFolder, "VRC 記錄檔資料夾路徑:");
+    mapping.insert(VrcLogFilePattern, "VRC 記錄檔名稱模式(正規表示式):");
+    mapping.insert(KeepOriginal, "保留原始檔案");
+    mapping.insert(CopyMoveLogFileTo, "記錄檔複製/移動至:");
+    mapping.insert(OutputFilePattern, "輸出檔名格式(chrono 的 strftime):");
+    mapping.insert(UseUcForFileName, "記錄檔名稱使用 UTC 時間");
+    mapping.insert(UseFileCreationTime, "使用檔案建立時間");
+    mapping.insert(SaveConfig, "儲存設定");
+    mapping.insert(ResetConfig, "重設設定");
+    mapping.insert(ExecuteNow, "立即執行");
+    mapping.insert(InstallToTaskScheduler, "註冊至工作排程器");
+    mapping.insert(UninstallFromTaskScheduler, "從工作排程器移除");
+    mapping.insert(SelectInGuiButtonText, "選擇資料夾");
+
+    mapping.insert(SourceFolderChooserCaption, "VRC 記錄檔資料夾");
+    mapping.insert(OutputFolderChooserCaption, "輸出資料夾");
+
+    mapping.insert(SaveBeforeCloseText, "關閉前要儲存設定嗎?");
+    mapping.insert(SaveBeforeCloseCaption, "儲存?");
+
+    mapping.insert(ConfigSavedAt, "已儲存");
+
+    mapping.insert(
+        ResetConfirmText,
+        "確定要將設定重設為預設值嗎?\n此操作無法復原",
+    );
+    mapping.insert(ResetConfirmCaption, "確認");
+
+    mapping.insert(InstallSucceedText, "已成功註冊至工作排程器!");
+    mapping.insert(InstallSucceedCaption, "成功");
+
+    mapping.insert(UninstallSucceedText, "已成功從工作排程器移除!");
+    mapping.insert(UninstallSucceedCaption, "成功");
+
+    mapping.insert(ErrorInRenameText, "重新命名記錄檔時發生錯誤");
+    mapping.insert(ErrorInRenameCaption, "錯誤");
+
+    mapping.insert(RenameSucceedText, "記錄檔重新命名成功!");
+
+    mapping.insert(
+        InvalidSourcePatternText,
+        "無法儲存設定: VRC 記錄檔名稱模式無效",
+    );
+    mapping.insert(InvalidSourcePatternCaption, "錯誤");
+
+    mapping.insert(InvalidOutputPatternText, "無法儲存設定: 輸出檔名格式無效");
+    mapping.insert(InvalidOutputPatternCaption, "錯誤");
+
+    mapping.insert(
+        InvalidSourceFolderText,
+        "無法儲存設定: VRC 記錄檔資料夾路徑無效",
+    );
+    mapping.insert(InvalidSourceFolderCaption, "錯誤");
+
+    mapping.insert(InvalidOutputFolderText, "無法儲存設定: 輸出資料夾無效");
+    mapping.insert(InvalidOutputFolderCaption, "錯誤");
+
+    mapping.insert(RenamePictures, "同時重新命名 VRChat 照片");
+    mapping.insert(PathToVrcPicturesFolder, "VRChat 照片資料夾路徑:");
+    mapping.insert(CopyMovePicturesTo, "照片複製/移動到:");
+    mapping.insert(PicturesFolderChooserCaption, "VRChat 照片資料夾");
+    mapping.insert(PicturesOutputFolderChooserCaption, "照片輸出資料夾");
+    mapping.insert(InvalidPicturesFolderText, "無法儲存設定: 照片資料夾無效");
+    mapping.insert(InvalidPicturesFolderCaption, "錯誤");
+
+    mapping.insert(CheckForUpdates, "檢查更新");
+
+    mapping.insert(UpdateAvailableText, "有新版本可用。是否下載?\n新版本");
+    mapping.insert(UpdateAvailableCaption, "有可用更新");
+
+    mapping.insert(NoUpdateText, "您正在使用最新版本。");
+    mapping.insert(NoUpdateCaption, "無更新");
+
+    mapping.insert(ErrorCheckingUpdateText, "更新 VRC Log Renamer 時發生錯誤");
+    mapping.insert(ErrorCheckingUpdateCaption, "錯誤");
+
+    mapping.insert(UpdateDownloadedText, "新版本已下載到");
+    mapping.insert(UpdateDownloadedCaption, "下載完成!");
+
+    mapping.insert(CrashedText, "VRC Log Renamer 意外當機。\n是否開啟當機報告?");
+    mapping.insert(CrashedCaption, "當機");
+
+    mapping.insert(ScheduleTime, "執行時間 (HH:MM)");
+    mapping.insert(ScheduleIntervalDays, "執行間隔 (天)");
+    mapping.insert(NextRun, "下次執行");
+    mapping.insert(NextRunUnknown, "未知");
+    mapping.insert(
+        InvalidScheduleText,
+        "執行時間必須為 HH:MM 格式, 間隔必須為正整數天",
+    );
+    mapping.insert(InvalidScheduleCaption, "排程無效");
+    mapping.insert(WakeToRun, "喚醒電腦執行");
+    mapping.insert(StartWhenAvailable, "錯過時盡快執行");
+
+    mapping.insert(TaskSchedulerErrorText, "存取工作排程器時發生錯誤");
+    mapping.insert(
+        TaskSchedulerDeniedText,
+        "存取工作排程器遭到拒絕。此電腦的原則可能限制了工作排程器。\n您可以改為在登入時執行。",
+    );
+    mapping.insert(TaskSchedulerErrorCaption, "工作排程器錯誤");
+
+    mapping.insert(
+        RunAtLogonWithRegistry,
+        "使用登錄檔在登入時執行, 而非工作排程器",
+    );
+    mapping.insert(NextRunAtLogon, "登入時");
+    mapping.insert(RunKeyErrorText, "寫入登錄檔 Run 機碼時發生錯誤");
+    mapping.insert(RunKeyErrorCaption, "登錄檔錯誤");
+
+    mapping.insert(PresetIsoDate, "ISO 日期");
+    mapping.insert(PresetDateAndWorld, "日期 + 世界名稱");
+    mapping.insert(PresetYearMonthFolders, "年/月資料夾");
+    mapping.insert(PresetKeepOriginalSuffix, "保留原始後綴");
+    mapping.insert(SomeFilesFailedText, "部分記錄檔重新命名失敗");
+    mapping.insert(CopyDetails, "複製詳細資訊");
+    mapping.insert(CloseButton, "關閉");
+    mapping.insert(AboutButton, "關於");
+    mapping.insert(AboutCaption, "關於 VRC Log Renamer");
+    mapping.insert(OpenRepository, "在瀏覽器中開啟");
+    mapping.insert(LicensesLabel, "本應用程式及其相依套件的授權");
+    mapping.insert(
+        TooltipSourceFolder,
+        "VRChat 寫入記錄檔的資料夾。\n範例: %LOCALAPPDATA%Low\\VRChat\\VRChat",
+    );
+    mapping.insert(
+        TooltipSourcePattern,
+        "比對要重新命名的記錄檔名稱的正規表示式。\n範例: ^output_log_\\d{2}-\\d{2}-\\d{2}\\.txt$",
+    );
+    mapping.insert(
+        TooltipKeepOriginal,
+        "複製檔案而不是移動檔案，保留原始檔案。",
+    );
+    mapping.insert(
+        TooltipOutputFolder,
+        "重新命名後的記錄檔複製或移動到的資料夾。\n範例: %USERPROFILE%\\Documents\\VRChat Logs",
+    );
+    mapping.insert(TooltipOutputPattern, "使用 chrono 的 strftime 格式的重新命名後檔案名稱。使用 \\ 建立資料夾。\n%Y-%m-%d_%H-%M-%S: 日期和時間, {world}: 世界名稱, {regex:name}: 記錄檔模式的群組\n範例: output_log_%Y-%m-%d_%H-%M-%S.txt");
+    mapping.insert(TooltipOutputPatternPreset, "用常用模式填入輸出檔案模式。");
+    mapping.insert(TooltipUseUtc, "重新命名後的檔案名稱使用 UTC 時間而不是本地時間。\n範例: 台北時間 (UTC+8) 12:00 變為 04-00-00");
+    mapping.insert(
+        TooltipUseCreationTime,
+        "使用檔案的建立時間而不是檔案名稱中的時間。",
+    );
+    mapping.insert(
+        TooltipRenamePictures,
+        "同時重新命名在 VRChat 中拍攝的照片，並加上世界名稱。",
+    );
+    mapping.insert(
+        TooltipPicturesFolder,
+        "VRChat 儲存照片的資料夾。\n範例: %USERPROFILE%\\Pictures\\VRChat",
+    );
+    mapping.insert(
+        TooltipPicturesOutputFolder,
+        "重新命名後的照片複製或移動到的資料夾。\n範例: %USERPROFILE%\\Pictures\\VRChat\\renamed",
+    );
+    mapping.insert(
+        TooltipScheduleTime,
+        "排程工作每天執行的時間，使用 24 小時制。\n範例: 04:30",
+    );
+    mapping.insert(
+        TooltipScheduleIntervalDays,
+        "排程工作每隔幾天執行一次。\n範例: 7 表示每週執行",
+    );
+    mapping.insert(TooltipWakeToRun, "喚醒睡眠中的電腦以執行排程工作。");
+    mapping.insert(
+        TooltipStartWhenAvailable,
+        "如果因電腦關機等原因錯過了排程時間，則盡快執行工作。",
+    );
+    mapping.insert(
+        TooltipRunAtLogon,
+        "使用登錄檔的 Run 機碼在每次登入時執行重新命名工具。在無法使用工作排程器時使用。",
+    );
+    mapping.insert(
+        ConfirmRunText,
+        "將複製或移動勾選的檔案。取消勾選要略過的檔案。",
+    );
+    mapping.insert(ConfirmRunCaption, "確認");
+    mapping.insert(MatchingFiles, "符合的檔案");
+    mapping.insert(TotalSize, "總大小");
+    mapping.insert(FreeSpace, "可用空間");
+    mapping.insert(RequiredSpace, "所需空間");
+    mapping.insert(
+        NotEnoughSpaceText,
+        "無法開始重新命名: 目的地磁碟機的可用空間不足。",
+    );
+    mapping.insert(NotEnoughSpaceCaption, "空間不足");
+    mapping.insert(FileColumn, "檔案");
+    mapping.insert(SizeColumn, "大小");
+    mapping.insert(CancelButton, "取消");
+    mapping.insert(VrcLogExcludePattern, "排除的記錄檔模式 (正規表示式, 選填):");
+    mapping.insert(InvalidExcludePatternText, "無法儲存設定: 排除模式無效");
+    mapping.insert(InvalidExcludePatternCaption, "錯誤");
+    mapping.insert(TooltipExcludePattern, "符合此正規表示式的記錄檔即使符合記錄檔模式也不會重新命名。留空則全部重新命名。\n範例: ^output_log_00-00-00\\.txt$");
+    mapping.insert(RenameLogCaption, "正在重新命名");
+    mapping.insert(DetectButton, "偵測");
+    mapping.insert(SourceFolderNotFoundText, "在標準資料夾、Steam 收藏庫或 Oculus 收藏庫中找不到包含 VRChat 記錄的資料夾。\n請手動選擇資料夾。");
+    mapping.insert(SourceFolderNotFoundCaption, "找不到");
+    mapping.insert(
+        TooltipDetect,
+        "在標準 LocalLow 資料夾、Steam 收藏庫和 Oculus 收藏庫中尋找包含 VRChat 記錄的資料夾。",
+    );
+    mapping.insert(VrchatRunning, "VRChat 正在執行 — 將略過最新的記錄");
+    mapping.insert(StatisticsButton, "統計");
+    mapping.insert(StatisticsCaption, "遊玩時間統計");
+    mapping.insert(StatisticsSessions, "工作階段數");
+    mapping.insert(StatisticsTotalHours, "總時數");
+    mapping.insert(StatisticsHoursPerWeek, "每週時數");
+    mapping.insert(StatisticsMostVisitedWorlds, "最常造訪的世界");
+    mapping.insert(UnsupportedSpecifier, "不支援的格式指定元");
+    mapping.insert(SpecifierPosition, "位置");
+    mapping.insert(
+        CollisionAlways,
+        "輸出檔案格式中沒有日期和時間，所有日誌將得到相同的檔案名稱。",
+    );
+    mapping.insert(CollisionSameDay, "同一天啟動的日誌將得到相同的檔案名稱。");
+    mapping.insert(
+        CollisionSameHour,
+        "同一小時內啟動的日誌將得到相同的檔案名稱。",
+    );
+    mapping.insert(
+        CollisionSameMinute,
+        "同一分鐘內啟動的日誌將得到相同的檔案名稱。",
+    );
+    mapping.insert(
+        OutputPatternCollisionText,
+        "請在格式中加入 {seq} 或 %S 等更精細的說明符。仍要儲存嗎？",
+    );
+    mapping.insert(OutputPatternCollisionCaption, "警告");
+    mapping.insert(
+        TooltipSourcePreset,
+        "填入 VRChat 或其他社交 VR 平台的日誌資料夾和檔案模式。",
+    );
+    mapping.insert(OutputOverlapsSourceText, "重新命名後的日誌會放在日誌資料夾中，且名稱與日誌檔案模式相符，因此會再次被當作日誌。仍要儲存嗎？");
+    mapping.insert(OutputOverlapsSourceCaption, "輸出資料夾重疊");
+    mapping.insert(BuildCommitLabel, "提交");
+    mapping.insert(BuildDateLabel, "建置日期");
+    mapping.insert(BuildTargetLabel, "目標平台");
+}
+
+fn localization_ko(mapping: &mut HashMap<Message, &str>) {
+    mapping.insert(
+        ErrorReadingConfigFile,
+        "설정 파일을 읽는 중 오류가 발생했습니다",
+    );
+    mapping.insert(
+        ClickOKToDiscordAndContinue,
+        "확인을 누르면 설정을 버리고 계속합니다.",
+    );
+    mapping.insert(ErrorLoadingConfigFileCaption, "오류");
+
+    mapping.insert(
+        ErrorWritingConfigFileText,
+        "설정 파일을 쓰는 중 오류가 발생했습니다",
+    );
+    mapping.insert(ErrorWritingConfigFileCaption, "오류");
+
+    mapping.insert(PathToVrcLogFolder, "VRC 로그 폴더 경로:");
+    mapping.insert(VrcLogFilePattern, "VRC 로그 파일 패턴(정규식):");
+    mapping.insert(KeepOriginal, "원본 유지");
+    mapping.insert(CopyMoveLogFileTo, "로그 파일 복사/이동 위치:");
+    mapping.insert(OutputFilePattern, "출력 파일 패턴(chrono의 strftime):");
+    mapping.insert(UseUcForFileName, "로그 파일 이름에 UTC 시간 사용");
+    mapping.insert(UseFileCreationTime, "파일 생성 시간 사용");
+    mapping.insert(SaveConfig, "설정 저장");
+    mapping.insert(ResetConfig, "설정 초기화");
+    mapping.insert(ExecuteNow, "지금 실행");
+    mapping.insert(InstallToTaskScheduler, "작업 스케줄러에 등록");
+    mapping.insert(UninstallFromTaskScheduler, "작업 스케줄러에서 해제");
+    mapping.insert(SelectInGuiButtonText, "폴더 선택");
+
+    mapping.insert(SourceFolderChooserCaption, "VRC 로그 폴더");
+    mapping.insert(OutputFolderChooserCaption, "출력 폴더");
+
+    mapping.insert(SaveBeforeCloseText, "닫기 전에 설정을 저장하시겠습니까?");
+    mapping.insert(SaveBeforeCloseCaption, "저장?");
+
+    mapping.insert(ConfigSavedAt, "저장됨");
+
+    mapping.insert(
+        ResetConfirmText,
+        "정말로 설정을 기본값으로 초기화하시겠습니까?\n이 작업은 되돌릴 수 없습니다",
+    );
+    mapping.insert(ResetConfirmCaption, "확인");
+
+    mapping.insert(InstallSucceedText, "작업 스케줄러에 등록했습니다!");
+    mapping.insert(InstallSucceedCaption, "성공");
+
+    mapping.insert(UninstallSucceedText, "작업 스케줄러에서 해제했습니다!");
+    mapping.insert(UninstallSucceedCaption, "성공");
+
+    mapping.insert(
+        ErrorInRenameText,
+        "로그 이름을 바꾸는 중 오류가 발생했습니다",
+    );
+    mapping.insert(ErrorInRenameCaption, "오류");
+
+    mapping.insert(RenameSucceedText, "로그 이름 바꾸기에 성공했습니다!");
+
+    mapping.insert(
+        InvalidSourcePatternText,
+        "설정을 저장할 수 없습니다: VRC 로그 파일 패턴이 올바르지 않습니다",
+    );
+    mapping.insert(InvalidSourcePatternCaption, "오류");
+
+    mapping.insert(
+        InvalidOutputPatternText,
+        "설정을 저장할 수 없습니다: 출력 파일 패턴이 올바르지 않습니다",
+    );
+    mapping.insert(InvalidOutputPatternCaption, "오류");
+
+    mapping.insert(
+        InvalidSourceFolderText,
+        "설정을 저장할 수 없습니다: VRC 로그 폴더 경로가 올바르지 않습니다",
+    );
+    mapping.insert(InvalidSourceFolderCaption, "오류");
+
+    mapping.insert(
+        InvalidOutputFolderText,
+        "설정을 저장할 수 없습니다: 출력 폴더가 올바르지 않습니다",
+    );
+    mapping.insert(InvalidOutputFolderCaption, "오류");
+
+    mapping.insert(RenamePictures, "VRChat 사진도 이름 변경");
+    mapping.insert(PathToVrcPicturesFolder, "VRChat 사진 폴더 경로:");
+    mapping.insert(CopyMovePicturesTo, "사진 복사/이동 위치:");
+    mapping.insert(PicturesFolderChooserCaption, "VRChat 사진 폴더");
+    mapping.insert(PicturesOutputFolderChooserCaption, "사진 출력 폴더");
+    mapping.insert(
+        InvalidPicturesFolderText,
+        "설정을 저장할 수 없습니다: 사진 폴더가 올바르지 않습니다",
+    );
+    mapping.insert(InvalidPicturesFolderCaption, "오류");
+
+    mapping.insert(CheckForUpdates, "업데이트 확인");
+
+    mapping.insert(
+        UpdateAvailableText,
+        "새 버전이 있습니다. 다운로드하시겠습니까?\n새 버전",
+    );
+    mapping.insert(UpdateAvailableCaption, "업데이트 가능");
+
+    mapping.insert(NoUpdateText, "최신 버전을 사용하고 있습니다.");
+    mapping.insert(NoUpdateCaption, "업데이트 없음");
+
+    mapping.insert(ErrorCheckingUpdateText, "VRC Log Renamer 업데이트 중 오류");
+    mapping.insert(ErrorCheckingUpdateCaption, "오류");
+
+    mapping.insert(UpdateDownloadedText, "새 버전을 다운로드했습니다");
+    mapping.insert(UpdateDownloadedCaption, "다운로드 완료!");
+
+    mapping.insert(
+        CrashedText,
+        "VRC Log Renamer가 예기치 않게 종료되었습니다.\n충돌 보고서를 여시겠습니까?",
+    );
+    mapping.insert(CrashedCaption, "충돌");
+
+    mapping.insert(ScheduleTime, "실행 시각 (HH:MM)");
+    mapping.insert(ScheduleIntervalDays, "실행 간격 (일)");
+    mapping.insert(NextRun, "다음 실행");
+    mapping.insert(NextRunUnknown, "알 수 없음");
+    mapping.insert(
+        InvalidScheduleText,
+        "실행 시각은 HH:MM 형식, 간격은 양의 일 수여야 합니다",
+    );
+    mapping.insert(InvalidScheduleCaption, "잘못된 일정");
+    mapping.insert(WakeToRun, "절전 모드 해제 후 실행");
+    mapping.insert(StartWhenAvailable, "놓친 실행 즉시 실행");
+
+    mapping.insert(
+        TaskSchedulerErrorText,
+        "작업 스케줄러에 접근하는 중 오류가 발생했습니다",
+    );
+    mapping.insert(TaskSchedulerDeniedText, "작업 스케줄러에 대한 접근이 거부되었습니다. 이 컴퓨터의 정책으로 작업 스케줄러가 제한되었을 수 있습니다.\n작업 스케줄러 대신 로그온 시 실행할 수 있습니다.");
+    mapping.insert(TaskSchedulerErrorCaption, "작업 스케줄러 오류");
+
+    mapping.insert(
+        RunAtLogonWithRegistry,
+        "작업 스케줄러 대신 레지스트리로 로그온 시 실행",
+    );
+    mapping.insert(NextRunAtLogon, "로그온 시");
+    mapping.insert(
+        RunKeyErrorText,
+        "레지스트리 Run 키에 쓰는 중 오류가 발생했습니다",
+    );
+    mapping.insert(RunKeyErrorCaption, "레지스트리 오류");
+
+    mapping.insert(PresetIsoDate, "ISO 날짜");
+    mapping.insert(PresetDateAndWorld, "날짜 + 월드 이름");
+    mapping.insert(PresetYearMonthFolders, "연/월 폴더");
+    mapping.insert(PresetKeepOriginalSuffix, "원래 접미사 유지");
+    mapping.insert(
+        SomeFilesFailedText,
+        "일부 로그 파일의 이름을 바꾸지 못했습니다",
+    );
+    mapping.insert(CopyDetails, "세부 정보 복사");
+    mapping.insert(CloseButton, "닫기");
+    mapping.insert(AboutButton, "정보");
+    mapping.insert(AboutCaption, "VRC Log Renamer 정보");
+    mapping.insert(OpenRepository, "브라우저에서 열기");
+    mapping.insert(LicensesLabel, "이 앱과 종속성의 라이선스");
+    mapping.insert(
+        TooltipSourceFolder,
+        "VRChat이 로그 파일을 기록하는 폴더입니다.\n예: %LOCALAPPDATA%Low\\VRChat\\VRChat",
+    );
+    mapping.insert(TooltipSourcePattern, "이름을 바꿀 로그 파일 이름과 일치하는 정규 표현식입니다.\n예: ^output_log_\\d{2}-\\d{2}-\\d{2}\\.txt$");
+    mapping.insert(
+        TooltipKeepOriginal,
+        "파일을 이동하지 않고 복사하여 원본 파일을 그대로 유지합니다.",
+    );
+    mapping.insert(TooltipOutputFolder, "이름을 바꾼 로그 파일을 복사하거나 이동할 폴더입니다.\n예: %USERPROFILE%\\Documents\\VRChat Logs");
+    mapping.insert(TooltipOutputPattern, "chrono의 strftime 형식으로 된 변경 후 파일 이름입니다. \\로 폴더를 만들 수 있습니다.\n%Y-%m-%d_%H-%M-%S: 날짜와 시간, {world}: 월드 이름, {regex:name}: 로그 파일 패턴의 그룹\n예: output_log_%Y-%m-%d_%H-%M-%S.txt");
+    mapping.insert(
+        TooltipOutputPatternPreset,
+        "자주 사용하는 패턴으로 출력 파일 패턴을 채웁니다.",
+    );
+    mapping.insert(TooltipUseUtc, "변경 후 파일 이름에 현지 시간 대신 UTC 시간을 사용합니다.\n예: 한국 시간(UTC+9) 12:00은 03-00-00이 됩니다");
+    mapping.insert(
+        TooltipUseCreationTime,
+        "파일 이름의 시간 대신 파일 생성 시간을 사용합니다.",
+    );
+    mapping.insert(
+        TooltipRenamePictures,
+        "VRChat에서 찍은 사진도 월드 이름을 붙여 이름을 바꿉니다.",
+    );
+    mapping.insert(
+        TooltipPicturesFolder,
+        "VRChat이 사진을 저장하는 폴더입니다.\n예: %USERPROFILE%\\Pictures\\VRChat",
+    );
+    mapping.insert(TooltipPicturesOutputFolder, "이름을 바꾼 사진을 복사하거나 이동할 폴더입니다.\n예: %USERPROFILE%\\Pictures\\VRChat\\renamed");
+    mapping.insert(
+        TooltipScheduleTime,
+        "예약된 작업을 실행할 시각입니다. 24시간 형식으로 입력합니다.\n예: 04:30",
+    );
+    mapping.insert(
+        TooltipScheduleIntervalDays,
+        "예약된 작업을 며칠마다 실행할지입니다.\n예: 매주 실행하려면 7",
+    );
+    mapping.insert(
+        TooltipWakeToRun,
+        "예약된 작업을 실행하기 위해 컴퓨터를 절전 모드에서 깨웁니다.",
+    );
+    mapping.insert(
+        TooltipStartWhenAvailable,
+        "컴퓨터가 꺼져 있는 등의 이유로 예약 시간을 놓친 경우 가능한 한 빨리 작업을 실행합니다.",
+    );
+    mapping.insert(TooltipRunAtLogon, "레지스트리의 Run 키를 사용하여 로그인할 때마다 이름 변경 도구를 실행합니다. 작업 스케줄러를 사용할 수 없을 때 사용하세요.");
+    mapping.insert(
+        ConfirmRunText,
+        "체크한 파일을 복사하거나 이동합니다. 건너뛸 파일은 체크를 해제하세요.",
+    );
+    mapping.insert(ConfirmRunCaption, "확인");
+    mapping.insert(MatchingFiles, "대상 파일");
+    mapping.insert(TotalSize, "전체 크기");
+    mapping.insert(FreeSpace, "여유 공간");
+    mapping.insert(RequiredSpace, "필요한 공간");
+    mapping.insert(
+        NotEnoughSpaceText,
+        "이름 변경을 시작할 수 없습니다: 대상 드라이브의 여유 공간이 부족합니다.",
+    );
+    mapping.insert(NotEnoughSpaceCaption, "공간 부족");
+    mapping.insert(FileColumn, "파일");
+    mapping.insert(SizeColumn, "크기");
+    mapping.insert(CancelButton, "취소");
+    mapping.insert(
+        VrcLogExcludePattern,
+        "제외할 로그 파일 패턴 (정규 표현식, 선택):",
+    );
+    mapping.insert(
+        InvalidExcludePatternText,
+        "설정을 저장할 수 없습니다: 제외 패턴이 올바르지 않습니다",
+    );
+    mapping.insert(InvalidExcludePatternCaption, "오류");
+    mapping.insert(TooltipExcludePattern, "이 정규 표현식과 일치하는 로그 파일은 로그 파일 패턴과 일치해도 이름을 바꾸지 않습니다. 비워 두면 모두 바꿉니다.\n예: ^output_log_00-00-00\\.txt$");
+    mapping.insert(RenameLogCaption, "이름 변경 중");
+    mapping.insert(DetectButton, "감지");
+    mapping.insert(SourceFolderNotFoundText, "표준 폴더, Steam 라이브러리, Oculus 라이브러리에서 VRChat 로그가 있는 폴더를 찾을 수 없습니다.\n폴더를 직접 선택해 주세요.");
+    mapping.insert(SourceFolderNotFoundCaption, "찾을 수 없음");
+    mapping.insert(TooltipDetect, "표준 LocalLow 폴더, Steam 라이브러리, Oculus 라이브러리에서 VRChat 로그가 있는 폴더를 찾습니다.");
+    mapping.insert(
+        VrchatRunning,
+        "VRChat이 실행 중입니다 — 최신 로그는 건너뜁니다",
+    );
+    mapping.insert(StatisticsButton, "통계");
+    mapping.insert(StatisticsCaption, "플레이 시간 통계");
+    mapping.insert(StatisticsSessions, "세션 수");
+    mapping.insert(StatisticsTotalHours, "총 시간");
+    mapping.insert(StatisticsHoursPerWeek, "주별 시간");
+    mapping.insert(StatisticsMostVisitedWorlds, "가장 많이 방문한 월드");
+    mapping.insert(UnsupportedSpecifier, "지원되지 않는 지정자");
+    mapping.insert(SpecifierPosition, "위치");
+    mapping.insert(
+        CollisionAlways,
+        "출력 파일 형식에 날짜와 시간이 없어 모든 로그가 같은 파일 이름이 됩니다.",
+    );
+    mapping.insert(
+        CollisionSameDay,
+        "같은 날에 시작된 로그는 같은 파일 이름이 됩니다.",
+    );
+    mapping.insert(
+        CollisionSameHour,
+        "같은 시간대에 시작된 로그는 같은 파일 이름이 됩니다.",
+    );
+    mapping.insert(
+        CollisionSameMinute,
+        "같은 분에 시작된 로그는 같은 파일 이름이 됩니다.",
+    );
+    mapping.insert(
+        OutputPatternCollisionText,
+        "{seq} 또는 %S 같은 더 세밀한 지정자를 추가하세요. 그래도 저장하시겠습니까?",
+    );
+    mapping.insert(OutputPatternCollisionCaption, "경고");
+    mapping.insert(
+        TooltipSourcePreset,
+        "VRChat 또는 다른 소셜 VR 플랫폼의 로그 폴더와 파일 패턴을 채웁니다.",
+    );
+    mapping.insert(OutputOverlapsSourceText, "이름이 변경된 로그가 로그 폴더에 저장되고 이름이 로그 파일 패턴과 일치하므로 다시 로그로 인식됩니다. 그래도 저장하시겠습니까?");
+    mapping.insert(OutputOverlapsSourceCaption, "출력 폴더 중복");
+    mapping.insert(BuildCommitLabel, "커밋");
+    mapping.insert(BuildDateLabel, "빌드 날짜");
+    mapping.insert(BuildTargetLabel, "대상");
+}
+
+fn localization_de(mapping: &mut HashMap<Message, &str>) {
+    mapping.insert(
+        ErrorReadingConfigFile,
+        "Fehler beim Lesen der Konfigurationsdatei",
+    );
+    mapping.insert(
+        ClickOKToDiscordAndContinue,
+        "Klicke auf OK, um die Konfiguration zu verwerfen und fortzufahren.",
+    );
+    mapping.insert(ErrorLoadingConfigFileCaption, "Fehler");
+
+    mapping.insert(
+        ErrorWritingConfigFileText,
+        "Fehler beim Schreiben der Konfigurationsdatei",
+    );
+    mapping.insert(ErrorWritingConfigFileCaption, "Fehler");
+
+    mapping.insert(PathToVrcLogFolder, "Pfad zum VRC-Log-Ordner:");
+    mapping.insert(VrcLogFilePattern, "VRC-Log-Dateimuster (Regex):");
+    mapping.insert(KeepOriginal, "Original behalten");
+    mapping.insert(CopyMoveLogFileTo, "Log-Datei kopieren/verschieben nach:");
+    mapping.insert(
+        OutputFilePattern,
+        "Ausgabe-Dateimuster (strftime von chrono):",
+    );
+    mapping.insert(UseUcForFileName, "UTC-Zeit für den Log-Namen verwenden");
+    mapping.insert(UseFileCreationTime, "Erstellungszeit der Datei verwenden");
+    mapping.insert(SaveConfig, "Speichern");
+    mapping.insert(ResetConfig, "Zurücksetzen");
+    mapping.insert(ExecuteNow, "Jetzt ausführen");
+    mapping.insert(InstallToTaskScheduler, "In Aufgabenplanung eintragen");
+    mapping.insert(UninstallFromTaskScheduler, "Aus Aufgabenplanung entfernen");
+    mapping.insert(SelectInGuiButtonText, "Ordner wählen");
+
+    mapping.insert(SourceFolderChooserCaption, "VRC-Log-Ordner");
+    mapping.insert(OutputFolderChooserCaption, "Ausgabeordner");
+
+    mapping.insert(
+        SaveBeforeCloseText,
+        "Konfiguration vor dem Schließen speichern?",
+    );
+    mapping.insert(SaveBeforeCloseCaption, "Speichern?");
+
+    mapping.insert(ConfigSavedAt, "Gespeichert");
+
+    mapping.insert(
+        ResetConfirmText,
+        "Konfiguration wirklich auf die Standardwerte zurücksetzen?\nDies kann nicht rückgängig gemacht werden",
+    );
+    mapping.insert(ResetConfirmCaption, "Bestätigen?");
+
+    mapping.insert(
+        InstallSucceedText,
+        "VRC Log Renamer wurde erfolgreich in die Aufgabenplanung eingetragen!",
+    );
+    mapping.insert(InstallSucceedCaption, "Erfolgreich!");
+
+    mapping.insert(
+        UninstallSucceedText,
+        "VRC Log Renamer wurde erfolgreich aus der Aufgabenplanung entfernt!",
+    );
+    mapping.insert(UninstallSucceedCaption, "Erfolgreich!");
+
+    mapping.insert(ErrorInRenameText, "Fehler beim Umbenennen der Logs");
+    mapping.insert(ErrorInRenameCaption, "Fehler!");
+
+    mapping.insert(RenameSucceedText, "Logs erfolgreich umbenannt!");
+
+    mapping.insert(
+        InvalidSourcePatternText,
+        "Konfiguration kann nicht gespeichert werden: Das Log-Dateimuster ist ungültig",
+    );
+    mapping.insert(InvalidSourcePatternCaption, "Fehler");
+
+    mapping.insert(
+        InvalidOutputPatternText,
+        "Konfiguration kann nicht gespeichert werden: Das Ausgabe-Dateimuster ist ungültig",
+    );
+    mapping.insert(InvalidOutputPatternCaption, "Fehler");
+
+    mapping.insert(
+        InvalidSourceFolderText,
+        "Konfiguration kann nicht gespeichert werden: Der Pfad zum VRC-Log-Ordner ist ungültig",
+    );
+    mapping.insert(InvalidSourceFolderCaption, "Fehler");
+
+    mapping.insert(
+        InvalidOutputFolderText,
+        "Konfiguration kann nicht gespeichert werden: Der Ausgabeordner ist ungültig",
+    );
+    mapping.insert(InvalidOutputFolderCaption, "Fehler");
+
+    mapping.insert(RenamePictures, "VRChat-Bilder umbenennen");
+    mapping.insert(PathToVrcPicturesFolder, "Pfad zum VRChat-Bilderordner:");
+    mapping.insert(CopyMovePicturesTo, "Bilder kopieren/verschieben nach:");
+    mapping.insert(PicturesFolderChooserCaption, "VRChat-Bilderordner");
+    mapping.insert(
+        PicturesOutputFolderChooserCaption,
+        "Ausgabeordner für Bilder",
+    );
+    mapping.insert(
+        InvalidPicturesFolderText,
+        "Konfiguration kann nicht gespeichert werden: Der Bilderordner ist ungültig",
+    );
+    mapping.insert(InvalidPicturesFolderCaption, "Fehler");
+
+    mapping.insert(CheckForUpdates, "Nach Updates suchen");
+
+    mapping.insert(
+        UpdateAvailableText,
+        "Eine neue Version ist verfügbar. Herunterladen?\nNeue Version",
+    );
+    mapping.insert(UpdateAvailableCaption, "Update verfügbar");
+
+    mapping.insert(NoUpdateText, "Sie verwenden die neueste Version.");
+    mapping.insert(NoUpdateCaption, "Kein Update");
+
+    mapping.insert(
+        ErrorCheckingUpdateText,
+        "Fehler beim Aktualisieren von VRC Log Renamer",
+    );
+    mapping.insert(ErrorCheckingUpdateCaption, "Fehler");
+
+    mapping.insert(
+        UpdateDownloadedText,
+        "Die neue Version wurde heruntergeladen nach",
+    );
+    mapping.insert(UpdateDownloadedCaption, "Heruntergeladen!");
+
+    mapping.insert(
+        CrashedText,
+        "VRC Log Renamer ist unerwartet abgestürzt.\nMöchten Sie den Absturzbericht öffnen?",
+    );
+    mapping.insert(CrashedCaption, "Absturz");
+
+    mapping.insert(ScheduleTime, "Ausführungszeit (HH:MM)");
+    mapping.insert(ScheduleIntervalDays, "Alle N Tage ausführen");
+    mapping.insert(NextRun, "Nächste Ausführung");
+    mapping.insert(NextRunUnknown, "unbekannt");
+    mapping.insert(
+        InvalidScheduleText,
+        "Die Zeit muss im Format HH:MM und das Intervall eine positive Anzahl Tage sein",
+    );
+    mapping.insert(InvalidScheduleCaption, "Ungültiger Zeitplan");
+    mapping.insert(WakeToRun, "Computer zum Ausführen aufwecken");
+    mapping.insert(StartWhenAvailable, "Verpasste Ausführungen nachholen");
+
+    mapping.insert(
+        TaskSchedulerErrorText,
+        "Fehler beim Zugriff auf die Aufgabenplanung",
+    );
+    mapping.insert(TaskSchedulerDeniedText, "Der Zugriff auf die Aufgabenplanung wurde verweigert. Die Aufgabenplanung ist möglicherweise durch eine Richtlinie eingeschränkt.\nSie können den Renamer stattdessen bei der Anmeldung ausführen.");
+    mapping.insert(TaskSchedulerErrorCaption, "Fehler der Aufgabenplanung");
+
+    mapping.insert(
+        RunAtLogonWithRegistry,
+        "Bei der Anmeldung über die Registrierung statt der Aufgabenplanung ausführen",
+    );
+    mapping.insert(NextRunAtLogon, "bei der Anmeldung");
+    mapping.insert(
+        RunKeyErrorText,
+        "Fehler beim Schreiben des Run-Schlüssels in der Registrierung",
+    );
+    mapping.insert(RunKeyErrorCaption, "Registrierungsfehler");
+
+    mapping.insert(PresetIsoDate, "ISO-Datum");
+    mapping.insert(PresetDateAndWorld, "Datum + Weltname");
+    mapping.insert(PresetYearMonthFolders, "Jahr/Monat-Ordner");
+    mapping.insert(PresetKeepOriginalSuffix, "Ursprüngliches Suffix behalten");
+    mapping.insert(
+        SomeFilesFailedText,
+        "Einige Logdateien konnten nicht umbenannt werden",
+    );
+    mapping.insert(CopyDetails, "Details kopieren");
+    mapping.insert(CloseButton, "Schließen");
+    mapping.insert(AboutButton, "Über");
+    mapping.insert(AboutCaption, "Über VRC Log Renamer");
+    mapping.insert(OpenRepository, "Im Browser öffnen");
+    mapping.insert(
+        LicensesLabel,
+        "Lizenzen dieser App und ihrer Abhängigkeiten",
+    );
+    mapping.insert(TooltipSourceFolder, "Der Ordner, in den VRChat Logdateien schreibt.\nBeispiel: %LOCALAPPDATA%Low\\VRChat\\VRChat");
+    mapping.insert(TooltipSourcePattern, "Regulärer Ausdruck für die Namen der umzubenennenden Logdateien.\nBeispiel: ^output_log_\\d{2}-\\d{2}-\\d{2}\\.txt$");
+    mapping.insert(
+        TooltipKeepOriginal,
+        "Die Dateien kopieren statt verschieben, damit die Originaldateien erhalten bleiben.",
+    );
+    mapping.insert(TooltipOutputFolder, "Der Ordner, in den umbenannte Logdateien kopiert oder verschoben werden.\nBeispiel: %USERPROFILE%\\Documents\\VRChat Logs");
+    mapping.insert(TooltipOutputPattern, "Der Name umbenannter Dateien im strftime-Format von chrono. Mit \\ werden Ordner erstellt.\n%Y-%m-%d_%H-%M-%S: Datum und Uhrzeit, {world}: Weltname, {regex:name}: Gruppe des Logdatei-Musters\nBeispiel: output_log_%Y-%m-%d_%H-%M-%S.txt");
+    mapping.insert(
+        TooltipOutputPatternPreset,
+        "Das Ausgabedatei-Muster mit einem häufig verwendeten Muster füllen.",
+    );
+    mapping.insert(TooltipUseUtc, "Für die Namen umbenannter Dateien UTC statt der Ortszeit verwenden.\nBeispiel: 12:00 in Deutschland (UTC+1) wird zu 11-00-00");
+    mapping.insert(
+        TooltipUseCreationTime,
+        "Die Erstellungszeit der Datei statt der Zeit im Dateinamen verwenden.",
+    );
+    mapping.insert(
+        TooltipRenamePictures,
+        "Auch die in VRChat aufgenommenen Bilder umbenennen und den Weltnamen hinzufügen.",
+    );
+    mapping.insert(
+        TooltipPicturesFolder,
+        "Der Ordner, in dem VRChat Bilder speichert.\nBeispiel: %USERPROFILE%\\Pictures\\VRChat",
+    );
+    mapping.insert(TooltipPicturesOutputFolder, "Der Ordner, in den umbenannte Bilder kopiert oder verschoben werden.\nBeispiel: %USERPROFILE%\\Pictures\\VRChat\\renamed");
+    mapping.insert(
+        TooltipScheduleTime,
+        "Die Uhrzeit, zu der die geplante Aufgabe läuft, im 24-Stunden-Format.\nBeispiel: 04:30",
+    );
+    mapping.insert(
+        TooltipScheduleIntervalDays,
+        "Wie oft die geplante Aufgabe läuft, in Tagen.\nBeispiel: 7 für wöchentlich",
+    );
+    mapping.insert(
+        TooltipWakeToRun,
+        "Den Computer aus dem Ruhezustand wecken, um die geplante Aufgabe auszuführen.",
+    );
+    mapping.insert(TooltipStartWhenAvailable, "Die Aufgabe so bald wie möglich ausführen, wenn der geplante Zeitpunkt verpasst wurde, z. B. weil der Computer aus war.");
+    mapping.insert(TooltipRunAtLogon, "Den Umbenenner bei jeder Anmeldung über den Run-Schlüssel der Registrierung ausführen. Verwenden, wenn die Aufgabenplanung nicht verfügbar ist.");
+    mapping.insert(
+        ConfirmRunText,
+        "Die markierten Dateien werden kopiert oder verschoben. Zu überspringende Dateien abwählen.",
+    );
+    mapping.insert(ConfirmRunCaption, "Bestätigen");
+    mapping.insert(MatchingFiles, "Passende Dateien");
+    mapping.insert(TotalSize, "Gesamtgröße");
+    mapping.insert(FreeSpace, "Freier Speicher");
+    mapping.insert(RequiredSpace, "Benötigter Speicher");
+    mapping.insert(NotEnoughSpaceText, "Umbenennen kann nicht gestartet werden: Auf dem Ziellaufwerk ist nicht genügend freier Speicher.");
+    mapping.insert(NotEnoughSpaceCaption, "Nicht genügend Speicher");
+    mapping.insert(FileColumn, "Datei");
+    mapping.insert(SizeColumn, "Größe");
+    mapping.insert(CancelButton, "Abbrechen");
+    mapping.insert(
+        VrcLogExcludePattern,
+        "Auszuschließende Logdateien (Regex, optional):",
+    );
+    mapping.insert(
+        InvalidExcludePatternText,
+        "Konfiguration kann nicht gespeichert werden: Ausschlussmuster ist ungültig",
+    );
+    mapping.insert(InvalidExcludePatternCaption, "Fehler");
+    mapping.insert(TooltipExcludePattern, "Logdateien, die diesem regulären Ausdruck entsprechen, werden nicht umbenannt, auch wenn sie dem Logdatei-Muster entsprechen. Leer lassen, um alle umzubenennen.\nBeispiel: ^output_log_00-00-00\\.txt$");
+    mapping.insert(RenameLogCaption, "Umbenennen");
+    mapping.insert(DetectButton, "Suchen");
+    mapping.insert(SourceFolderNotFoundText, "In Standardordner, Steam-Bibliotheken und Oculus-Bibliothek wurde kein Ordner mit VRChat-Logs gefunden.\nBitte wählen Sie den Ordner manuell aus.");
+    mapping.insert(SourceFolderNotFoundCaption, "Nicht gefunden");
+    mapping.insert(TooltipDetect, "Sucht den Ordner mit VRChat-Logs im Standardordner LocalLow, in den Steam-Bibliotheken und in der Oculus-Bibliothek.");
+    mapping.insert(
+        VrchatRunning,
+        "VRChat läuft gerade — das neueste Log wird übersprungen",
+    );
+    mapping.insert(StatisticsButton, "Statistik");
+    mapping.insert(StatisticsCaption, "Spielzeitstatistik");
+    mapping.insert(StatisticsSessions, "Sitzungen");
+    mapping.insert(StatisticsTotalHours, "Stunden insgesamt");
+    mapping.insert(StatisticsHoursPerWeek, "Stunden pro Woche");
+    mapping.insert(StatisticsMostVisitedWorlds, "Meistbesuchte Welten");
+    mapping.insert(UnsupportedSpecifier, "Nicht unterstützter Platzhalter");
+    mapping.insert(SpecifierPosition, "Position");
+    mapping.insert(CollisionAlways, "Alle Logs erhalten denselben Dateinamen, da das Ausgabemuster weder Datum noch Uhrzeit enthält.");
+    mapping.insert(
+        CollisionSameDay,
+        "Logs, die am selben Tag gestartet wurden, erhalten denselben Dateinamen.",
+    );
+    mapping.insert(
+        CollisionSameHour,
+        "Logs, die in derselben Stunde gestartet wurden, erhalten denselben Dateinamen.",
+    );
+    mapping.insert(
+        CollisionSameMinute,
+        "Logs, die in derselben Minute gestartet wurden, erhalten denselben Dateinamen.",
+    );
+    mapping.insert(
+        OutputPatternCollisionText,
+        "Füge {seq} oder feinere Platzhalter wie %S zum Muster hinzu. Trotzdem speichern?",
+    );
+    mapping.insert(OutputPatternCollisionCaption, "Warnung");
+    mapping.insert(TooltipSourcePreset, "Füllt den Log-Ordner und das Dateimuster für VRChat oder andere Social-VR-Plattformen aus.");
+    mapping.insert(OutputOverlapsSourceText, "Die umbenannten Logs werden im Log-Ordner mit Namen abgelegt, die dem Log-Dateimuster entsprechen, und daher erneut als Logs gefunden. Trotzdem speichern?");
+    mapping.insert(
+        OutputOverlapsSourceCaption,
+        "Ausgabeordner überschneidet sich",
+    );
+    mapping.insert(BuildCommitLabel, "Commit");
+    mapping.insert(BuildDateLabel, "Build-Datum");
+    mapping.insert(BuildTargetLabel, "Ziel");
 }
@@ -0,0 +1,209 @@
+// VRC Log Renamer - the tool to rename logs of VRChat to have date info
+// Copyright (C) 2022 anatawa12
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Estimates the impact of a run to confirm it before running from the GUI.
+
+use crate::config::{ConfigFile, Pictures};
+use crate::vfs::{FileSystem, RealFs};
+use crate::{source_entries, to_wide};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+use windows::core::PCWSTR;
+use windows::Win32::Storage::FileSystem::{GetDiskFreeSpaceExW, GetVolumePathNameW};
+
+/// the files a run will copy or move
+#[derive(Debug, Default)]
+pub(crate) struct Impact {
+    /// the files matching the patterns
+    pub files: Vec<MatchedFile>,
+    /// the drives the files are written to
+    pub destinations: Vec<Destination>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct MatchedFile {
+    pub path: PathBuf,
+    pub size: u64,
+    /// the roots of the volumes the file is copied to. moving in the same volume is not included
+    pub copied_to: Vec<PathBuf>,
+}
+
+/// the drive the files are written to
+#[derive(Debug, Clone)]
+pub(crate) struct Destination {
+    /// the root of the volume like `C:\`
+    pub volume: PathBuf,
+    /// the free space available to the current user
+    pub free: u64,
+}
+
+impl Impact {
+    /// the total size of the files
+    pub fn bytes(&self) -> u64 {
+        self.files.iter().map(|x| x.size).sum()
+    }
+
+    /// the bytes written to the volume. moving in the same volume doesn't need space
+    pub fn required(&self, volume: &Path) -> u64 {
+        self.files
+            .iter()
+            .filter(|x| x.copied_to.iter().any(|x| x == volume))
+            .map(|x| x.size)
+            .sum()
+    }
+
+    /// the destination without enough free space with the required bytes if any
+    pub fn lacking_space(&self) -> Option<(&Destination, u64)> {
+        self.destinations
+            .iter()
+            .map(|x| (x, self.required(&x.volume)))
+            .find(|(x, required)| *required > x.free)
+    }
+
+    /// the impact of copying or moving only the selected files
+    pub fn selected(&self, selection: &HashSet<PathBuf>) -> Impact {
+        Impact {
+            files: self
+                .files
+                .iter()
+                .filter(|x| selection.contains(&x.path))
+                .cloned()
+                .collect(),
+            destinations: self.destinations.clone(),
+        }
+    }
+
+    fn add(&mut self, path: PathBuf, size: u64, volume: &Path, copies: bool, mirrors: &[PathBuf]) {
+        let mut copied_to = mirrors.to_vec();
+        if copies {
+            copied_to.push(volume.to_owned());
+        }
+        self.files.push(MatchedFile {
+            path,
+            size,
+            copied_to,
+        });
+    }
+
+    fn add_destination(&mut self, volume: PathBuf) -> io::Result<()> {
+        if !self.destinations.iter().any(|x| x.volume == volume) {
+            let free = free_space(&volume)?;
+            self.destinations.push(Destination { volume, free });
+        }
+        Ok(())
+    }
+}
+
+/// collects the files matching the patterns like `rename_files` without copying them.
+/// the files in use or already copied are also counted
+pub(crate) fn estimate(config: &ConfigFile) -> io::Result<Impact> {
+    let mut impact = Impact::default();
+    for source in config.sources() {
+        let output = config.output_for(source);
+        let volume = volume_of(output.folder())?;
+        // hard link in the same volume doesn't need space
+        let copies =
+            volume_of(source.folder())? != volume || (source.keep_old() && !source.hard_link());
+        impact.add_destination(volume.clone())?;
+        let mut mirrors = Vec::new();
+        for mirror in output.mirror_folders() {
+            let mirror = volume_of(mirror)?;
+            impact.add_destination(mirror.clone())?;
+            mirrors.push(mirror);
+        }
+        for path in source_entries(&RealFs, source, &output)? {
+            let file_name = path.file_name().unwrap().to_string_lossy();
+            if source.pattern().is_match(&file_name) && !source.is_excluded(&file_name) {
+                let metadata = RealFs.metadata(&path)?;
+                if !metadata.is_symlink {
+                    impact.add(path.clone(), metadata.len, &volume, copies, &mirrors);
+                }
+            }
+        }
+    }
+    if let Some(pictures) = config.pictures() {
+        let volume = volume_of(pictures.output_folder())?;
+        let copies = pictures.keep_old() || volume_of(pictures.folder())? != volume;
+        impact.add_destination(volume.clone())?;
+        estimate_pictures(pictures, pictures.folder(), &volume, copies, &mut impact)?;
+    }
+    Ok(impact)
+}
+
+fn estimate_pictures(
+    config: &Pictures,
+    folder: &Path,
+    volume: &Path,
+    copies: bool,
+    impact: &mut Impact,
+) -> io::Result<()> {
+    for entry in fs::read_dir(folder)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            if &path != config.output_folder() {
+                estimate_pictures(config, &path, volume, copies, impact)?;
+            }
+        } else if config
+            .pattern()
+            .is_match(&entry.file_name().to_string_lossy())
+        {
+            impact.add(path, entry.metadata()?.len(), volume, copies, &[]);
+        }
+    }
+    Ok(())
+}
+
+/// the root of the volume the path is in. the path doesn't have to exist
+pub(crate) fn volume_of(path: &Path) -> io::Result<PathBuf> {
+    let mut buffer = [0u16; 261];
+    unsafe {
+        GetVolumePathNameW(PCWSTR(to_wide(path).as_ptr()), &mut buffer).ok()?;
+    }
+    let len = buffer.iter().position(|&x| x == 0).unwrap_or(buffer.len());
+    Ok(PathBuf::from(String::from_utf16_lossy(&buffer[..len])))
+}
+
+fn free_space(volume: &Path) -> io::Result<u64> {
+    let mut free = 0u64;
+    unsafe {
+        GetDiskFreeSpaceExW(
+            PCWSTR(to_wide(volume).as_ptr()),
+            Some(&mut free),
+            None,
+            None,
+        )
+        .ok()?;
+    }
+    Ok(free)
+}
+
+/// formats the size in bytes like `1.5 MiB`
+pub(crate) fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
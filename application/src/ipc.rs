@@ -0,0 +1,150 @@
+// VRC Log Renamer - the tool to rename logs of VRChat to have date info
+// Copyright (C) 2022 anatawa12
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The named pipe server to trigger renaming from other local tools.
+//!
+//! Clients connect to `\\.\pipe\vrc-log-renamer`, write one command line and read the response
+//! until the server closes the connection.
+//! Commands are `rename`, `status`, `reload-config` and `stop`.
+
+use crate::config::{read_config, ConfigFile};
+use crate::{config_file_path, notify, rename_main, RenameSummary, PIPE_CONNECTED_OS_CODE};
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use std::fs::File;
+use std::io;
+use std::io::{BufRead, BufReader, Write};
+use std::os::windows::io::FromRawHandle;
+use windows::core::HSTRING;
+use windows::Win32::Foundation::{HANDLE, INVALID_HANDLE_VALUE};
+use windows::Win32::Storage::FileSystem::{FlushFileBuffers, PIPE_ACCESS_DUPLEX};
+use windows::Win32::System::Pipes::{
+    ConnectNamedPipe, CreateNamedPipeW, DisconnectNamedPipe, PIPE_READMODE_BYTE,
+    PIPE_REJECT_REMOTE_CLIENTS, PIPE_TYPE_BYTE, PIPE_WAIT,
+};
+
+pub(crate) const PIPE_NAME: &str = r"\\.\pipe\vrc-log-renamer";
+
+struct ServerState {
+    config: ConfigFile,
+    last_run: Option<(DateTime<Local>, Result<RenameSummary, String>)>,
+}
+
+pub(crate) fn serve() -> Result<()> {
+    let mut state = ServerState {
+        config: read_config()?,
+        last_run: None,
+    };
+
+    let handle = unsafe {
+        CreateNamedPipeW(
+            &HSTRING::from(PIPE_NAME),
+            PIPE_ACCESS_DUPLEX,
+            PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+            1,
+            4096,
+            4096,
+            0,
+            None,
+        )
+    };
+    if handle == INVALID_HANDLE_VALUE {
+        return Err(io::Error::last_os_error().into());
+    }
+    // the File closes the pipe handle on drop
+    let pipe = unsafe { File::from_raw_handle(handle.0 as _) };
+
+    info!("listening on {}", PIPE_NAME);
+    loop {
+        if !unsafe { ConnectNamedPipe(handle, None) }.as_bool() {
+            // the client connected between create and connect
+            let error = io::Error::last_os_error();
+            if error.raw_os_error() != Some(PIPE_CONNECTED_OS_CODE) {
+                return Err(error.into());
+            }
+        }
+        let result = handle_client(&pipe, handle, &mut state);
+        unsafe { DisconnectNamedPipe(handle) };
+        match result {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(e) => warn!("error handling ipc client: {}", e),
+        }
+    }
+    info!("ipc server stopped");
+    Ok(())
+}
+
+/// returns false if the server should stop
+fn handle_client(pipe: &File, handle: HANDLE, state: &mut ServerState) -> io::Result<bool> {
+    let mut line = String::new();
+    BufReader::new(pipe).read_line(&mut line)?;
+    let command = line.trim();
+    info!("ipc command: {}", command);
+
+    let mut writer = pipe;
+    let mut keep_running = true;
+    match command {
+        "rename" => {
+            let summary = rename_main(&state.config, None);
+            notify::notify_run(&state.config, &summary);
+            let summary = summary.map_err(|e| e.to_string());
+            write_summary(&mut writer, &summary)?;
+            state.last_run = Some((Local::now(), summary));
+        }
+        "status" => {
+            writeln!(writer, "ok")?;
+            writeln!(writer, "config: {}", config_file_path().display())?;
+            match &state.last_run {
+                None => writeln!(writer, "last run: never")?,
+                Some((time, summary)) => {
+                    writeln!(writer, "last run: {}", time.to_rfc3339())?;
+                    write_summary(&mut writer, summary)?;
+                }
+            }
+        }
+        "reload-config" => match read_config() {
+            Ok(config) => {
+                state.config = config;
+                writeln!(writer, "ok")?;
+            }
+            Err(e) => writeln!(writer, "error: {}", e)?,
+        },
+        "stop" => {
+            writeln!(writer, "ok")?;
+            keep_running = false;
+        }
+        unknown => writeln!(writer, "error: unknown command: {}", unknown)?,
+    }
+    // wait for client to read the response before disconnecting
+    unsafe { FlushFileBuffers(handle) };
+    Ok(keep_running)
+}
+
+fn write_summary(writer: &mut impl Write, summary: &Result<RenameSummary, String>) -> io::Result<()> {
+    match summary {
+        Ok(summary) => {
+            writeln!(writer, "renamed: {}", summary.renamed.len())?;
+            writeln!(writer, "skipped: {}", summary.skipped)?;
+            writeln!(writer, "errors: {}", summary.errors.len())?;
+            for error in &summary.errors {
+                writeln!(writer, "error: {}: {}", error.path.display(), error.error)?;
+            }
+        }
+        Err(e) => writeln!(writer, "error: {}", e)?,
+    }
+    Ok(())
+}
@@ -0,0 +1,107 @@
+// VRC Log Renamer - the tool to rename logs of VRChat to have date info
+// Copyright (C) 2022 anatawa12
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The journal is the append-only record of copies and moves in progress, saved next to the config
+//! file. A destination begun but not ended was left half-written by an interrupted run like power
+//! loss, and is removed on the next run so it's not mistaken for a completed copy.
+
+use crate::config_file_path;
+use std::fs;
+use std::io::{self, BufRead, BufReader, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+const BEGIN: &str = "begin";
+const END: &str = "end";
+
+pub struct Journal {
+    /// None for the simulation which doesn't write anything
+    file: Option<fs::File>,
+}
+
+fn journal_file_path() -> PathBuf {
+    config_file_path().with_file_name("journal.txt")
+}
+
+impl Journal {
+    /// cleans up the destinations left by the interrupted run and starts new journal
+    pub fn open() -> io::Result<Self> {
+        let path = journal_file_path();
+        for (src, dst) in interrupted(&path)? {
+            clean_up(&src, &dst)?;
+        }
+        fs::create_dir_all(path.parent().unwrap())?;
+        let file = fs::File::create(&path)?;
+        Ok(Self { file: Some(file) })
+    }
+
+    /// the journal recording nothing
+    pub fn disabled() -> Self {
+        Self { file: None }
+    }
+
+    /// records that the source is about to be copied or moved to the destination
+    pub fn begin(&mut self, src: &Path, dst: &Path) -> io::Result<()> {
+        self.write(&format!("{}\t{}\t{}", BEGIN, src.display(), dst.display()))
+    }
+
+    /// records that the destination is completely written
+    pub fn end(&mut self, dst: &Path) -> io::Result<()> {
+        self.write(&format!("{}\t{}", END, dst.display()))
+    }
+
+    fn write(&mut self, line: &str) -> io::Result<()> {
+        let file = match &mut self.file {
+            Some(file) => file,
+            None => return Ok(()),
+        };
+        writeln!(file, "{}", line)?;
+        // the entry must be on the disk before the copy starts
+        file.sync_data()
+    }
+}
+
+/// the sources and destinations begun but not ended
+fn interrupted(path: &Path) -> io::Result<Vec<(PathBuf, PathBuf)>> {
+    let file = match fs::File::open(path) {
+        Ok(file) => file,
+        Err(ref e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut begun = Vec::<(PathBuf, PathBuf)>::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        // the last line may be broken by the interruption
+        match line.split('\t').collect::<Vec<_>>().as_slice() {
+            [BEGIN, src, dst] => begun.push((src.into(), dst.into())),
+            [END, dst] => begun.retain(|(_, x)| x != Path::new(dst)),
+            _ => {}
+        }
+    }
+    Ok(begun)
+}
+
+/// removes the destination if the source still exists.
+/// if the source doesn't exist, the move is completed since the source is removed last
+fn clean_up(src: &Path, dst: &Path) -> io::Result<()> {
+    if !src.exists() || !dst.exists() {
+        return Ok(());
+    }
+    warn!(
+        "{} was left by interrupted run. removing to copy again",
+        dst.display()
+    );
+    fs::remove_file(dst)
+}
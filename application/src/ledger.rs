@@ -0,0 +1,152 @@
+// VRC Log Renamer - the tool to rename logs of VRChat to have date info
+// Copyright (C) 2022 anatawa12
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The ledger is the record of log files archived by this tool, saved next to the config file.
+
+use crate::config_file_path;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Ledger {
+    #[serde(default)]
+    files: Vec<LedgerEntry>,
+    /// the last run which examined all the logs without errors
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    watermark: Option<Watermark>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct Watermark {
+    /// the start time of the run in RFC 3339. the logs modified after this are examined next time
+    started_at: String,
+    /// the hash of the source config. all the logs are examined again if the sources are changed
+    sources_sha256: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LedgerEntry {
+    path: PathBuf,
+    /// None if upload is not configured when archived, which is not uploaded later
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    uploaded: Option<bool>,
+    /// the hash of the source log to find the same log archived before
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source_sha256: Option<String>,
+}
+
+fn ledger_file_path() -> PathBuf {
+    config_file_path().with_file_name("ledger.toml")
+}
+
+impl Ledger {
+    pub fn load() -> io::Result<Self> {
+        match fs::read_to_string(ledger_file_path()) {
+            Ok(toml) => toml::from_str(&toml).map_err(|e| Error::new(ErrorKind::InvalidData, e)),
+            Err(ref e) if e.kind() == ErrorKind::NotFound => Ok(Default::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = ledger_file_path();
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(
+            path,
+            toml::to_string(self).map_err(|e| Error::new(ErrorKind::InvalidData, e))?,
+        )
+    }
+
+    /// records the archived file with the hash of the source if computed.
+    /// the file is uploaded later only if `upload` is true
+    pub fn record(&mut self, path: &Path, source_sha256: Option<String>, upload: bool) {
+        match self.entry_mut(path) {
+            Some(entry) => {
+                if source_sha256.is_some() {
+                    entry.source_sha256 = source_sha256;
+                }
+            }
+            None => self.files.push(LedgerEntry {
+                path: path.to_owned(),
+                uploaded: upload.then_some(false),
+                source_sha256,
+            }),
+        }
+    }
+
+    /// returns the archived file which still exists and whose source has the hash
+    pub fn find_by_hash(&self, source_sha256: &str) -> Option<&Path> {
+        self.files
+            .iter()
+            .find(|x| x.source_sha256.as_deref() == Some(source_sha256) && x.path.exists())
+            .map(|x| x.path.as_path())
+    }
+
+    /// returns the start time of the last complete run if the sources are not changed since then
+    pub fn watermark(&self, sources_sha256: &str) -> Option<DateTime<Local>> {
+        let watermark = self.watermark.as_ref()?;
+        if watermark.sources_sha256 != sources_sha256 {
+            return None;
+        }
+        DateTime::parse_from_rfc3339(&watermark.started_at)
+            .ok()
+            .map(|x| x.with_timezone(&Local))
+    }
+
+    pub fn set_watermark(&mut self, started_at: DateTime<Local>, sources_sha256: String) {
+        self.watermark = Some(Watermark {
+            started_at: started_at.to_rfc3339(),
+            sources_sha256,
+        });
+    }
+
+    /// returns the number of archived files recorded
+    pub fn archived_count(&self) -> usize {
+        self.files.len()
+    }
+
+    /// returns all the archived files recorded
+    pub fn archived(&self) -> impl Iterator<Item = &Path> {
+        self.files.iter().map(|x| x.path.as_path())
+    }
+
+    /// whether the file is the renamed log recorded
+    pub fn is_archived(&self, path: &Path) -> bool {
+        self.files.iter().any(|x| x.path == path)
+    }
+
+    fn entry_mut(&mut self, path: &Path) -> Option<&mut LedgerEntry> {
+        self.files.iter_mut().find(|x| x.path == path)
+    }
+
+    /// returns archived files which still exist and are not uploaded yet
+    pub fn not_uploaded(&self) -> Vec<PathBuf> {
+        self.files
+            .iter()
+            .filter(|x| x.uploaded == Some(false) && x.path.exists())
+            .map(|x| x.path.clone())
+            .collect()
+    }
+
+    pub fn mark_uploaded(&mut self, path: &Path) {
+        if let Some(entry) = self.entry_mut(path) {
+            entry.uploaded = Some(true);
+        }
+    }
+}
@@ -0,0 +1,62 @@
+// VRC Log Renamer - the tool to rename logs of VRChat to have date info
+// Copyright (C) 2022 anatawa12
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The lock prevents scheduled runs and GUI runs from copying same files concurrently.
+
+use anyhow::{bail, Context, Result};
+use windows::Win32::Foundation::{
+    CloseHandle, HANDLE, WAIT_ABANDONED, WAIT_OBJECT_0, WAIT_TIMEOUT,
+};
+use windows::Win32::System::Threading::{CreateMutexW, ReleaseMutex, WaitForSingleObject};
+
+/// the time to wait for other run to finish before giving up
+const WAIT_MILLIS: u32 = 60 * 1000;
+
+/// the lock for renaming. released on drop.
+/// the mutex is owned by the thread so this must be dropped on the thread acquired it
+pub(crate) struct RenameLock(HANDLE);
+
+impl RenameLock {
+    /// acquires the lock waiting for other run to finish for a while
+    pub(crate) fn acquire() -> Result<Self> {
+        // Global namespace to lock with the scheduled runs in other sessions
+        let handle =
+            unsafe { CreateMutexW(None, false, windows::w!("Global\\vrc-log-renamer-rename")) }
+                .context("creating rename lock")?;
+        let lock = Self(handle);
+        info!("waiting for other renamer run if running");
+        match unsafe { WaitForSingleObject(handle, WAIT_MILLIS) } {
+            // abandoned means other run exited without releasing, so we can take it
+            WAIT_OBJECT_0 | WAIT_ABANDONED => Ok(lock),
+            WAIT_TIMEOUT => {
+                // we don't own the mutex so don't release it
+                unsafe { CloseHandle(lock.0) };
+                std::mem::forget(lock);
+                bail!("another renamer run is in progress. try again later")
+            }
+            _ => Err(std::io::Error::last_os_error()).context("waiting for rename lock"),
+        }
+    }
+}
+
+impl Drop for RenameLock {
+    fn drop(&mut self) {
+        unsafe {
+            ReleaseMutex(self.0);
+            CloseHandle(self.0);
+        }
+    }
+}
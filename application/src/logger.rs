@@ -0,0 +1,70 @@
+// VRC Log Renamer - the tool to rename logs of VRChat to have date info
+// Copyright (C) 2022 anatawa12
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::Mutex;
+
+static STDOUT_RESERVED: AtomicBool = AtomicBool::new(false);
+static SINK: Mutex<Option<Sender<String>>> = Mutex::new(None);
+
+/// prints progress message of renamer.
+/// printed to stderr if stdout is reserved for machine-readable output.
+macro_rules! info {
+    ($($arg: tt)*) => {
+        $crate::logger::info(format_args!($($arg)*))
+    };
+}
+
+/// prints error message of renamer which doesn't stop renaming other files.
+macro_rules! warn {
+    ($($arg: tt)*) => {
+        $crate::logger::warn(format_args!($($arg)*))
+    };
+}
+
+/// makes `info!` print to stderr to keep stdout machine-readable
+pub fn reserve_stdout() {
+    STDOUT_RESERVED.store(true, Ordering::Relaxed);
+}
+
+pub fn info(args: fmt::Arguments) {
+    if STDOUT_RESERVED.load(Ordering::Relaxed) {
+        eprintln!("{}", args);
+    } else {
+        println!("{}", args);
+    }
+    send_to_sink(args);
+}
+
+pub fn warn(args: fmt::Arguments) {
+    eprintln!("{}", args);
+    send_to_sink(args);
+}
+
+/// also sends the messages of `info!` and `warn!` to the channel like to show them in GUI.
+/// None to stop sending
+pub fn set_sink(sink: Option<Sender<String>>) {
+    *SINK.lock().unwrap() = sink;
+}
+
+fn send_to_sink(args: fmt::Arguments) {
+    if let Some(sink) = SINK.lock().unwrap().as_ref() {
+        // the receiver may be dropped like closed window
+        let _ = sink.send(args.to_string());
+    }
+}
@@ -19,56 +19,274 @@
 
 #[macro_use]
 mod i18n;
+#[macro_use]
+mod logger;
+mod checksums;
 mod config;
+mod crash_report;
+mod crashes;
+mod detect;
 mod gui;
+mod hooks;
+mod impact;
+mod ipc;
+mod journal;
+mod ledger;
+mod lock;
+mod notify;
+#[cfg(feature = "package")]
+mod package;
+mod pattern;
+mod pictures;
+mod preview;
+mod profile;
+mod setup;
+mod shadow;
+mod split;
+mod stats;
 mod task_managers;
+mod theme;
+mod tooltip;
+mod ui_state;
+mod update;
+mod upload;
+mod vfs;
 
-use crate::config::{read_config, ConfigFile};
-use crate::task_managers::{register_task_manager, unregister_task_manager};
-use anyhow::{bail, Result};
+use crate::config::{
+    read_config, save_config, ConfigFile, Dedupe, InUse, Installer, LaunchTime, Output, Source,
+    TimeZone, Unparsable, CONFIG_KEYS,
+};
+use crate::journal::Journal;
+use crate::ledger::Ledger;
+use crate::pattern::Collision;
+use crate::profile::Phase;
+use crate::stats::Stats;
+use crate::task_managers::{export_task, import_task, install, uninstall};
+use crate::vfs::{Env, FileSystem, FixedClock, MemoryFs};
+use anyhow::{bail, Context, Result};
 use chrono::format::Item;
-use chrono::{DateTime, Local, NaiveDateTime, Utc};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, Utc};
 use once_cell::race::OnceBox;
-use regex::Captures;
+use regex::{Captures, Regex};
+use serde::Serialize;
+use serde_json::json;
 use std::borrow::Cow;
+use std::collections::HashSet;
 use std::convert::Infallible;
-use std::io::{Read, Write};
+use std::io::{BufRead, Read, Write};
 use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::SystemTime;
 use std::{fs, io};
 use take_if::TakeIf;
 use winsafe::co::{KF, KNOWNFOLDERID};
 use winsafe::SHGetKnownFolderPath;
 
 pub static LICENSES_TXT: &'static str = include_str!(concat!(env!("OUT_DIR"), "/licenses.txt"));
+/// the commit, the date and the target triple of the build embedded by build.rs
+pub(crate) const GIT_HASH: &str = env!("BUILD_GIT_HASH");
+pub(crate) const BUILD_DATE: &str = env!("BUILD_DATE");
+pub(crate) const BUILD_TARGET: &str = env!("BUILD_TARGET");
+
+/// exit codes of the process. Task Scheduler records them as the last run result
+mod exit_code {
+    pub const SUCCESS: u8 = 0;
+    pub const ERROR: u8 = 1;
+    pub const PARTIAL_FAILURE: u8 = 2;
+    pub const CONFIG_ERROR: u8 = 3;
+    pub const RENAME_FAILED: u8 = 4;
+}
+
+fn main() -> ExitCode {
+    crash_report::install();
+    match main_mode() {
+        Ok(code) => ExitCode::from(code),
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            ExitCode::from(exit_code::ERROR)
+        }
+    }
+}
 
-fn main() -> Result<()> {
-    let mut args = std::env::args();
-    args.next();
-    match args.next().as_ref().map(String::as_str) {
+fn main_mode() -> Result<u8> {
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+    let alloc_console = take_flag(&mut args, "--console");
+    let format = take_option(&mut args, "--format");
+    let full = take_flag(&mut args, "--full");
+    let since = take_option(&mut args, "--since");
+    let until = take_option(&mut args, "--until");
+    let now = take_option(&mut args, "--now");
+    if take_flag(&mut args, "--profile") {
+        profile::enable();
+    }
+    let mode = args.first().map(String::as_str);
+    if !matches!(mode, None | Some("gui")) {
+        attach_console(alloc_console);
+    }
+    let format = match format?.as_deref() {
+        None | Some("text") => OutputFormat::Text,
+        Some("json") => {
+            logger::reserve_stdout();
+            OutputFormat::Json
+        }
+        Some(unknown) => bail!("unknown output format: {}", unknown),
+    };
+    match mode {
         None | Some("gui") => {
             gui::gui_main()?;
         }
         Some("rename") | Some("scheduled") => {
-            let config = read_config()?;
-            rename_main(&config)?;
+            let mut config = match read_config() {
+                Ok(config) => config,
+                Err(e) => {
+                    eprintln!("error reading config: {}", e);
+                    return Ok(exit_code::CONFIG_ERROR);
+                }
+            };
+            apply_run_options(&mut config, since?, until?, full)?;
+            if mode == Some("scheduled") && config.low_priority() {
+                enter_background_mode();
+            }
+            let summary = rename_main(&config, None);
+            notify::notify_run(&config, &summary);
+            print_summary(&summary, format)?;
+            return Ok(match summary {
+                Ok(summary) if summary.errors.is_empty() => exit_code::SUCCESS,
+                Ok(_) => exit_code::PARTIAL_FAILURE,
+                Err(e) => {
+                    eprintln!("Error: {:?}", e);
+                    exit_code::RENAME_FAILED
+                }
+            });
+        }
+        Some("preview") => {
+            let mut config = read_config()?;
+            apply_run_options(&mut config, since?, until?, full)?;
+            // stdout is for the list of planned logs
+            logger::reserve_stdout();
+            print_preview(&preview::preview_files(&config)?, format)?;
+        }
+        Some("simulate") => {
+            let mut config = read_config()?;
+            apply_run_options(&mut config, since?, until?, full)?;
+            let now = match now?.as_deref() {
+                None => SystemTime::now(),
+                Some(now) => parse_now(now)?,
+            };
+            // stdout is for the renamed logs
+            logger::reserve_stdout();
+            let summary = simulate(&config, now);
+            if let (OutputFormat::Text, Ok(summary)) = (format, &summary) {
+                for file in &summary.renamed {
+                    println!("{} -> {}", file.source.display(), file.destination.display());
+                }
+            }
+            print_summary(&summary, format)?;
+        }
+        Some("status") => {
+            print_status(format)?;
+        }
+        Some("verify-archive") => {
+            verify_archive(&read_config()?, format)?;
+        }
+        Some("clean-empty") => {
+            clean_empty(&read_config()?, format)?;
+        }
+        Some("doctor") => {
+            doctor(&read_config()?, format)?;
+        }
+        Some("stats") => {
+            let stats = update_stats(&Ledger::load()?)?;
+            print_stats(&stats.summary(STATS_WEEKS, STATS_WORLDS), format)?;
+        }
+        Some("config") => {
+            config_command(&args[1..])?;
+        }
+        Some("print-default-config") => {
+            print!("{}", ConfigFile::default_config_text());
+        }
+        Some("serve") => {
+            ipc::serve()?;
+        }
+        Some("task") => {
+            task_command(&args[1..])?;
+        }
+        Some("sessions") => {
+            sessions_command(&args[1..])?;
         }
         Some("register_schedule") => {
-            register_task_manager()?;
+            let config = read_config()?;
+            match install(config.schedule())? {
+                Some(next) => println!("next run: {}", next.format("%Y-%m-%d %H:%M")),
+                None if config.schedule().installer() == Installer::RunKey => {
+                    println!("next run: at logon")
+                }
+                None => println!("next run: unknown"),
+            }
         }
         Some("unregister_schedule") => {
-            unregister_task_manager()?;
+            uninstall(read_config()?.schedule())?;
+        }
+        Some("install") => {
+            let installed = setup::install_app()?;
+            println!("installed to {}", installed.display());
+        }
+        Some("uninstall") => {
+            setup::uninstall_app(read_config()?.schedule())?;
+        }
+        #[cfg(feature = "package")]
+        Some("package") => {
+            package::package_command(&args[1..])?;
         }
         Some("licenses") => {
             print!("{}", LICENSES_TXT);
         }
+        Some("version") => {
+            print_version(format)?;
+        }
         Some("help") => {
             println!("gui(default): run in gui mode.");
             println!("rename: run renamer with saved config.");
             println!("scheduled: run renamer as a scheduled task. currently same as 'rename'");
-            println!("register_schedule: register to task scheduler");
-            println!("unregister_schedule: unregister from task scheduler");
+            println!("preview: print what rename would do for each log without renaming");
+            println!("simulate: rename on the copy of the folders in memory and print the result");
+            println!("status: print config and archived logs status");
+            println!("verify-archive: hash the logs in SHA256SUMS again to find corrupted ones");
+            println!("clean-empty: remove empty folders in the output folders");
+            println!("doctor: check the config for settings which may not work as expected");
+            println!("stats: print play time per week and most visited worlds in archived logs");
+            println!("config get <key>: print the value of config");
+            println!("config set <key> <value>: validate and save the value to config");
+            println!("print-default-config: print all config keys with default values");
+            println!("serve: run named pipe server to accept rename, status, reload-config and stop");
+            println!("register_schedule: register to task scheduler or Run key in registry");
+            println!("unregister_schedule: unregister from task scheduler and Run key in registry");
+            println!("install: copy this exe to LocalAppData\\Programs and register the schedule");
+            println!("uninstall: unregister the schedule and remove the installed exe");
+            println!("sessions export <file.csv|file.ics>: write play sessions as CSV or calendar");
+            println!("task export <file.xml>: write the registered task definition to the file");
+            println!("task import <file.xml>: register the task definition in the file");
+            #[cfg(feature = "package")]
+            println!("package winget|inno <exe> <file>: write winget manifest or Inno Setup file");
             println!("licenses: print list of dependencies & licenses");
+            println!("version: print the version, commit, build date and target of this exe");
             println!("help: print this msesage");
+            println!();
+            println!("options:");
+            println!("--console: open new console window to show output of the mode");
+            println!("--format <text|json>: output format of the modes printing results");
+            println!("--full: examine all logs even if not modified since the last run");
+            println!("--since <yyyy-mm-dd>: rename only logs launched on or after the date");
+            println!("--until <yyyy-mm-dd>: rename only logs launched on or before the date");
+            println!("--now <yyyy-mm-ddThh:mm:ss>: the current time assumed by simulate");
+            println!("--profile: print the time of each phase of rename, scheduled and simulate");
+            println!();
+            println!("exit codes of rename and scheduled:");
+            println!("0: all logs are renamed or skipped");
+            println!("1: unexpected error");
+            println!("2: some logs failed to rename");
+            println!("3: error reading config");
+            println!("4: renaming failed as a whole, like the source folder does not exist");
         }
         Some(unknown) => {
             bail!(
@@ -78,114 +296,1360 @@ fn main() -> Result<()> {
         }
     }
 
+    Ok(exit_code::SUCCESS)
+}
+
+/// applies the command line options limiting the logs to rename
+fn apply_run_options(
+    config: &mut ConfigFile,
+    since: Option<String>,
+    until: Option<String>,
+    full: bool,
+) -> Result<()> {
+    let since = since.as_deref().map(Source::parse_date).transpose()?;
+    let until = until.as_deref().map(Source::parse_date).transpose()?;
+    config.set_date_range(since.flatten(), until.flatten());
+    if full {
+        config.set_value("incremental", "false")?;
+    }
+    Ok(())
+}
+
+fn config_command(args: &[String]) -> Result<()> {
+    match args.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        ["get", key] => match read_config()?.get_value(key)? {
+            Some(toml::Value::String(value)) => println!("{}", value),
+            Some(value) => println!("{}", value),
+            None => bail!("{} is not set", key),
+        },
+        ["set", key, value] => {
+            let mut config = read_config()?;
+            config.set_value(key, value)?;
+            // the config must be loadable after saving
+            config
+                .validate()
+                .with_context(|| format!("cannot set {}", key))?;
+            save_config(&config)?;
+        }
+        _ => {
+            bail!(
+                "usage: config get <key> | config set <key> <value>\nkeys: {}",
+                CONFIG_KEYS.join(", ")
+            )
+        }
+    }
+    Ok(())
+}
+
+fn task_command(args: &[String]) -> Result<()> {
+    match args.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        ["export", file] => {
+            let xml = export_task(read_config()?.schedule())?;
+            // task scheduler and schtasks read the XML in UTF-16 with BOM
+            let mut bytes = vec![0xFF, 0xFE];
+            bytes.extend(xml.encode_utf16().flat_map(u16::to_le_bytes));
+            fs::write(file, bytes).with_context(|| format!("writing {}", file))?;
+        }
+        ["import", file] => {
+            let bytes = fs::read(file).with_context(|| format!("reading {}", file))?;
+            let xml = decode_xml_text(&bytes).with_context(|| format!("decoding {}", file))?;
+            match import_task(read_config()?.schedule(), &xml)? {
+                Some(next) => println!("next run: {}", next.format("%Y-%m-%d %H:%M")),
+                None => println!("next run: unknown"),
+            }
+        }
+        _ => bail!("usage: task export <file.xml> | task import <file.xml>"),
+    }
+    Ok(())
+}
+
+fn sessions_command(args: &[String]) -> Result<()> {
+    match args.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        ["export", file] => {
+            let stats = update_stats(&Ledger::load()?)?;
+            let extension = Path::new(file).extension().unwrap_or_default();
+            let text = match extension.to_string_lossy().to_ascii_lowercase().as_str() {
+                "csv" => stats.sessions_csv(),
+                "ics" => stats.sessions_ics(),
+                _ => bail!("unknown export format: {}. use .csv or .ics", file),
+            };
+            fs::write(file, text).with_context(|| format!("writing {}", file))?;
+        }
+        _ => bail!("usage: sessions export <file.csv|file.ics>"),
+    }
     Ok(())
 }
 
-fn rename_main(config: &ConfigFile) -> Result<()> {
-    let out_folder = config.output().folder();
-    fs::create_dir_all(out_folder)?;
-    for entry in fs::read_dir(config.source().folder())? {
+/// decodes UTF-16 XML with BOM or UTF-8 XML
+fn decode_xml_text(bytes: &[u8]) -> Result<String> {
+    match bytes {
+        [0xFF, 0xFE, rest @ ..] => {
+            let units = rest
+                .chunks_exact(2)
+                .map(|x| u16::from_le_bytes([x[0], x[1]]))
+                .collect::<Vec<_>>();
+            Ok(String::from_utf16(&units)?)
+        }
+        [0xEF, 0xBB, 0xBF, rest @ ..] => Ok(String::from_utf8(rest.to_vec())?),
+        _ => Ok(String::from_utf8(bytes.to_vec())?),
+    }
+}
+
+/// removes the flag from args and returns true if found
+fn take_flag(args: &mut Vec<String>, flag: &str) -> bool {
+    let len = args.len();
+    args.retain(|x| x != flag);
+    len != args.len()
+}
+
+/// removes the option and its value from args and returns the value if found
+fn take_option(args: &mut Vec<String>, name: &str) -> Result<Option<String>> {
+    match args.iter().position(|x| x == name) {
+        None => Ok(None),
+        Some(i) if i + 1 < args.len() => {
+            let value = args.remove(i + 1);
+            args.remove(i);
+            Ok(Some(value))
+        }
+        Some(_) => bail!("{} requires a value", name),
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum OutputFormat {
+    Text,
+    Json,
+}
+
+fn print_summary(summary: &Result<RenameSummary>, format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Text => {
+            if let Ok(summary) = summary {
+                println!(
+                    "renamed: {}, skipped: {}, errors: {}",
+                    summary.renamed.len(),
+                    summary.skipped,
+                    summary.errors.len()
+                );
+            }
+            for phase in profile::report().into_iter().flatten() {
+                println!("{}", phase);
+            }
+        }
+        OutputFormat::Json => {
+            let mut json = match summary {
+                Ok(summary) => json!({
+                    "result": summary.result_name(),
+                    "renamed": summary.renamed,
+                    "skipped": summary.skipped,
+                    "errors": summary.errors,
+                }),
+                Err(e) => json!({
+                    "result": "failed",
+                    "error": format!("{:#}", e),
+                }),
+            };
+            if let Some(profile) = profile::report() {
+                json["profile"] = serde_json::to_value(profile)?;
+            }
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+    }
+    Ok(())
+}
+
+fn print_preview(planned: &[preview::PlannedFile], format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Text => {
+            for file in planned {
+                print!("{}: {}", file.action.name(), file.source.display());
+                if let Some(destination) = &file.destination {
+                    print!(" -> {}", destination.display());
+                }
+                if let Some(reason) = &file.reason {
+                    print!(" ({})", reason);
+                }
+                println!();
+            }
+        }
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(planned)?);
+        }
+    }
+    Ok(())
+}
+
+/// verifies the archived logs with `SHA256SUMS` in the output folders. fails if any is not ok
+fn verify_archive(config: &ConfigFile, format: OutputFormat) -> Result<()> {
+    let mut folders = config
+        .sources()
+        .map(|source| config.output_for(source).folder().to_owned())
+        .collect::<Vec<_>>();
+    folders.sort();
+    folders.dedup();
+    let mut verified = Vec::new();
+    for folder in &folders {
+        verified.extend(
+            checksums::verify(folder).with_context(|| format!("verifying {}", folder.display()))?,
+        );
+    }
+    let paths = |state| {
+        verified
+            .iter()
+            .filter(|(_, x)| *x == state)
+            .map(|(path, _)| path)
+            .collect::<Vec<_>>()
+    };
+    let corrupted = paths(checksums::Verified::Corrupted);
+    let missing = paths(checksums::Verified::Missing);
+    let ok = verified.len() - corrupted.len() - missing.len();
+    match format {
+        OutputFormat::Text => {
+            for path in &corrupted {
+                println!("corrupted: {}", path.display());
+            }
+            for path in &missing {
+                println!("missing: {}", path.display());
+            }
+            println!(
+                "ok: {}, corrupted: {}, missing: {}",
+                ok,
+                corrupted.len(),
+                missing.len()
+            );
+        }
+        OutputFormat::Json => {
+            let json = json!({
+                "ok": ok,
+                "corrupted": corrupted,
+                "missing": missing,
+            });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+    }
+    if !corrupted.is_empty() || !missing.is_empty() {
+        bail!(
+            "{} archived logs are corrupted or missing",
+            corrupted.len() + missing.len()
+        );
+    }
+    Ok(())
+}
+
+/// removes the empty folders left in the output folders like `partial` or the date folders
+fn clean_empty(config: &ConfigFile, format: OutputFormat) -> Result<()> {
+    let mut folders = config
+        .sources()
+        .map(|source| config.output_for(source).folder().to_owned())
+        .collect::<Vec<_>>();
+    folders.sort();
+    folders.dedup();
+    let mut removed = Vec::new();
+    for folder in folders.iter().filter(|x| x.is_dir()) {
+        remove_empty_folders(folder, &mut removed)
+            .with_context(|| format!("cleaning {}", folder.display()))?;
+    }
+    match format {
+        OutputFormat::Text => {
+            for path in &removed {
+                println!("removed: {}", path.display());
+            }
+            println!("removed {} empty folders", removed.len());
+        }
+        OutputFormat::Json => {
+            let json = json!({ "removed": removed });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+    }
+    Ok(())
+}
+
+/// removes the empty folders in the folder recursively and returns true if the folder is empty.
+/// the folder itself is kept, and links are not followed not to touch anything outside it
+fn remove_empty_folders(folder: &Path, removed: &mut Vec<PathBuf>) -> io::Result<bool> {
+    let mut empty = true;
+    for entry in fs::read_dir(folder)? {
         let entry = entry?;
-        if let Some(captures) = config
-            .source()
-            .pattern()
-            .captures(&entry.file_name().to_string_lossy())
-        {
-            println!("{} matches pattern. checking", entry.path().display());
-            if let Some(err) = move_log_file(config, &entry.path(), captures).err() {
-                eprintln!("error moving '{}': {}", entry.path().display(), err);
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() && !file_type.is_symlink() {
+            let path = entry.path();
+            if remove_empty_folders(&path, removed)? {
+                fs::remove_dir(&path)?;
+                removed.push(path);
+                continue;
+            }
+        }
+        empty = false;
+    }
+    Ok(empty)
+}
+
+/// prints the warnings for the config which is valid but may not work as expected
+fn doctor(config: &ConfigFile, format: OutputFormat) -> Result<()> {
+    let mut patterns = config
+        .sources()
+        .map(|source| config.output_for(source))
+        .filter_map(|output| {
+            let collision = pattern::collision(output.pattern())?;
+            Some((output.pattern_as_string(), collision))
+        })
+        .collect::<Vec<_>>();
+    patterns.sort_by(|(a, _), (b, _)| a.cmp(b));
+    patterns.dedup_by(|(a, _), (b, _)| a == b);
+    let overlaps = config
+        .sources()
+        .map(|source| (source, config.output_for(source)))
+        .filter(|(source, output)| output_overlaps(source, output))
+        .collect::<Vec<_>>();
+    match format {
+        OutputFormat::Text => {
+            for (pattern, collision) in &patterns {
+                let logs = match collision {
+                    Collision::Always => "all logs",
+                    Collision::SameDay => "the logs launched on the same day",
+                    Collision::SameHour => "the logs launched in the same hour",
+                    Collision::SameMinute => "the logs launched in the same minute",
+                };
+                println!(
+                    "warning: output pattern '{}' gives the same name to {}. \
+                    add {{seq}} or finer specifiers like %S",
+                    pattern, logs
+                );
+            }
+            for (source, output) in &overlaps {
+                println!(
+                    "warning: the logs renamed to '{}' match the pattern of the source '{}' \
+                    and are found as logs again. change the output folder or the pattern",
+                    output.folder().display(),
+                    source.folder().display(),
+                );
+            }
+            println!("warnings: {}", patterns.len() + overlaps.len());
+        }
+        OutputFormat::Json => {
+            let collisions = patterns.iter().map(|(pattern, collision)| {
+                json!({
+                    "kind": "pattern_collision",
+                    "pattern": pattern,
+                    "collision": collision.name(),
+                })
+            });
+            let overlaps = overlaps.iter().map(|(source, output)| {
+                json!({
+                    "kind": "output_overlaps_source",
+                    "source": source.folder(),
+                    "output": output.folder(),
+                })
+            });
+            let warnings = collisions.chain(overlaps).collect::<Vec<_>>();
+            println!("{}", serde_json::to_string_pretty(&json!({ "warnings": warnings }))?);
+        }
+    }
+    Ok(())
+}
+
+/// the number of recent weeks and the most visited worlds in the statistics
+pub(crate) const STATS_WEEKS: usize = 8;
+pub(crate) const STATS_WORLDS: usize = 10;
+
+fn print_stats(summary: &stats::Summary, format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Text => {
+            println!("sessions: {}", summary.sessions);
+            println!("total: {:.1} hours", summary.total_hours);
+            println!("hours per week:");
+            for week in &summary.weeks {
+                println!("  {}: {:.1}", week.week, week.hours);
+            }
+            println!("most visited worlds:");
+            for world in &summary.worlds {
+                println!("  {}: {}", world.world_name, world.visits);
             }
         }
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(summary)?);
+        }
+    }
+    Ok(())
+}
+
+fn print_status(format: OutputFormat) -> Result<()> {
+    let config_error = read_config().err().map(|e| e.to_string());
+    let ledger = Ledger::load()?;
+    match format {
+        OutputFormat::Text => {
+            println!("config: {}", config_file_path().display());
+            if let Some(e) = &config_error {
+                println!("config error: {}", e);
+            }
+            println!("archived: {}", ledger.archived_count());
+            println!("not uploaded: {}", ledger.not_uploaded().len());
+        }
+        OutputFormat::Json => {
+            let json = json!({
+                "config": config_file_path(),
+                "config_error": config_error,
+                "archived": ledger.archived_count(),
+                "not_uploaded": ledger.not_uploaded(),
+            });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+    }
+    Ok(())
+}
+
+fn print_version(format: OutputFormat) -> Result<()> {
+    let version = env!("CARGO_PKG_VERSION");
+    match format {
+        OutputFormat::Text => {
+            println!("vrc-log-renamer {}", version);
+            println!("commit: {}", GIT_HASH);
+            println!("build date: {}", BUILD_DATE);
+            println!("target: {}", BUILD_TARGET);
+        }
+        OutputFormat::Json => {
+            let json = json!({
+                "version": version,
+                "commit": GIT_HASH,
+                "build_date": BUILD_DATE,
+                "target": BUILD_TARGET,
+            });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
     }
     Ok(())
 }
 
-fn move_log_file(config: &ConfigFile, path: &Path, captures: Captures) -> io::Result<()> {
-    // first, try to open as read to check if the log file is not of running VRChat
-    let mut file = match fs::File::options().write(true).read(true).open(path) {
-        Ok(f) => f,
-        Err(_) => {
-            println!("{} may be used by other process. skipping", path.display());
-            return Ok(());
+/// release build is windows subsystem app so there's no console by default.
+/// attach to the console of parent process (or open new console) to make output visible.
+#[cfg(not(debug_assertions))]
+fn attach_console(alloc: bool) {
+    use windows::Win32::System::Console::{AllocConsole, AttachConsole, ATTACH_PARENT_PROCESS};
+    unsafe {
+        if alloc {
+            AllocConsole();
+        } else {
+            AttachConsole(ATTACH_PARENT_PROCESS);
+        }
+    }
+}
+
+#[cfg(debug_assertions)]
+fn attach_console(_alloc: bool) {}
+
+/// lowers cpu and I/O priority of this process to not to disturb the running game or stream.
+fn enter_background_mode() {
+    use windows::Win32::System::Threading::{
+        GetCurrentProcess, SetPriorityClass, PROCESS_MODE_BACKGROUND_BEGIN,
+    };
+    // background mode lowers I/O and memory priority as well as scheduling priority
+    if !unsafe { SetPriorityClass(GetCurrentProcess(), PROCESS_MODE_BACKGROUND_BEGIN) }.as_bool() {
+        eprintln!("failed to enter background mode: {}", io::Error::last_os_error());
+    } else {
+        info!("running with background priority");
+    }
+}
+
+/// the result of one renamer run
+#[derive(Debug, Default, Serialize)]
+pub struct RenameSummary {
+    /// the files copied or moved in this run
+    pub renamed: Vec<RenamedFile>,
+    /// the number of files skipped because they're in use or already copied
+    pub skipped: usize,
+    /// the files failed to copy or move with the error
+    pub errors: Vec<FileError>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RenamedFile {
+    pub source: PathBuf,
+    pub destination: PathBuf,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileError {
+    pub path: PathBuf,
+    pub error: String,
+}
+
+impl RenameSummary {
+    /// "success" if no errors, "partial" if some files failed
+    pub fn result_name(&self) -> &'static str {
+        if self.errors.is_empty() {
+            "success"
+        } else {
+            "partial"
+        }
+    }
+}
+
+/// copies or moves the files. only the files in the selection are processed if specified
+fn rename_main(
+    config: &ConfigFile,
+    selection: Option<&HashSet<PathBuf>>,
+) -> Result<RenameSummary> {
+    // prevent scheduled run and gui run from copying same files concurrently
+    let _lock = lock::RenameLock::acquire()?;
+    hooks::run_before_run(config)?;
+    let summary = rename_files(config, selection);
+    hooks::run_after_run(config, &summary);
+    summary
+}
+
+fn rename_files(
+    config: &ConfigFile,
+    selection: Option<&HashSet<PathBuf>>,
+) -> Result<RenameSummary> {
+    let env = Env::real();
+    let mut summary = RenameSummary::default();
+    let mut ledger = Ledger::load()?;
+    let mut journal = Journal::open()?;
+    let started_at = DateTime::<Local>::from(env.clock.now());
+    // the logs not selected or out of the date range are examined again next time
+    let complete_run = selection.is_none() && !config.sources().any(Source::has_date_range);
+    let mut after_file = |output: &Output, path: &Path, moved: &MovedLog, summary: &mut _| {
+        after_renamed(config, output, path, moved, summary)
+    };
+    rename_logs(
+        config,
+        env,
+        &mut ledger,
+        &mut journal,
+        selection,
+        &mut summary,
+        &mut after_file,
+    )?;
+    if complete_run && summary.errors.is_empty() {
+        ledger.set_watermark(started_at, sources_sha256(config));
+    }
+    if let Some(pictures) = config.pictures() {
+        let output = config.output();
+        // the ledger must be saved even if the pictures folder cannot be read
+        let result =
+            pictures::rename_pictures(pictures, output, selection, &mut journal, &mut summary);
+        if let Err(err) = result {
+            warn!("error renaming pictures: {}", err);
+            summary.errors.push(FileError {
+                path: pictures.folder().clone(),
+                error: format!("renaming pictures: {:#}", err),
+            });
+        }
+    }
+    if let Some(upload) = config.upload() {
+        let folders = config
+            .sources()
+            .map(|source| config.output_for(source).folder().clone())
+            .collect::<Vec<_>>();
+        upload::upload_archived(upload, &folders, &mut ledger, &mut summary);
+    }
+    ledger.save()?;
+    if let Err(e) = update_stats(&ledger) {
+        warn!("error updating statistics: {}", e);
+    }
+    Ok(summary)
+}
+
+/// renames the logs on the copy of the source, output and mirror folders in the memory.
+/// the files are read from the disk when needed but nothing is written
+fn simulate(config: &ConfigFile, now: SystemTime) -> Result<RenameSummary> {
+    let fs = MemoryFs::default();
+    for source in config.sources() {
+        let output = config.output_for(source);
+        fs.add_from_disk(source.folder())?;
+        fs.add_from_disk(output.folder())?;
+        for mirror in output.mirror_folders() {
+            fs.add_from_disk(mirror)?;
         }
+    }
+    let clock = FixedClock(now);
+    let env = Env {
+        fs: &fs,
+        clock: &clock,
     };
+    simulate_files(config, env, &mut Ledger::load()?)
+}
+
+fn parse_now(now: &str) -> Result<SystemTime> {
+    let now = NaiveDateTime::parse_from_str(now, "%Y-%m-%dT%H:%M:%S")
+        .with_context(|| format!("invalid time: {}. use yyyy-mm-ddThh:mm:ss", now))?;
+    match now.and_local_timezone(Local).earliest() {
+        Some(now) => Ok(now.into()),
+        None => bail!("{} does not exist in the local time zone", now),
+    }
+}
+
+/// runs the rename engine on the tree of `env` with the ledger not saved, without the journal,
+/// the pictures, the hooks and the post-processing like splitting by world
+pub(crate) fn simulate_files(
+    config: &ConfigFile,
+    env: Env,
+    ledger: &mut Ledger,
+) -> Result<RenameSummary> {
+    let mut summary = RenameSummary::default();
+    rename_logs(
+        config,
+        env,
+        ledger,
+        &mut Journal::disabled(),
+        None,
+        &mut summary,
+        &mut |_, _, _, _| {},
+    )?;
+    Ok(summary)
+}
+
+/// renames the logs of all sources. the renamed logs are recorded to the ledger, and `after_file`
+/// is called with the output, the log and where it is archived for each of them
+fn rename_logs(
+    config: &ConfigFile,
+    env: Env,
+    ledger: &mut Ledger,
+    journal: &mut Journal,
+    selection: Option<&HashSet<PathBuf>>,
+    summary: &mut RenameSummary,
+    after_file: &mut dyn FnMut(&Output, &Path, &MovedLog, &mut RenameSummary),
+) -> Result<()> {
+    let watermark = if config.incremental() && selection.is_none() {
+        ledger.watermark(&sources_sha256(config))
+    } else {
+        None
+    };
+    let mut unmodified = 0;
+    for source in config.sources() {
+        let output = config.output_for(source);
+        env.fs.create_dir_all(output.folder())?;
+        for path in source_entries(env.fs, source, &output)? {
+            let file_name = path.file_name().unwrap().to_string_lossy();
+            if let Some(captures) = source.pattern().captures(&file_name) {
+                match skip_reason(env.fs, source, &path, ledger, selection, watermark)? {
+                    Some(SkipReason::Unmodified) => {
+                        unmodified += 1;
+                        continue;
+                    }
+                    Some(_) => continue,
+                    None => {}
+                }
+                info!("{} matches pattern. checking", path.display());
+                let moved = move_log_file(env, source, &output, &path, captures, ledger, journal);
+                match moved {
+                    Ok(Some(moved)) => {
+                        after_file(&output, &path, &moved, summary);
+                        let upload = config.upload().is_some();
+                        ledger.record(&moved.path, moved.source_sha256, upload);
+                        summary.renamed.push(RenamedFile {
+                            source: path.clone(),
+                            destination: moved.path,
+                        })
+                    }
+                    Ok(None) => summary.skipped += 1,
+                    Err(err) => {
+                        warn!("error moving '{}': {}", path.display(), err);
+                        summary.errors.push(FileError {
+                            path: path.clone(),
+                            error: err.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+    }
+    if unmodified != 0 {
+        info!("{} logs not modified since the last run are skipped", unmodified);
+        summary.skipped += unmodified;
+    }
+    Ok(())
+}
+
+/// the sha256 of the log and the log with the same content archived before for `output.dedupe`.
+/// the log is not hashed if dedupe is off
+pub(crate) fn find_archived(
+    fs: &dyn FileSystem,
+    ledger: &Ledger,
+    output: &Output,
+    path: &Path,
+) -> io::Result<(Option<String>, Option<PathBuf>)> {
+    if output.dedupe() == Dedupe::Off {
+        return Ok((None, None));
+    }
+    let _timer = profile::start(Phase::Hash);
+    if profile::is_enabled() {
+        profile::count(Phase::Hash, 1, fs.metadata(path)?.len);
+    }
+    let sha256 = fs.sha256(path)?;
+    let archived = ledger.find_by_hash(&sha256).map(Path::to_owned);
+    Ok((Some(sha256), archived))
+}
+
+/// splits, collects crashes, runs the hook and writes the checksum of the renamed log
+fn after_renamed(
+    config: &ConfigFile,
+    output: &Output,
+    path: &Path,
+    moved: &MovedLog,
+    summary: &mut RenameSummary,
+) {
+    let dst_path = moved.path.as_path();
+    if output.split_worlds() {
+        match split::split_by_world(dst_path, output) {
+            Ok(files) => info!("split into {} world logs", files.len()),
+            Err(err) => {
+                warn!("error splitting '{}': {}", dst_path.display(), err);
+                summary.errors.push(FileError {
+                    path: dst_path.to_owned(),
+                    error: err.to_string(),
+                });
+            }
+        }
+    }
+    if let Some(crashes) = config.crashes() {
+        if let Err(err) = crashes::collect_crashes(crashes, dst_path) {
+            warn!("error collecting crashes: {}", err);
+            summary.errors.push(FileError {
+                path: crashes.folder().clone(),
+                error: err.to_string(),
+            });
+        }
+    }
+    hooks::run_after_file(config, path, dst_path);
+    if output.checksums() {
+        let sha256 = moved.sha256.as_deref();
+        if let Err(err) = checksums::append(output.folder(), dst_path, sha256) {
+            warn!(
+                "error writing checksum of '{}': {}",
+                dst_path.display(),
+                err
+            );
+            summary.errors.push(FileError {
+                path: dst_path.to_owned(),
+                error: err.to_string(),
+            });
+        }
+    }
+}
+
+/// the reason to skip the log before reading it, or None if it should be examined
+fn skip_reason(
+    fs: &dyn FileSystem,
+    source: &Source,
+    path: &Path,
+    ledger: &Ledger,
+    selection: Option<&HashSet<PathBuf>>,
+    watermark: Option<DateTime<Local>>,
+) -> io::Result<Option<SkipReason>> {
+    let metadata = fs.metadata(path)?;
+    if metadata.is_symlink {
+        info!("{} is a link to renamed log. skipping", path.display());
+        return Ok(Some(SkipReason::Link));
+    }
+    if source.is_excluded(&path.file_name().unwrap().to_string_lossy()) {
+        info!("{} matches exclude pattern. skipping", path.display());
+        return Ok(Some(SkipReason::Excluded));
+    }
+    // the output folder in the source folder with the names matching the source pattern
+    if ledger.is_archived(path) {
+        info!("{} is renamed by this tool. skipping", path.display());
+        return Ok(Some(SkipReason::OwnOutput));
+    }
+    if !is_selected(selection, path) {
+        info!("{} is not selected. skipping", path.display());
+        return Ok(Some(SkipReason::NotSelected));
+    }
+    if let Some(watermark) = watermark {
+        if DateTime::<Local>::from(metadata.modified) < watermark {
+            return Ok(Some(SkipReason::Unmodified));
+        }
+    }
+    Ok(None)
+}
+
+/// parses the archived logs not in the statistics yet
+pub(crate) fn update_stats(ledger: &Ledger) -> io::Result<Stats> {
+    let mut stats = Stats::load()?;
+    stats.update(ledger.archived());
+    stats.save()?;
+    Ok(stats)
+}
+
+fn is_selected(selection: Option<&HashSet<PathBuf>>, path: &Path) -> bool {
+    selection.is_none_or(|selection| selection.contains(path))
+}
+
+/// the hash of the sources and the output to detect changes of them since the last run
+fn sources_sha256(config: &ConfigFile) -> String {
+    let sources = config
+        .sources()
+        .map(|source| (source, config.output_for(source)))
+        .collect::<Vec<_>>();
+    let json = serde_json::to_string(&sources).expect("serializing sources");
+    content_hash(json.as_bytes(), "sha256").unwrap()
+}
+
+/// the files in the source folder, and in the subfolders if `recursive`.
+/// the output folder is not searched even if it's in the source folder
+pub(crate) fn source_entries(
+    fs: &dyn FileSystem,
+    source: &Source,
+    output: &Output,
+) -> io::Result<Vec<PathBuf>> {
+    fn walk(
+        fs: &dyn FileSystem,
+        folder: &Path,
+        recursive: bool,
+        output: &Output,
+        entries: &mut Vec<PathBuf>,
+    ) -> io::Result<()> {
+        for path in fs.read_dir(folder)? {
+            if fs.metadata(&path)?.is_dir {
+                if recursive && &path != output.folder() {
+                    walk(fs, &path, recursive, output, entries)?;
+                }
+            } else {
+                entries.push(path);
+            }
+        }
+        Ok(())
+    }
+    let _timer = profile::start(Phase::Scan);
+    let mut entries = Vec::new();
+    walk(
+        fs,
+        source.folder(),
+        source.recursive(),
+        output,
+        &mut entries,
+    )?;
+    profile::count(Phase::Scan, entries.len() as u64, 0);
+    Ok(entries)
+}
+
+/// true if the renamed logs are put where the source searches with the names matching the
+/// source pattern, so they are renamed again in the next run
+pub(crate) fn output_overlaps(source: &Source, output: &Output) -> bool {
+    use std::fmt::Write as _;
+    // the variables like `{world}` are assumed to be empty
+    let sample = NaiveDate::from_ymd(2022, 10, 1).and_hms(12, 34, 56);
+    let pat_iter = MatchingIter::new(output.pattern().iter(), |_| Some(Cow::Borrowed("")));
+    let mut file_name = String::new();
+    if write!(file_name, "{}", sample.format_with_items(pat_iter)).is_err() {
+        return false;
+    }
+    let path = output.folder().join(output.sanitize_path(&file_name));
+    let (folder, file_name) = match (path.parent(), path.file_name()) {
+        (Some(folder), Some(file_name)) => (folder, file_name.to_string_lossy()),
+        _ => return false,
+    };
+    // the paths are case-insensitive on windows
+    let lower = |path: &Path| PathBuf::from(path.to_string_lossy().to_lowercase());
+    let folder = lower(folder);
+    let source_folder = lower(source.folder());
+    let output_folder = lower(output.folder());
+    // source_entries doesn't search the output folder in the source folder
+    let searched = folder == source_folder
+        || source.recursive()
+            && folder.starts_with(&source_folder)
+            && (output_folder == source_folder || !folder.starts_with(&output_folder));
+    searched && source.pattern().is_match(&file_name)
+}
+
+/// the destination of the log decided by the planning stage
+#[derive(Debug)]
+enum Destination {
+    /// the path to copy or move the log to
+    Path(PathBuf),
+    /// the log is not renamed
+    Skip(SkipReason),
+    /// the log cannot be parsed and is moved to the path in `unparsed` folder
+    Quarantine(PathBuf),
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum SkipReason {
+    /// the symbolic link left at the original path by `leave_link`
+    Link,
+    Excluded,
+    /// the log renamed by this tool in the source folder
+    OwnOutput,
+    NotSelected,
+    /// not modified since the last complete run
+    Unmodified,
+    /// the log with same content is archived before with `dedupe = "skip"`
+    AlreadyArchived,
+    /// opened by the running VRChat
+    InUse,
+    OutOfDateRange,
+    /// the destination exists
+    AlreadyCopied,
+    /// the destination is the log itself
+    SamePath,
+}
+
+impl SkipReason {
+    fn name(self) -> &'static str {
+        match self {
+            SkipReason::Link => "link",
+            SkipReason::Excluded => "excluded",
+            SkipReason::OwnOutput => "own_output",
+            SkipReason::NotSelected => "not_selected",
+            SkipReason::Unmodified => "unmodified",
+            SkipReason::AlreadyArchived => "already_archived",
+            SkipReason::InUse => "in_use",
+            SkipReason::OutOfDateRange => "out_of_date_range",
+            SkipReason::AlreadyCopied => "already_copied",
+            SkipReason::SamePath => "same_path",
+        }
+    }
+}
+
+/// the log copied or moved by `move_log_file`
+struct MovedLog {
+    path: PathBuf,
+    /// the hash computed while copying for `output.checksums`
+    sha256: Option<String>,
+    /// the hash of the log for `output.dedupe`
+    source_sha256: Option<String>,
+}
+
+/// returns the copied/moved file or None if skipped
+fn move_log_file(
+    env: Env,
+    source: &Source,
+    output: &Output,
+    path: &Path,
+    captures: Captures,
+    ledger: &Ledger,
+    journal: &mut Journal,
+) -> io::Result<Option<MovedLog>> {
+    let fs = env.fs;
+    let planned = {
+        let _timer = profile::start(Phase::Parse);
+        profile::count(Phase::Parse, 1, 0);
+        plan_destination(env, source, output, path, captures, true)?
+    };
+    let dst_path = match planned {
+        Destination::Path(dst_path) => dst_path,
+        Destination::Skip(reason) => {
+            if reason == SkipReason::InUse && source.capture_live() {
+                // overwrites the previous copy. the log will be archived after VRChat exits
+                let partial_folder = output.folder().join("partial");
+                fs.capture_live(path, &partial_folder.join(path.file_name().unwrap()));
+            }
+            return Ok(None);
+        }
+        Destination::Quarantine(dst_path) => {
+            quarantine_log_file(fs, source, path, &dst_path)?;
+            return Ok(None);
+        }
+    };
+    // hashed after planning so that the skipped logs like the live log are not read
+    let (source_sha256, archived) = match find_archived(fs, ledger, output, path) {
+        Ok(found) => found,
+        // locked after planning, like by VRChat launched meanwhile
+        Err(e) if e.raw_os_error() == Some(SHARING_VIOLATION_OS_CODE) => {
+            info!("{} may be used by other process. skipping", path.display());
+            return Ok(None);
+        }
+        Err(e) => return Err(e),
+    };
+    if let (Dedupe::Skip, Some(archived)) = (output.dedupe(), &archived) {
+        info!(
+            "{} is already archived as {}. skipping",
+            path.display(),
+            archived.display()
+        );
+        // the duplicate left in the source folder would be hashed again on every run
+        if !source.keep_old() {
+            fs.remove_file(path)?;
+        }
+        return Ok(None);
+    }
+    let archived = archived.as_deref();
+    // the pattern may have folders
+    if let Some(parent) = dst_path.parent() {
+        fs.create_dir_all(parent)?;
+    }
+
+    // report progress of copying large logs
+    let mut reported = 0;
+    let mut progress = |copied: u64, total: u64| {
+        let percent = (copied * 100).checked_div(total).unwrap_or(100);
+        if percent >= reported + 10 {
+            reported = percent / 10 * 10;
+            info!("copying {}: {}%", path.display(), reported);
+        }
+        true
+    };
+
+    let _timer = profile::start(Phase::Copy);
+    if profile::is_enabled() {
+        profile::count(Phase::Copy, 1, fs.metadata(path)?.len);
+    }
+
+    // copy to the mirrors first so that the log is not moved if any of them failed
+    let relative = dst_path.strip_prefix(output.folder()).unwrap_or(&dst_path);
+    for mirror in output.mirror_folders() {
+        let mirror_path = mirror.join(relative);
+        if fs.exists(&mirror_path) {
+            info!(
+                "{} exists. we assume mirror log is already copied",
+                mirror_path.display()
+            );
+            continue;
+        }
+        if let Some(parent) = mirror_path.parent() {
+            fs.create_dir_all(parent)?;
+        }
+        journal.begin(path, &mirror_path)?;
+        fs.copy(path, &mirror_path, output.filter_lines(), &mut progress)?;
+        journal.end(&mirror_path)?;
+        fs.set_attributes(output, &mirror_path, true);
+    }
+
+    journal.begin(path, &dst_path)?;
+    let mut sha256 = None;
+    // true if the archived log is the hard link to the original log kept in the source folder
+    let linked_to_source = if let Some(archived) = archived {
+        // the same log is archived before. share it instead of storing twice
+        if let Err(e) = fs.hard_link(archived, &dst_path) {
+            info!("cannot create hard link to {}: {}. copying", archived.display(), e);
+            fs.copy(path, &dst_path, output.filter_lines(), &mut progress)?;
+        }
+        if !source.keep_old() {
+            fs.remove_file(path)?;
+        }
+        false
+    } else if source.keep_old() && source.hard_link() && output.filter_lines().is_empty() {
+        // share the content instead of duplicating it. copied if not possible like across volumes
+        match fs.hard_link(path, &dst_path) {
+            Ok(()) => true,
+            Err(e) => {
+                info!("cannot create hard link: {}. copying", e);
+                fs.copy(path, &dst_path, output.filter_lines(), &mut progress)?;
+                false
+            }
+        }
+    } else if output.checksums() && source.keep_old() && output.filter_lines().is_empty() {
+        // hash while copying instead of reading the copy again for the checksum
+        let buffer_size = output.copy_buffer_size();
+        sha256 = Some(fs.copy_hashed(path, &dst_path, buffer_size, &mut progress)?);
+        false
+    } else if source.keep_old() || !output.filter_lines().is_empty() {
+        // filtered log is always written as a copy. the original is kept if keep_old
+        fs.copy(path, &dst_path, output.filter_lines(), &mut progress)?;
+        if !source.keep_old() {
+            fs.remove_file(path)?;
+        }
+        false
+    } else {
+        // move log file
+        fs.rename(path, &dst_path, &mut progress)?;
+        false
+    };
+    journal.end(&dst_path)?;
+    if !source.keep_old() && source.leave_link() {
+        if let Err(e) = fs.leave_link(path, &dst_path) {
+            warn!("error leaving link at {}: {}", path.display(), e);
+        }
+    }
+    // the hard link shares the attribute with the original log kept in the source folder
+    fs.set_attributes(output, &dst_path, !linked_to_source);
+
+    // the complete log is archived so the partial copy is no longer needed
+    let partial_path = output
+        .folder()
+        .join("partial")
+        .join(path.file_name().unwrap());
+    if fs.exists(&partial_path) {
+        if let Err(e) = fs.remove_file(&partial_path) {
+            warn!("error removing {}: {}", partial_path.display(), e);
+        }
+    }
+
+    Ok(Some(MovedLog {
+        path: dst_path,
+        sha256,
+        source_sha256,
+    }))
+}
+
+/// the log modified in this duration is in use with `source.in_use = "mtime"`
+const IN_USE_MTIME_AGE: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+
+/// true if the log is modified the last in the logs of the source in the folder
+fn is_newest_log(fs: &dyn FileSystem, source: &Source, path: &Path) -> io::Result<bool> {
+    let modified = fs.metadata(path)?.modified;
+    for entry in fs.read_dir(path.parent().unwrap())? {
+        let file_name = entry.file_name().unwrap();
+        if source.pattern().is_match(&file_name.to_string_lossy())
+            && fs.metadata(&entry)?.modified > modified
+        {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// the longest wait before retrying to open the locked log
+const MAX_LOCKED_RETRY_WAIT: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// decides the destination of the log without modifying anything.
+/// waits for the locked log with `source.locked_retry` if `wait_locked`
+fn plan_destination(
+    env: Env,
+    source: &Source,
+    output: &Output,
+    path: &Path,
+    captures: Captures,
+    wait_locked: bool,
+) -> io::Result<Destination> {
+    let fs = env.fs;
+    // first, check if the log file is not of running VRChat
+    let in_use = match source.in_use() {
+        // VRChat doesn't allow others to write the log
+        InUse::Open => false,
+        InUse::Process => is_vrchat_running() && is_newest_log(fs, source, path)?,
+        InUse::Mtime => {
+            let modified = fs.metadata(path)?.modified;
+            let elapsed = env.clock.now().duration_since(modified);
+            elapsed.map_or(true, |x| x < IN_USE_MTIME_AGE)
+        }
+        InUse::None => false,
+    };
+    let open = || fs.check_open(path, source.in_use() == InUse::Open);
+    let mut opened = open();
+    // the log VRChat is writing is not released by waiting
+    let written_by_vrchat = match &opened {
+        Err(e) if e.raw_os_error() == Some(SHARING_VIOLATION_OS_CODE) => {
+            is_newest_log(fs, source, path)?
+        }
+        _ => false,
+    };
+    let retry = if wait_locked && !written_by_vrchat {
+        source.locked_retry()
+    } else {
+        0
+    };
+    // the log may be locked for a while after VRChat exits, like by antivirus
+    for attempt in 0..retry {
+        if opened.is_ok() || in_use {
+            break;
+        }
+        let wait = source.locked_retry_delay() * (1 << attempt.min(16));
+        let wait = wait.min(MAX_LOCKED_RETRY_WAIT);
+        info!("{} is locked. retrying in {:?}", path.display(), wait);
+        std::thread::sleep(wait);
+        opened = open();
+    }
+    if opened.is_err() || in_use {
+        info!("{} may be used by other process. skipping", path.display());
+        return Ok(Destination::Skip(SkipReason::InUse));
+    }
     // then, assume launch time
-    let (utc_date, local_date) = if config.output().file_ctime() {
-        let created = file.metadata()?.created()?;
-        let date_time = DateTime::<Local>::from(created);
-        (Some(date_time.into()), date_time.naive_local())
+    let creation_time = || io::Result::Ok(file_time(fs.metadata(path)?.created));
+    let (utc_date, local_date) = if output.file_ctime() {
+        creation_time()?
     } else {
-        assume_launch_time(&mut file)?
+        match launch_time(fs, path, source, &captures) {
+            Ok(dates) => dates,
+            Err(e) => match source.unparsable() {
+                Unparsable::Error => return Err(e),
+                Unparsable::Ctime => {
+                    info!("{} cannot be parsed: {}. using ctime", path.display(), e);
+                    creation_time()?
+                }
+                Unparsable::Quarantine => {
+                    info!("{} cannot be parsed: {}. quarantining", path.display(), e);
+                    let folder = output.folder().join("unparsed");
+                    return Ok(Destination::Quarantine(folder.join(path.file_name().unwrap())));
+                }
+            },
+        }
     };
-    // now, close the file.
-    drop(file);
+    if !source.is_in_date_range(local_date.date()) {
+        info!("{} is out of the date range. skipping", path.display());
+        return Ok(Destination::Skip(SkipReason::OutOfDateRange));
+    }
 
-    // Data to copy log is ready. Now, move/copy log file.
-    fs::create_dir_all(config.output().folder())?;
-    let pat_iter = MatchingIter::new(config.output().pattern().iter(), |name| {
+    // Data to copy log is ready. Now, decide the destination.
+    let content = once_cell::unsync::OnceCell::new();
+    let world = once_cell::unsync::OnceCell::new();
+    let read_error = std::cell::RefCell::new(None);
+    let pat_iter = MatchingIter::new(output.pattern().iter(), |name| {
+        if name == "uuid" {
+            return Some(Cow::Owned(new_uuid()));
+        }
+        if name == "world" {
+            let first_world_name = || split::first_world_name(fs.open(path)?);
+            let world = match world.get_or_try_init(first_world_name) {
+                Ok(world) => world,
+                Err(e) => {
+                    *read_error.borrow_mut() = Some(e);
+                    return None;
+                }
+            };
+            // the world name may have folder separators
+            let world = world.as_deref().unwrap_or("unknown");
+            return Some(Cow::Owned(output.sanitize_file_name(world)));
+        }
         let (namespace, name) = name.split_once(':')?;
         match namespace {
+            "hash" => {
+                let content = match content.get_or_try_init(|| fs.read(path)) {
+                    Ok(content) => content,
+                    Err(e) => {
+                        *read_error.borrow_mut() = Some(e);
+                        return None;
+                    }
+                };
+                content_hash(content, name).map(Cow::Owned)
+            }
+            "source" if name == "reldir" => {
+                // the folder from the source folder with trailing `\` or empty
+                let folder = path.parent().unwrap_or(path);
+                let relative = folder.strip_prefix(source.folder()).unwrap_or(Path::new(""));
+                let mut reldir = relative.to_string_lossy().into_owned();
+                if !reldir.is_empty() {
+                    reldir.push('\\');
+                }
+                Some(Cow::Owned(reldir))
+            }
             "regex" => {
                 let captured = captures
                     .name(name)
                     .map(|matches| Cow::Owned(matches.as_str().to_owned()))
                     .unwrap_or(Cow::Borrowed(""));
-                println!("regex: {} : {:?}", name, captured);
+                info!("regex: {} : {:?}", name, captured);
                 Some(captured)
             }
             _ => None,
         }
     });
-    let date_format = if config.output().utc_time() {
+    let date_format = if let Some(time_zone) = output.time_zone() {
+        in_time_zone(time_zone, local_date)?.format_with_items(pat_iter)
+    } else if output.utc_time() {
         utc_date.unwrap().format_with_items(pat_iter)
     } else {
         local_date.format_with_items(pat_iter)
     };
-    let dst_path = config.output().folder().join(format!("{}", date_format));
-
-    if dst_path.exists() {
-        // if there's file at dst, we assume copy/move is done
-        println!(
-            "{} exists. we assume output log is already copied",
-            dst_path.display()
-        );
-        return Ok(());
+    let file_name = date_format.to_string();
+    if let Some(e) = read_error.into_inner() {
+        return Err(e);
     }
+    let file_name = output.sanitize_path(&file_name);
 
-    if config.source().keep_old() {
-        // copy log file
-        fs::copy(&path, &dst_path)?;
-        // copy ctime and mtime
-        use std::fs::File;
-        use std::os::windows::fs::MetadataExt;
-        use std::os::windows::io::AsRawHandle;
-        use windows::Win32::Foundation::HANDLE;
-        use windows::Win32::Foundation::FILETIME;
-        fn new_filetime(time: u64) -> FILETIME {
-            FILETIME {
-                dwLowDateTime: (time & 0xFFFFFFFF) as u32,
-                dwHighDateTime: (time >> 32 & 0xFFFFFFFF) as u32,
+    let dst_path = if file_name.contains(SEQ_TOKEN) {
+        match find_seq_path(fs, output, &file_name, path)? {
+            Some(dst_path) => dst_path,
+            None => {
+                info!("{} is already copied", path.display());
+                return Ok(Destination::Skip(SkipReason::AlreadyCopied));
             }
         }
-        let metadata = File::open(&path)?.metadata()?;
-        let dst_file = File::options().write(true).open(&dst_path)?;
-        let handle = HANDLE(dst_file.as_raw_handle() as isize);
-        let success = unsafe {
-            windows::Win32::Storage::FileSystem::SetFileTime(
-                handle,
-                Some(&new_filetime(metadata.creation_time())),
-                None,
-                Some(&new_filetime(metadata.last_write_time())),
-            )
-        };
-        if !success.as_bool() {
-            return Err(io::Error::last_os_error());
-        }
     } else {
-        // move log file
-        move_file(path, dst_path)?;
+        let dst_path = output.folder().join(&file_name);
+        // the pattern like the source file name gives the log its own path,
+        // which would be removed after copying or truncated by copying to itself
+        if is_same_path(&dst_path, path) {
+            info!("{} is renamed to itself. skipping", path.display());
+            return Ok(Destination::Skip(SkipReason::SamePath));
+        }
+        if fs.exists(&dst_path) {
+            // if there's file at dst, we assume copy/move is done
+            info!(
+                "{} exists. we assume output log is already copied",
+                dst_path.display()
+            );
+            return Ok(Destination::Skip(SkipReason::AlreadyCopied));
+        }
+        dst_path
+    };
+    Ok(Destination::Path(dst_path))
+}
+
+/// creates symbolic link at the original path to the moved log.
+/// creating symbolic link requires the developer mode or the administrator,
+/// so falls back to the shortcut named `<original>.lnk`
+fn leave_link(original: &Path, moved: &Path) -> io::Result<()> {
+    match std::os::windows::fs::symlink_file(moved, original) {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            info!("cannot create symbolic link: {}. creating shortcut", e);
+            let mut shortcut = original.as_os_str().to_owned();
+            shortcut.push(".lnk");
+            create_shortcut(Path::new(&shortcut), moved)
+        }
+    }
+}
+
+pub(crate) fn create_shortcut(shortcut: &Path, target: &Path) -> io::Result<()> {
+    use windows::core::{Interface, PCWSTR};
+    use windows::Win32::System::Com::{CoCreateInstance, IPersistFile, CLSCTX_INPROC_SERVER};
+    use windows::Win32::UI::Shell::{IShellLinkW, ShellLink};
+    let _scope = winsafe::CoInitializeEx(winsafe::co::COINIT::MULTITHREADED);
+    let to_io = |e: windows::core::Error| io::Error::other(e);
+    unsafe {
+        let link: IShellLinkW =
+            CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER).map_err(to_io)?;
+        link.SetPath(PCWSTR(to_wide(target).as_ptr()))
+            .map_err(to_io)?;
+        let file = link.cast::<IPersistFile>().map_err(to_io)?;
+        file.Save(PCWSTR(to_wide(shortcut).as_ptr()), true)
+            .map_err(to_io)?;
+    }
+    Ok(())
+}
+
+/// compresses and marks read-only the archived log if enabled. `read_only` is false for the hard
+/// link sharing the attribute with the original log.
+/// the failure is not an error since the file system may not support compression like exFAT
+fn set_attributes(output: &Output, path: &Path, read_only: bool) {
+    if output.compress() {
+        if let Err(e) = set_compression(path) {
+            warn!("error compressing {}: {}", path.display(), e);
+        }
+    }
+    // this is done after compressing since compressing needs to open the file for writing
+    if read_only && output.read_only() {
+        let result = fs::metadata(path).and_then(|metadata| {
+            let mut permissions = metadata.permissions();
+            permissions.set_readonly(true);
+            fs::set_permissions(path, permissions)
+        });
+        if let Err(e) = result {
+            warn!("error marking {} read-only: {}", path.display(), e);
+        }
     }
+}
 
+/// enables NTFS compression of the file with `FSCTL_SET_COMPRESSION`
+fn set_compression(path: &Path) -> io::Result<()> {
+    use std::os::windows::io::AsRawHandle;
+    use windows::Win32::Foundation::HANDLE;
+    use windows::Win32::System::Ioctl::FSCTL_SET_COMPRESSION;
+    use windows::Win32::System::SystemServices::COMPRESSION_FORMAT_DEFAULT;
+    use windows::Win32::System::IO::DeviceIoControl;
+    let file = fs::File::options().read(true).write(true).open(path)?;
+    let format = COMPRESSION_FORMAT_DEFAULT as u16;
+    let mut returned = 0u32;
+    let success = unsafe {
+        DeviceIoControl(
+            HANDLE(file.as_raw_handle() as isize),
+            FSCTL_SET_COMPRESSION,
+            Some((&format as *const u16).cast()),
+            std::mem::size_of::<u16>() as u32,
+            None,
+            0,
+            Some(&mut returned),
+            None,
+        )
+    };
+    if !success.as_bool() {
+        let e = io::Error::last_os_error();
+        return Err(io::Error::new(
+            e.kind(),
+            format!("FSCTL_SET_COMPRESSION failed: {}", e),
+        ));
+    }
     Ok(())
 }
 
@@ -217,11 +1681,42 @@ impl<'a, I: Iterator<Item = &'a Item<'static>>, F: Fn(&str) -> Option<Cow<str>>>
                 let var = &pat[..pat_end];
                 *pat = &pat[pat_end..];
                 // remove { and }
-                let var_name = &var[1..var.len() - 1];
-                builder.push_str(f(var_name).as_deref().unwrap_or(var))
+                let mut modifiers = var[1..var.len() - 1].split('|');
+                let var_name = modifiers.next().unwrap();
+                let value = f(var_name).and_then(|value| {
+                    modifiers.try_fold(value.into_owned(), |value, modifier| {
+                        apply_modifier(&value, modifier)
+                    })
+                });
+                builder.push_str(value.as_deref().unwrap_or(var))
             }
         }
 
+        /// applies modifier like `lower` or `pad3` to the value.
+        /// returns None for unknown modifier
+        fn apply_modifier(value: &str, modifier: &str) -> Option<String> {
+            fn count(modifier: &str, prefix: &str) -> Option<usize> {
+                modifier.strip_prefix(prefix)?.parse().ok()
+            }
+            Some(match modifier {
+                "lower" => value.to_lowercase(),
+                "upper" => value.to_uppercase(),
+                "trim" => value.trim().to_owned(),
+                _ => {
+                    if let Some(width) = count(modifier, "pad") {
+                        format!("{:0>width$}", value, width = width)
+                    } else if let Some(len) = count(modifier, "first") {
+                        value.chars().take(len).collect()
+                    } else if let Some(len) = count(modifier, "last") {
+                        let skip = value.chars().count().saturating_sub(len);
+                        value.chars().skip(skip).collect()
+                    } else {
+                        return None;
+                    }
+                }
+            })
+        }
+
         let mut pat = pat_in;
 
         loops(&self.f, &mut pat, &mut builder);
@@ -257,15 +1752,210 @@ impl<'a, I: Iterator<Item = &'a Item<'static>>, F: Fn(&str) -> Option<Cow<str>>>
     }
 }
 
-fn assume_launch_time(f: &mut fs::File) -> io::Result<(Option<DateTime<Utc>>, NaiveDateTime)> {
-    // length of "%Y.%m.%d %H:%M:%S" is 19 bytes
-    let mut buffer = [0 as u8; 19];
-    f.read_exact(&mut buffer)?;
-    // it must be ascii.
-    let str = std::str::from_utf8(&buffer)
-        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid utf8"))?;
-    let time_from_log = NaiveDateTime::parse_from_str(str, "%Y.%m.%d %H:%M:%S")
-        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid VRC log"))?;
+/// copies or moves the log file cannot be parsed to the path in `unparsed` folder
+/// to not fail every run.
+fn quarantine_log_file(
+    fs: &dyn FileSystem,
+    source: &Source,
+    path: &Path,
+    dst_path: &Path,
+) -> io::Result<()> {
+    fs.create_dir_all(dst_path.parent().unwrap())?;
+    if fs.exists(dst_path) {
+        info!("{} is already quarantined", path.display());
+        return Ok(());
+    }
+    if source.keep_old() {
+        fs.copy(path, dst_path, &[], &mut |_, _| true)
+    } else {
+        fs.rename(path, dst_path, &mut |_, _| true)
+    }
+}
+
+/// the token replaced with the lowest number making the destination unique.
+/// this is not processed by `MatchingIter` but after formatting since it depends on existing files
+const SEQ_TOKEN: &str = "{seq}";
+
+/// returns the path with `{seq}` replaced by the lowest number of non-existing file
+/// or None if the file with same content as output of `src` already exists or `src` itself is
+/// the path
+fn find_seq_path(
+    fs: &dyn FileSystem,
+    output: &Output,
+    file_name: &str,
+    src: &Path,
+) -> io::Result<Option<PathBuf>> {
+    let expected = once_cell::unsync::OnceCell::new();
+    let expected_content = || {
+        expected.get_or_try_init(|| {
+            let mut content = Vec::new();
+            filter_lines(&mut fs.open(src)?, &mut content, output.filter_lines())?;
+            io::Result::Ok(content)
+        })
+    };
+    for seq in 1.. {
+        let dst_path = output
+            .folder()
+            .join(file_name.replace(SEQ_TOKEN, &seq.to_string()));
+        if is_same_path(&dst_path, src) {
+            return Ok(None);
+        }
+        let metadata = match fs.metadata(&dst_path) {
+            Ok(metadata) => metadata,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(Some(dst_path)),
+            Err(e) => return Err(e),
+        };
+        let expected = expected_content()?;
+        if metadata.len == expected.len() as u64 && &fs.read(&dst_path)? == expected {
+            return Ok(None);
+        }
+    }
+    unreachable!()
+}
+
+/// compares the paths case-insensitively like windows does
+pub(crate) fn is_same_path(a: &Path, b: &Path) -> bool {
+    a.to_string_lossy().to_lowercase() == b.to_string_lossy().to_lowercase()
+}
+
+/// copies lines not matching any of the filters
+fn filter_lines(from: &mut impl Read, to: &mut impl Write, filters: &[Regex]) -> io::Result<()> {
+    if filters.is_empty() {
+        io::copy(from, to)?;
+        return Ok(());
+    }
+    let mut reader = io::BufReader::new(from);
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+        let text = String::from_utf8_lossy(&line);
+        if !filters.iter().any(|filter| filter.is_match(&text)) {
+            to.write_all(&line)?;
+        }
+    }
+    Ok(())
+}
+
+/// writes the log with lines matching `output.filter_lines` dropped
+fn copy_filtered(from: &Path, to: &Path, filters: &[Regex]) -> io::Result<()> {
+    let mut from_file = fs::File::open(from)?;
+    let metadata = from_file.metadata()?;
+    let mut to_file = io::BufWriter::new(
+        fs::File::options()
+            .create_new(true)
+            .write(true)
+            .open(to)?,
+    );
+    filter_lines(&mut from_file, &mut to_file, filters)?;
+    let to_file = to_file.into_inner().map_err(|e| e.into_error())?;
+    copy_file_times(&metadata, &to_file)?;
+    Ok(())
+}
+
+/// generates random uuid with `CoCreateGuid`
+fn new_uuid() -> String {
+    let guid = unsafe { windows::Win32::System::Com::CoCreateGuid() }.unwrap();
+    format!(
+        "{:08x}-{:04x}-{:04x}-{:02x}{:02x}-{}",
+        guid.data1,
+        guid.data2,
+        guid.data3,
+        guid.data4[0],
+        guid.data4[1],
+        guid.data4[2..]
+            .iter()
+            .map(|x| format!("{:02x}", x))
+            .collect::<String>()
+    )
+}
+
+/// computes the hash of the content for `{hash:algorithm:length}` token.
+/// the length is optional and whole hash is returned if omitted
+pub(crate) fn content_hash(content: &[u8], spec: &str) -> Option<String> {
+    use sha2::Digest;
+    let (algorithm, length) = match spec.split_once(':') {
+        Some((algorithm, length)) => (algorithm, Some(length.parse::<usize>().ok()?)),
+        None => (spec, None),
+    };
+    let hash = match algorithm {
+        "sha1" => sha1::Sha1::digest(content).to_vec(),
+        "sha256" => sha2::Sha256::digest(content).to_vec(),
+        _ => return None,
+    };
+    let mut hex = hash.iter().map(|x| format!("{:02x}", x)).collect::<String>();
+    if let Some(length) = length {
+        hex.truncate(length);
+    }
+    Some(hex)
+}
+
+/// reads the launch time from the ones in `source.launch_time` in order.
+/// returns the error of the last one if none is found
+fn launch_time(
+    fs: &dyn FileSystem,
+    path: &Path,
+    source: &Source,
+    captures: &Captures,
+) -> io::Result<(Option<DateTime<Utc>>, NaiveDateTime)> {
+    let mut error = io::Error::new(io::ErrorKind::InvalidInput, "source.launch_time is empty");
+    for launch_time in source.launch_time() {
+        let result = match launch_time {
+            LaunchTime::Header => assume_launch_time(fs, path, source),
+            LaunchTime::Ctime => fs.metadata(path).map(|x| file_time(x.created)),
+            LaunchTime::Mtime => fs.metadata(path).map(|x| file_time(x.modified)),
+            LaunchTime::FileName => time_from_captures(captures),
+        };
+        match result {
+            Ok(dates) => return Ok(dates),
+            Err(e) => error = e,
+        }
+    }
+    Err(error)
+}
+
+fn file_time(time: SystemTime) -> (Option<DateTime<Utc>>, NaiveDateTime) {
+    let date_time = DateTime::<Local>::from(time);
+    (Some(date_time.into()), date_time.naive_local())
+}
+
+/// builds the time from the captures of the source pattern named `year`, `month`, `day`, `hour`,
+/// `minute` and `second`, or the strftime specifiers `Y` (or `y`), `m`, `d`, `H`, `M` and `S`.
+/// the second can be omitted. this is fast since the log is not read
+fn time_from_captures(captures: &Captures) -> io::Result<(Option<DateTime<Utc>>, NaiveDateTime)> {
+    let number = |name, specifier| {
+        let capture = captures.name(name).or_else(|| captures.name(specifier))?;
+        capture.as_str().parse::<u32>().ok()
+    };
+    let time = (|| {
+        let year = match number("year", "Y") {
+            Some(year) => year as i32,
+            None => 2000 + number("year", "y")? as i32,
+        };
+        NaiveDate::from_ymd_opt(year, number("month", "m")?, number("day", "d")?)?.and_hms_opt(
+            number("hour", "H")?,
+            number("minute", "M")?,
+            number("second", "S").unwrap_or(0),
+        )
+    })()
+    .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "no launch time in file name"))?;
+    Ok((time.and_local_timezone(Utc).earliest(), time))
+}
+
+/// the bytes of the first line of the log read to find the launch time
+const HEADER_MAX_LEN: u64 = 256;
+
+fn assume_launch_time(
+    fs: &dyn FileSystem,
+    path: &Path,
+    source: &Source,
+) -> io::Result<(Option<DateTime<Utc>>, NaiveDateTime)> {
+    let mut line = Vec::new();
+    io::BufReader::new(fs.open(path)?.take(HEADER_MAX_LEN)).read_until(b'\n', &mut line)?;
+    let time_from_log = parse_header(&String::from_utf8_lossy(&line), source.header_format())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid VRC log"))?;
 
     /*
     // TODO: creation time based time zone inference
@@ -288,33 +1978,252 @@ fn assume_launch_time(f: &mut fs::File) -> io::Result<(Option<DateTime<Utc>>, Na
     ))
 }
 
+/// parses the time at the head of the line. the time can be any length with the format like `%B`
+/// or `%-d` so the longest one is taken
+fn parse_header(line: &str, format: &str) -> Option<NaiveDateTime> {
+    (1..=line.len())
+        .rev()
+        .filter(|&end| line.is_char_boundary(end))
+        .find_map(|end| NaiveDateTime::parse_from_str(&line[..end], format).ok())
+}
+
+/// converts the local time to the time in the time zone
+fn in_time_zone(time_zone: &TimeZone, local: NaiveDateTime) -> io::Result<NaiveDateTime> {
+    let utc = local
+        .and_local_timezone(Local)
+        .earliest()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "nonexistent local time"))?
+        .with_timezone(&Utc);
+    match time_zone {
+        TimeZone::Fixed(offset) => Ok(utc.with_timezone(offset).naive_local()),
+        TimeZone::Iana(id) => iana_time(id, utc),
+    }
+}
+
+/// converts with `Windows.Globalization.Calendar` which knows the IANA time zones
+fn iana_time(id: &str, utc: DateTime<Utc>) -> io::Result<NaiveDateTime> {
+    use windows::core::HSTRING;
+    use windows::Globalization::Calendar;
+    // the seconds from 1601-01-01, the epoch of WinRT DateTime, to 1970-01-01
+    const EPOCH_DIFFERENCE: i64 = 11_644_473_600;
+    let to_io = |e: windows::core::Error| io::Error::other(e);
+    let calendar = Calendar::new().map_err(to_io)?;
+    calendar
+        .ChangeCalendarSystem(&HSTRING::from("GregorianCalendar"))
+        .map_err(to_io)?;
+    calendar
+        .ChangeClock(&HSTRING::from("24HourClock"))
+        .map_err(to_io)?;
+    calendar
+        .ChangeTimeZone(&HSTRING::from(id))
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, format!("{}: {}", id, e)))?;
+    let ticks = (utc.timestamp() + EPOCH_DIFFERENCE) * 10_000_000
+        + utc.timestamp_subsec_nanos() as i64 / 100;
+    calendar
+        .SetDateTime(windows::Foundation::DateTime {
+            UniversalTime: ticks,
+        })
+        .map_err(to_io)?;
+    let date = NaiveDate::from_ymd_opt(
+        calendar.Year().map_err(to_io)?,
+        calendar.Month().map_err(to_io)? as u32,
+        calendar.Day().map_err(to_io)? as u32,
+    );
+    date.and_then(|date| {
+        date.and_hms_nano_opt(
+            calendar.Hour().ok()? as u32,
+            calendar.Minute().ok()? as u32,
+            calendar.Second().ok()? as u32,
+            calendar.Nanosecond().ok()? as u32,
+        )
+    })
+    .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid time from calendar"))
+}
+
 #[cfg(windows)]
 // ERROR_NOT_SAME_DEVICE
 static CROSSES_DEVICES_OS_CODE: i32 = 17;
+// ERROR_SHARING_VIOLATION
+static SHARING_VIOLATION_OS_CODE: i32 = 32;
+// ERROR_PIPE_CONNECTED
+static PIPE_CONNECTED_OS_CODE: i32 = 535;
+
+/// sets ctime and mtime of the file to the ones of the metadata.
+/// `SetFileTime` returns nonzero on success, so the error is reported only if it returns zero
+fn copy_file_times(metadata: &fs::Metadata, file: &fs::File) -> io::Result<()> {
+    use std::os::windows::fs::MetadataExt;
+    use std::os::windows::io::AsRawHandle;
+    use windows::Win32::Foundation::HANDLE;
+    use windows::Win32::Foundation::FILETIME;
+    fn new_filetime(time: u64) -> FILETIME {
+        FILETIME {
+            dwLowDateTime: (time & 0xFFFFFFFF) as u32,
+            dwHighDateTime: (time >> 32 & 0xFFFFFFFF) as u32,
+        }
+    }
+    let handle = HANDLE(file.as_raw_handle() as isize);
+    let success = unsafe {
+        windows::Win32::Storage::FileSystem::SetFileTime(
+            handle,
+            Some(&new_filetime(metadata.creation_time())),
+            None,
+            Some(&new_filetime(metadata.last_write_time())),
+        )
+    };
+    if !success.as_bool() {
+        let e = io::Error::last_os_error();
+        return Err(io::Error::new(e.kind(), format!("SetFileTime failed: {}", e)));
+    }
+    Ok(())
+}
 
-fn move_file(from: impl AsRef<Path>, to: impl AsRef<Path>) -> io::Result<()> {
-    fn move_by_copy(from: &Path, to: &Path) -> io::Result<()> {
-        let mut from_file = fs::File::options().read(true).write(true).open(from)?;
-        let mut to_file = fs::File::options().create_new(true).write(true).open(to)?;
-        io::copy(&mut from_file, &mut to_file)?;
-        to_file.flush()?;
-        drop(from_file);
+/// sets file attributes like read-only or hidden of the file to the ones of the metadata
+fn copy_file_attributes(metadata: &fs::Metadata, path: &Path) -> io::Result<()> {
+    use std::os::windows::fs::MetadataExt;
+    use windows::core::PCWSTR;
+    use windows::Win32::Storage::FileSystem::{SetFileAttributesW, FILE_FLAGS_AND_ATTRIBUTES};
+    let path = to_wide(path);
+    let success = unsafe {
+        SetFileAttributesW(
+            PCWSTR(path.as_ptr()),
+            FILE_FLAGS_AND_ATTRIBUTES(metadata.file_attributes()),
+        )
+    };
+    if !success.as_bool() {
+        let e = io::Error::last_os_error();
+        return Err(io::Error::new(
+            e.kind(),
+            format!("SetFileAttributesW failed: {}", e),
+        ));
+    }
+    Ok(())
+}
+
+fn to_wide(path: &Path) -> Vec<u16> {
+    use std::os::windows::ffi::OsStrExt;
+    path.as_os_str().encode_wide().chain(Some(0)).collect()
+}
+
+// ERROR_REQUEST_ABORTED
+static REQUEST_ABORTED_OS_CODE: i32 = 1235;
+
+/// copies the file with `CopyFileExW` and falls back to streaming copy if it failed.
+/// the progress is called with copied and total bytes, and copying is cancelled if it returns false.
+/// ctime is not copied so use `copy_file_times` to keep it
+fn copy_file(
+    from: &Path,
+    to: &Path,
+    progress: &mut dyn FnMut(u64, u64) -> bool,
+) -> io::Result<()> {
+    match copy_file_ex(from, to, progress) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::Interrupted || to.exists() => Err(e),
+        Err(e) => {
+            info!("CopyFileExW failed: {}. falling back to streaming copy", e);
+            copy_by_stream(from, to)
+        }
+    }
+}
+
+fn copy_file_ex(
+    from: &Path,
+    to: &Path,
+    progress: &mut dyn FnMut(u64, u64) -> bool,
+) -> io::Result<()> {
+    use std::ffi::c_void;
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::HANDLE;
+    use windows::Win32::Storage::FileSystem::{CopyFileExW, LPPROGRESS_ROUTINE_CALLBACK_REASON};
+    // PROGRESS_CONTINUE and PROGRESS_CANCEL
+    const CONTINUE: u32 = 0;
+    const CANCEL: u32 = 1;
+    // COPY_FILE_FAIL_IF_EXISTS
+    const FAIL_IF_EXISTS: u32 = 1;
+
+    unsafe extern "system" fn routine(
+        total_file_size: i64,
+        total_bytes_transferred: i64,
+        _stream_size: i64,
+        _stream_bytes_transferred: i64,
+        _stream_number: u32,
+        _callback_reason: LPPROGRESS_ROUTINE_CALLBACK_REASON,
+        _source_file: HANDLE,
+        _destination_file: HANDLE,
+        data: *const c_void,
+    ) -> u32 {
+        let progress = &mut *(data as *mut &mut dyn FnMut(u64, u64) -> bool);
+        if progress(total_bytes_transferred as u64, total_file_size as u64) {
+            CONTINUE
+        } else {
+            CANCEL
+        }
+    }
+
+    let from_wide = to_wide(from);
+    let to_wide = to_wide(to);
+    let mut progress = progress;
+    let success = unsafe {
+        CopyFileExW(
+            PCWSTR(from_wide.as_ptr()),
+            PCWSTR(to_wide.as_ptr()),
+            Some(routine),
+            Some(&mut progress as *mut &mut dyn FnMut(u64, u64) -> bool as *const c_void),
+            None,
+            FAIL_IF_EXISTS,
+        )
+    };
+    if !success.as_bool() {
+        let e = io::Error::last_os_error();
+        if e.raw_os_error() == Some(REQUEST_ABORTED_OS_CODE) {
+            return Err(io::Error::new(io::ErrorKind::Interrupted, "copying cancelled"));
+        }
+        return Err(io::Error::new(e.kind(), format!("CopyFileExW failed: {}", e)));
+    }
+    Ok(())
+}
+
+fn copy_by_stream(from: &Path, to: &Path) -> io::Result<()> {
+    let mut from_file = fs::File::open(from)?;
+    let mut to_file = fs::File::options().create_new(true).write(true).open(to)?;
+    let metadata = from_file.metadata()?;
+    io::copy(&mut from_file, &mut to_file)?;
+    to_file.flush()?;
+    drop(to_file);
+    copy_file_attributes(&metadata, to)?;
+    Ok(())
+}
+
+fn move_file(
+    from: &Path,
+    to: &Path,
+    progress: &mut dyn FnMut(u64, u64) -> bool,
+) -> io::Result<()> {
+    fn move_by_copy(
+        from: &Path,
+        to: &Path,
+        progress: &mut dyn FnMut(u64, u64) -> bool,
+    ) -> io::Result<()> {
+        let metadata = fs::metadata(from)?;
+        copy_file(from, to, progress)?;
+        // keep timestamps and attributes like rename does
+        let to_file = fs::File::options().write(true).open(to)?;
+        copy_file_times(&metadata, &to_file)?;
         drop(to_file);
+        copy_file_attributes(&metadata, to)?;
         fs::remove_file(from)?;
         Ok(())
     }
-    fn inner(from: &Path, to: &Path) -> io::Result<()> {
-        match fs::rename(from, to) {
-            Ok(_) => Ok(()),
-            #[cfg(any())] // io_error_more is not stable yet
-            Err(ref e) if e.kind() == io::ErrorKind::CrossesDevices => move_by_copy(from, to),
-            Err(ref e) if e.raw_os_error() == Some(CROSSES_DEVICES_OS_CODE) => {
-                move_by_copy(from, to)
-            }
-            Err(e) => Err(e),
+    match fs::rename(from, to) {
+        Ok(_) => Ok(()),
+        #[cfg(any())] // io_error_more is not stable yet
+        Err(ref e) if e.kind() == io::ErrorKind::CrossesDevices => {
+            move_by_copy(from, to, progress)
         }
+        Err(ref e) if e.raw_os_error() == Some(CROSSES_DEVICES_OS_CODE) => {
+            move_by_copy(from, to, progress)
+        }
+        Err(e) => Err(e),
     }
-    inner(from.as_ref(), to.as_ref())
 }
 
 fn local_low_appdata_path() -> &'static Path {
@@ -327,6 +2236,56 @@ fn local_low_appdata_path() -> &'static Path {
     })
 }
 
+/// whether VRChat is running. the newest log is skipped by the renamer while VRChat writes to it
+pub(crate) fn is_vrchat_running() -> bool {
+    use winsafe::prelude::kernel_Hprocesslist;
+    use winsafe::{co, HPROCESSLIST};
+    let mut processes = match HPROCESSLIST::CreateToolhelp32Snapshot(co::TH32CS::SNAPPROCESS, None)
+    {
+        Ok(processes) => processes,
+        Err(e) => {
+            eprintln!("error listing processes: {}", e);
+            return false;
+        }
+    };
+    let running = processes
+        .iter_processes()
+        .filter_map(Result::ok)
+        .any(|process| process.szExeFile().eq_ignore_ascii_case("VRChat.exe"));
+    running
+}
+
+/// returns the http agent shared in this process. uses schannel via native-tls for TLS
+fn http_agent() -> &'static ureq::Agent {
+    static CELL: OnceBox<ureq::Agent> = OnceBox::new();
+    CELL.get_or_init(|| {
+        let tls = native_tls::TlsConnector::new().expect("initializing TLS");
+        Box::new(
+            ureq::AgentBuilder::new()
+                .tls_connector(std::sync::Arc::new(tls))
+                .timeout(std::time::Duration::from_secs(30))
+                .build(),
+        )
+    })
+}
+
+/// returns the http agent for uploading and downloading large files. there's no overall deadline
+/// so that the transfer may take long, but it fails if the connection stalls
+fn transfer_agent() -> &'static ureq::Agent {
+    static CELL: OnceBox<ureq::Agent> = OnceBox::new();
+    CELL.get_or_init(|| {
+        let tls = native_tls::TlsConnector::new().expect("initializing TLS");
+        Box::new(
+            ureq::AgentBuilder::new()
+                .tls_connector(std::sync::Arc::new(tls))
+                .timeout_connect(std::time::Duration::from_secs(30))
+                .timeout_read(std::time::Duration::from_secs(60))
+                .timeout_write(std::time::Duration::from_secs(60))
+                .build(),
+        )
+    })
+}
+
 fn config_file_path() -> &'static Path {
     static CELL: OnceBox<PathBuf> = OnceBox::new();
     /// returns read-writable file handle for config
@@ -346,3 +2305,225 @@ fn config_file_path() -> &'static Path {
 
     CELL.get_or_init(|| Box::new(find_config_file()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::windows::fs::MetadataExt;
+
+    /// the temporary folder removed on drop even if the test fails
+    struct TempFolder(PathBuf);
+
+    impl std::ops::Deref for TempFolder {
+        type Target = Path;
+
+        fn deref(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl Drop for TempFolder {
+        fn drop(&mut self) {
+            fs::remove_dir_all(&self.0).ok();
+        }
+    }
+
+    fn temp_folder() -> TempFolder {
+        let dir = std::env::temp_dir().join(format!("vrc-log-renamer-test-{}", new_uuid()));
+        fs::create_dir_all(&dir).unwrap();
+        TempFolder(dir)
+    }
+
+    #[test]
+    fn copy_file_times_copies_ctime_and_mtime() {
+        let dir = temp_folder();
+        let src = dir.join("src.txt");
+        let dst = dir.join("dst.txt");
+        fs::write(&src, "src").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        fs::write(&dst, "dst").unwrap();
+
+        let metadata = fs::metadata(&src).unwrap();
+        let dst_file = fs::File::options().write(true).open(&dst).unwrap();
+        copy_file_times(&metadata, &dst_file).unwrap();
+        drop(dst_file);
+
+        let copied = fs::metadata(&dst).unwrap();
+        assert_eq!(copied.creation_time(), metadata.creation_time());
+        assert_eq!(copied.last_write_time(), metadata.last_write_time());
+    }
+
+    #[test]
+    fn copy_hashed_hashes_the_copied_content() {
+        let dir = temp_folder();
+        let src = dir.join("src.txt");
+        let dst = dir.join("dst.txt");
+        let content = "2022.10.01 12:34:56 Log -  test\n".repeat(100);
+        fs::write(&src, &content).unwrap();
+
+        // smaller than the file to hash across the chunks
+        let sha256 = checksums::copy_hashed(&src, &dst, 7, &mut |_, _| true).unwrap();
+
+        assert_eq!(fs::read_to_string(&dst).unwrap(), content);
+        assert_eq!(Some(sha256), content_hash(content.as_bytes(), "sha256"));
+    }
+
+    #[test]
+    fn copy_file_times_reports_error() {
+        let dir = temp_folder();
+        let path = dir.join("file.txt");
+        fs::write(&path, "file").unwrap();
+
+        // the handle without write access cannot be used to set file time
+        let metadata = fs::metadata(&path).unwrap();
+        let read_only = fs::File::open(&path).unwrap();
+        let error = copy_file_times(&metadata, &read_only).unwrap_err();
+        assert!(error.to_string().contains("SetFileTime"));
+        drop(read_only);
+    }
+
+    #[test]
+    fn parse_header_takes_longest_time() {
+        let time = NaiveDate::from_ymd(2022, 10, 1).and_hms(12, 34, 56);
+        let vrchat = "2022.10.01 12:34:56 Log        -  [Behaviour] Initialized PlayerAPI\n";
+        assert_eq!(parse_header(vrchat, "%Y.%m.%d %H:%M:%S"), Some(time));
+        let long = "October 1, 2022 12:34:56 Log\n";
+        assert_eq!(parse_header(long, "%B %-d, %Y %H:%M:%S"), Some(time));
+        assert_eq!(parse_header("", "%Y.%m.%d %H:%M:%S"), None);
+        assert_eq!(parse_header("12:34:56 Log\n", "%Y.%m.%d %H:%M:%S"), None);
+    }
+
+    #[test]
+    fn launch_time_from_file_name() {
+        let pattern = Regex::new(concat!(
+            r"^output_log_(?P<year>\d{4})-(?P<month>\d{2})-(?P<day>\d{2})_",
+            r"(?P<hour>\d{2})-(?P<minute>\d{2})-(?P<second>\d{2})\.txt$",
+        ))
+        .unwrap();
+        let captures = pattern
+            .captures("output_log_2023-04-01_12-34-56.txt")
+            .unwrap();
+        let (_, time) = time_from_captures(&captures).unwrap();
+        assert_eq!(time, NaiveDate::from_ymd(2023, 4, 1).and_hms(12, 34, 56));
+
+        let pattern =
+            Regex::new(r"^(?P<y>\d{2})(?P<m>\d{2})(?P<d>\d{2})_(?P<H>\d{2})(?P<M>\d{2})\.log$")
+                .unwrap();
+        let captures = pattern.captures("230401_1234.log").unwrap();
+        let (_, time) = time_from_captures(&captures).unwrap();
+        assert_eq!(time, NaiveDate::from_ymd(2023, 4, 1).and_hms(12, 34, 0));
+
+        let captures = Source::default()
+            .pattern()
+            .captures("output_log_12-34-56.txt")
+            .unwrap();
+        assert!(time_from_captures(&captures).is_err());
+    }
+
+    #[test]
+    fn remove_empty_folders_keeps_files() {
+        let dir = temp_folder();
+        fs::create_dir_all(dir.join("2022").join("10")).unwrap();
+        fs::create_dir_all(dir.join("2022").join("11")).unwrap();
+        fs::create_dir_all(dir.join("partial")).unwrap();
+        fs::write(dir.join("2022").join("11").join("log.txt"), "log").unwrap();
+
+        let mut removed = Vec::new();
+        assert!(!remove_empty_folders(&dir, &mut removed).unwrap());
+        removed.sort();
+        assert_eq!(removed, [dir.join("2022").join("10"), dir.join("partial")]);
+        assert!(dir.join("2022").join("11").join("log.txt").exists());
+    }
+
+    #[test]
+    fn output_overlaps_source_pattern() {
+        let overlaps = |settings: &[(&str, &str)]| {
+            let mut config = ConfigFile::default();
+            config.set_value("source.folder", r"C:\logs").unwrap();
+            for (key, value) in settings {
+                config.set_value(key, value).unwrap();
+            }
+            output_overlaps(config.source(), &config.output_for(config.source()))
+        };
+        assert!(overlaps(&[("output.folder", r"C:\Logs")]));
+        assert!(!overlaps(&[("output.folder", r"C:\logs\renamed")]));
+        assert!(!overlaps(&[
+            ("output.folder", r"C:\logs"),
+            ("output.pattern", "log_%Y-%m-%d_%H-%M-%S.txt"),
+        ]));
+        // the output folder in the source folder is not searched
+        assert!(!overlaps(&[
+            ("source.recursive", "true"),
+            ("output.folder", r"C:\logs\renamed"),
+        ]));
+        assert!(overlaps(&[
+            ("source.recursive", "true"),
+            ("output.folder", r"C:\logs"),
+            ("output.pattern", r"%Y\output_log_%Y-%m-%d_%H-%M-%S.txt"),
+        ]));
+    }
+
+    /// the config renaming from `C:\logs` to `C:\archive` and the two logs launched on the same day
+    fn simulation(settings: &[(&str, &str)]) -> (ConfigFile, MemoryFs) {
+        let mut config = ConfigFile::default();
+        config.set_value("source.folder", r"C:\logs").unwrap();
+        config.set_value("output.folder", r"C:\archive").unwrap();
+        for (key, value) in settings {
+            config.set_value(key, value).unwrap();
+        }
+        let fs = MemoryFs::default();
+        let launched = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_664_600_000);
+        let logs = [
+            ("output_log_12-34-56.txt", "2022.10.01 12:34:56 first\n"),
+            ("output_log_18-00-00.txt", "2022.10.01 18:00:00 second\n"),
+        ];
+        for (i, (name, content)) in logs.iter().enumerate() {
+            let path = Path::new(r"C:\logs").join(name);
+            let modified = launched + std::time::Duration::from_secs(3600 * i as u64);
+            fs.add_file(&path, content.as_bytes(), modified);
+        }
+        (config, fs)
+    }
+
+    #[test]
+    fn simulate_numbers_collisions() {
+        let (config, fs) = simulation(&[("output.pattern", "log_%Y-%m-%d_{seq}.txt")]);
+        let clock = FixedClock(SystemTime::now());
+        let env = Env {
+            fs: &fs,
+            clock: &clock,
+        };
+        let summary = simulate_files(&config, env, &mut Ledger::default()).unwrap();
+        assert_eq!(summary.renamed.len(), 2);
+        assert_eq!(
+            fs.files(),
+            [
+                PathBuf::from(r"C:\archive\log_2022-10-01_1.txt"),
+                PathBuf::from(r"C:\archive\log_2022-10-01_2.txt"),
+            ]
+        );
+
+        // the renamed logs are not renamed again
+        let summary = simulate_files(&config, env, &mut Ledger::default()).unwrap();
+        assert!(summary.renamed.is_empty());
+    }
+
+    #[test]
+    fn simulate_skips_recently_modified() {
+        let (config, fs) = simulation(&[("source.in_use", "mtime"), ("source.keep_old", "true")]);
+        // 3 minutes after the second log is modified
+        let second = Path::new(r"C:\logs\output_log_18-00-00.txt");
+        let now = fs.metadata(second).unwrap().modified + std::time::Duration::from_secs(3 * 60);
+        let clock = FixedClock(now);
+        let env = Env {
+            fs: &fs,
+            clock: &clock,
+        };
+        let summary = simulate_files(&config, env, &mut Ledger::default()).unwrap();
+        assert_eq!(summary.skipped, 1);
+        let first = Path::new(r"C:\logs\output_log_12-34-56.txt");
+        let renamed = summary.renamed.iter().map(|x| &x.source);
+        assert_eq!(renamed.collect::<Vec<_>>(), [first]);
+        assert!(fs.exists(first));
+    }
+}
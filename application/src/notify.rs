@@ -0,0 +1,79 @@
+// VRC Log Renamer - the tool to rename logs of VRChat to have date info
+// Copyright (C) 2022 anatawa12
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::config::{ConfigFile, DiscordNotify};
+use crate::{http_agent, RenameSummary};
+use anyhow::Result;
+
+// discord limits the length of embed field values to 1024 characters
+const DISCORD_FIELD_LIMIT: usize = 1024;
+
+const COLOR_SUCCEED: u32 = 0x57F287;
+const COLOR_FAILED: u32 = 0xED4245;
+
+/// sends the summary of the run to configured notification targets.
+/// errors are reported to stderr and never fail the run itself.
+pub(crate) fn notify_run(config: &ConfigFile, summary: &Result<RenameSummary>) {
+    if let Some(discord) = config.notify().discord() {
+        if let Some(e) = notify_discord(discord, summary).err() {
+            eprintln!("error sending discord notification: {}", e);
+        }
+    }
+}
+
+fn notify_discord(config: &DiscordNotify, summary: &Result<RenameSummary>) -> Result<()> {
+    let embed = match summary {
+        Ok(summary) => {
+            let mut fields = vec![
+                ureq::json!({ "name": "Renamed", "value": summary.renamed.len().to_string(), "inline": true }),
+                ureq::json!({ "name": "Skipped", "value": summary.skipped.to_string(), "inline": true }),
+                ureq::json!({ "name": "Errors", "value": summary.errors.len().to_string(), "inline": true }),
+            ];
+            if !summary.errors.is_empty() {
+                let mut errors = String::new();
+                for error in &summary.errors {
+                    errors.push_str(&format!("{}: {}\n", error.path.display(), error.error));
+                }
+                fields.push(ureq::json!({ "name": "Error details", "value": truncate(&errors) }));
+            }
+            ureq::json!({
+                "title": "VRC Log Renamer",
+                "description": if summary.errors.is_empty() { "Renaming logs succeed" } else { "Some logs failed to rename" },
+                "color": if summary.errors.is_empty() { COLOR_SUCCEED } else { COLOR_FAILED },
+                "fields": fields,
+            })
+        }
+        Err(e) => ureq::json!({
+            "title": "VRC Log Renamer",
+            "description": truncate(&format!("Error during renaming logs: {}", e)),
+            "color": COLOR_FAILED,
+        }),
+    };
+
+    http_agent()
+        .post(config.webhook_url())
+        .send_json(ureq::json!({ "embeds": [embed] }))?;
+    Ok(())
+}
+
+fn truncate(str: &str) -> String {
+    if str.chars().count() <= DISCORD_FIELD_LIMIT {
+        return str.to_owned();
+    }
+    let mut truncated = str.chars().take(DISCORD_FIELD_LIMIT - 1).collect::<String>();
+    truncated.push('…');
+    truncated
+}
@@ -0,0 +1,130 @@
+// VRC Log Renamer - the tool to rename logs of VRChat to have date info
+// Copyright (C) 2022 anatawa12
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Generates the release packaging files from the crate metadata, for the release workflow.
+//! Only built with the `package` feature.
+
+use crate::content_hash;
+use anyhow::{bail, Context, Result};
+use std::fs;
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+const REPOSITORY: &str = env!("CARGO_PKG_REPOSITORY");
+const PUBLISHER: &str = env!("CARGO_PKG_AUTHORS");
+const DESCRIPTION: &str = env!("CARGO_PKG_DESCRIPTION");
+const LICENSE: &str = env!("CARGO_PKG_LICENSE");
+const APP_NAME: &str = "VRC Log Renamer";
+const EXE_NAME: &str = "vrc-log-renamer.exe";
+const WINGET_ID: &str = "anatawa12.VRCLogRenamer";
+
+pub(crate) fn package_command(args: &[String]) -> Result<()> {
+    match args
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        ["winget", exe, file] => {
+            let content = fs::read(exe).with_context(|| format!("reading {}", exe))?;
+            let sha256 = content_hash(&content, "sha256").unwrap();
+            write(file, &winget_manifest(&sha256))?;
+        }
+        ["inno", exe, file] => {
+            write(file, &inno_script(exe))?;
+        }
+        _ => bail!("usage: package winget <exe> <file.yaml> | package inno <exe> <file.iss>"),
+    }
+    Ok(())
+}
+
+fn write(file: &str, text: &str) -> Result<()> {
+    fs::write(file, text).with_context(|| format!("writing {}", file))
+}
+
+/// the release asset uploaded by the release workflow
+fn installer_url() -> String {
+    format!("{}/releases/download/v{}/{}", REPOSITORY, VERSION, EXE_NAME)
+}
+
+/// the singleton manifest of winget for the portable exe
+fn winget_manifest(sha256: &str) -> String {
+    let mut yaml = String::new();
+    yaml.push_str(&format!(
+        "# yaml-language-server: $schema={}\n",
+        "https://aka.ms/winget-manifest.singleton.1.4.0.schema.json"
+    ));
+    yaml.push_str(&format!("PackageIdentifier: {}\n", WINGET_ID));
+    yaml.push_str(&format!("PackageVersion: {}\n", VERSION));
+    yaml.push_str("PackageLocale: en-US\n");
+    yaml.push_str(&format!("Publisher: {}\n", PUBLISHER));
+    yaml.push_str(&format!("PackageName: {}\n", APP_NAME));
+    yaml.push_str(&format!("PackageUrl: {}\n", REPOSITORY));
+    yaml.push_str(&format!("License: {}\n", LICENSE));
+    yaml.push_str(&format!("ShortDescription: {}\n", DESCRIPTION));
+    yaml.push_str("Installers:\n");
+    yaml.push_str("  - Architecture: x64\n");
+    yaml.push_str("    InstallerType: portable\n");
+    yaml.push_str(&format!("    InstallerUrl: {}\n", installer_url()));
+    yaml.push_str(&format!(
+        "    InstallerSha256: {}\n",
+        sha256.to_ascii_uppercase()
+    ));
+    yaml.push_str("    Commands:\n");
+    yaml.push_str(&format!("      - {}\n", EXE_NAME.trim_end_matches(".exe")));
+    yaml.push_str("ManifestType: singleton\n");
+    yaml.push_str("ManifestVersion: 1.4.0\n");
+    yaml
+}
+
+/// the Inno Setup script installing to the same folder as the `install` command
+fn inno_script(exe: &str) -> String {
+    let mut iss = String::new();
+    iss.push_str("[Setup]\n");
+    iss.push_str(&format!("AppName={}\n", APP_NAME));
+    iss.push_str(&format!("AppVersion={}\n", VERSION));
+    iss.push_str(&format!("AppPublisher={}\n", PUBLISHER));
+    iss.push_str(&format!("AppPublisherURL={}\n", REPOSITORY));
+    iss.push_str("DefaultDirName={localappdata}\\Programs\\vrc-log-renamer\n");
+    iss.push_str("DisableProgramGroupPage=yes\n");
+    iss.push_str("PrivilegesRequired=lowest\n");
+    iss.push_str(&format!(
+        "OutputBaseFilename=vrc-log-renamer-{}-setup\n",
+        VERSION
+    ));
+    iss.push_str(&format!("UninstallDisplayIcon={{app}}\\{}\n", EXE_NAME));
+    iss.push_str("\n[Files]\n");
+    iss.push_str(&format!(
+        "Source: \"{}\"; DestDir: \"{{app}}\"; DestName: \"{}\"\n",
+        exe, EXE_NAME
+    ));
+    iss.push_str("\n[Icons]\n");
+    iss.push_str(&format!(
+        "Name: \"{{userprograms}}\\{}\"; Filename: \"{{app}}\\{}\"\n",
+        APP_NAME, EXE_NAME
+    ));
+    iss.push_str("\n[Run]\n");
+    iss.push_str(&format!(
+        "Filename: \"{{app}}\\{}\"; Parameters: \"register_schedule\"; Flags: runhidden\n",
+        EXE_NAME
+    ));
+    iss.push_str("\n[UninstallRun]\n");
+    iss.push_str(&format!(
+        "Filename: \"{{app}}\\{}\"; Parameters: \"unregister_schedule\"; Flags: runhidden; \
+        RunOnceId: \"UnregisterSchedule\"\n",
+        EXE_NAME
+    ));
+    iss
+}
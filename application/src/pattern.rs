@@ -0,0 +1,274 @@
+// VRC Log Renamer - the tool to rename logs of VRChat to have date info
+// Copyright (C) 2022 anatawa12
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The output file name pattern. The string the user wrote is kept and written back to the config
+//! as is, and it's parsed with the strftime of chrono when the items are needed.
+
+use chrono::format::{Fixed, Item, Numeric, StrftimeItems};
+use once_cell::sync::OnceCell;
+
+/// the specifier rejected by `parse_pattern`
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PatternError {
+    /// the byte offset of `%` in the pattern
+    pub position: usize,
+    pub specifier: String,
+}
+
+impl std::fmt::Display for PatternError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "'{}' at position {} is not supported",
+            self.specifier, self.position
+        )
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+/// the pattern with the items parsed from it
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    raw: String,
+    items: OnceCell<Vec<Item<'static>>>,
+}
+
+impl Pattern {
+    /// the pattern known to be valid, like the default ones. it's parsed on the first use
+    pub fn new(raw: &str) -> Self {
+        Self {
+            raw: raw.to_owned(),
+            items: OnceCell::new(),
+        }
+    }
+
+    pub fn parse(raw: &str) -> Result<Self, PatternError> {
+        Ok(Self {
+            raw: raw.to_owned(),
+            items: OnceCell::with_value(parse_pattern(raw)?),
+        })
+    }
+
+    /// the string as the user wrote
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+
+    pub fn items(&self) -> &[Item<'static>] {
+        self.items
+            .get_or_init(|| parse_pattern(&self.raw).expect("invalid pattern"))
+    }
+}
+
+pub fn parse_pattern(str: &str) -> Result<Vec<Item<'static>>, PatternError> {
+    fn own_strftime(item: Item) -> Item<'static> {
+        match item {
+            Item::Literal(s) => Item::OwnedLiteral(s.to_string().into_boxed_str()),
+            Item::Space(s) => Item::OwnedSpace(s.to_string().into_boxed_str()),
+            Item::OwnedLiteral(s) => Item::OwnedLiteral(s),
+            Item::OwnedSpace(s) => Item::OwnedSpace(s),
+            Item::Numeric(n, p) => {
+                if matches!(n, Numeric::Internal(_)) {
+                    // internal format is not allowed
+                    Item::Error
+                } else {
+                    Item::Numeric(n, p)
+                }
+            }
+            Item::Fixed(f) => match f {
+                Fixed::Internal(internal) => {
+                    if format_internal_format(&internal).is_some() {
+                        Item::Fixed(Fixed::Internal(internal))
+                    } else {
+                        Item::Error
+                    }
+                }
+                Fixed::TimezoneOffset | Fixed::TimezoneOffsetZ => Item::Error,
+                f => Item::Fixed(f),
+            },
+            Item::Error => Item::Error,
+        }
+    }
+    let pattern: Vec<Item> = StrftimeItems::new(str).map(own_strftime).collect();
+    if pattern.iter().any(|x| matches!(x, Item::Error)) {
+        // StrftimeItems doesn't tell the span of items so parse the specifiers one by one
+        let mut position = 0;
+        while let Some(offset) = str[position..].find('%') {
+            let start = position + offset;
+            let end = start + specifier_len(&str[start..]);
+            let specifier = &str[start..end];
+            if StrftimeItems::new(specifier)
+                .map(own_strftime)
+                .any(|x| matches!(x, Item::Error))
+            {
+                return Err(PatternError {
+                    position: start,
+                    specifier: specifier.to_owned(),
+                });
+            }
+            position = end;
+        }
+        return Err(PatternError {
+            position: 0,
+            specifier: str.to_owned(),
+        });
+    }
+    Ok(pattern)
+}
+
+/// the length of the specifier at the head of `str` starting with `%`, as StrftimeItems reads
+fn specifier_len(str: &str) -> usize {
+    let mut chars = str.char_indices().skip(1).peekable();
+    // the padding modifier
+    chars.next_if(|&(_, c)| matches!(c, '-' | '0' | '_'));
+    let end = match chars.next() {
+        // %.f, %.3f, %3f, %:z and %#z are read with the next characters
+        Some((_, '.')) => {
+            chars.next_if(|&(_, c)| c.is_ascii_digit());
+            chars.next()
+        }
+        Some((_, c)) if c.is_ascii_digit() || c == ':' || c == '#' => chars.next(),
+        c => c,
+    };
+    end.map_or(str.len(), |(i, c)| i + c.len_utf8())
+}
+
+/// how the logs get the same file name with the pattern, by the finest time in it
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Collision {
+    /// the pattern has neither day nor time
+    Always,
+    SameDay,
+    SameHour,
+    SameMinute,
+}
+
+impl Collision {
+    pub fn name(self) -> &'static str {
+        match self {
+            Collision::Always => "always",
+            Collision::SameDay => "same_day",
+            Collision::SameHour => "same_hour",
+            Collision::SameMinute => "same_minute",
+        }
+    }
+}
+
+/// finds how the logs launched close together collide. None if the pattern has seconds or
+/// `{seq}`, which is replaced with the number not used yet
+pub fn collision(pattern: &[Item<'static>]) -> Option<Collision> {
+    let mut finest = Collision::Always;
+    for item in pattern {
+        let collision = match item {
+            Item::Literal(s) if s.contains("{seq}") => return None,
+            Item::OwnedLiteral(s) if s.contains("{seq}") => return None,
+            Item::Numeric(n, _) => match n {
+                Numeric::Second | Numeric::Nanosecond | Numeric::Timestamp => return None,
+                Numeric::Minute => Collision::SameMinute,
+                Numeric::Hour | Numeric::Hour12 => Collision::SameHour,
+                Numeric::Day | Numeric::Ordinal => Collision::SameDay,
+                _ => continue,
+            },
+            Item::Fixed(
+                Fixed::Nanosecond
+                | Fixed::Nanosecond3
+                | Fixed::Nanosecond6
+                | Fixed::Nanosecond9
+                | Fixed::RFC2822
+                | Fixed::RFC3339
+                | Fixed::Internal(_),
+            ) => return None,
+            _ => continue,
+        };
+        finest = finest.max(collision);
+    }
+    Some(finest)
+}
+
+fn format_internal_format(fixed: &chrono::format::InternalFixed) -> Option<&'static str> {
+    use chrono::format::InternalFixed;
+    use once_cell::race::OnceBox;
+    type MappingType = [(InternalFixed, &'static str); 3];
+    static MAPPING: OnceBox<MappingType> = OnceBox::new();
+    fn init_mapping() -> Box<MappingType> {
+        fn fixed_internal(format: &str) -> (InternalFixed, &str) {
+            match StrftimeItems::new(format).next().unwrap() {
+                Item::Fixed(Fixed::Internal(fixed)) => (fixed, format),
+                _ => unreachable!("fixed_internal init failed"),
+            }
+        }
+        Box::new([
+            fixed_internal("%3f"),
+            fixed_internal("%6f"),
+            fixed_internal("%9f"),
+        ])
+    }
+    MAPPING
+        .get_or_init(init_mapping)
+        .iter()
+        .find(|(pat, _)| pat == fixed)
+        .map(|(_, a)| *a)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pad_variants_kept() {
+        for raw in ["%e_%k", "%_d_%_H", "%0Y-%m", "%F_%T", "%h%n"] {
+            assert_eq!(Pattern::parse(raw).unwrap().as_str(), raw);
+        }
+        let parsed = Pattern::parse("%_d").unwrap();
+        assert_eq!(parsed.items(), Pattern::parse("%e").unwrap().items());
+    }
+
+    #[test]
+    fn parsed_on_first_use() {
+        let raw = "output_log_%Y-%m-%d_%H-%M-%S{regex:in_sec_num}.txt";
+        assert_eq!(
+            Pattern::new(raw).items(),
+            Pattern::parse(raw).unwrap().items()
+        );
+    }
+
+    #[test]
+    fn collisions() {
+        let collision = |raw| collision(&parse_pattern(raw).unwrap());
+        assert_eq!(collision("output_log.txt"), Some(Collision::Always));
+        assert_eq!(collision("%a_%b.txt"), Some(Collision::Always));
+        assert_eq!(collision("%Y-%m-%d.txt"), Some(Collision::SameDay));
+        assert_eq!(collision("%H_%Y-%m-%d.txt"), Some(Collision::SameHour));
+        assert_eq!(collision("%Y-%m-%d_%H-%M.txt"), Some(Collision::SameMinute));
+        assert_eq!(collision("%M_%d_%H.txt"), Some(Collision::SameMinute));
+        assert_eq!(collision("%Y-%m-%d_{seq}.txt"), None);
+        assert_eq!(collision("%Y-%m-%d_%H-%M-%S.txt"), None);
+        assert_eq!(collision("%s.txt"), None);
+        assert_eq!(collision("%F_%T.txt"), None);
+    }
+
+    #[test]
+    fn pattern_error_position() {
+        let error = parse_pattern("output_%Y-%m-%q.txt").unwrap_err();
+        assert_eq!(error.position, 13);
+        assert_eq!(error.specifier, "%q");
+        let error = parse_pattern("%Y%.3f%z").unwrap_err();
+        assert_eq!(error.position, 6);
+        assert_eq!(error.specifier, "%z");
+        assert!(parse_pattern("%%q_%-d_%.f").is_ok());
+    }
+}
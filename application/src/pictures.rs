@@ -0,0 +1,214 @@
+// VRC Log Renamer - the tool to rename logs of VRChat to have date info
+// Copyright (C) 2022 anatawa12
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Renames VRChat pictures with the same pattern machinery as logs.
+
+use crate::config::{Output, Pictures};
+use crate::journal::Journal;
+use crate::{
+    copy_file, copy_file_times, is_selected, move_file, FileError, MatchingIter, RenameSummary,
+    RenamedFile,
+};
+use chrono::{DateTime, Local, NaiveDateTime};
+use regex::Captures;
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// copies or moves pictures in the pictures folder and its subfolders
+pub(crate) fn rename_pictures(
+    config: &Pictures,
+    output: &Output,
+    selection: Option<&HashSet<PathBuf>>,
+    journal: &mut Journal,
+    summary: &mut RenameSummary,
+) -> io::Result<()> {
+    fs::create_dir_all(config.output_folder())?;
+    rename_in_folder(config, output, config.folder(), selection, journal, summary)
+}
+
+fn rename_in_folder(
+    config: &Pictures,
+    output: &Output,
+    folder: &Path,
+    selection: Option<&HashSet<PathBuf>>,
+    journal: &mut Journal,
+    summary: &mut RenameSummary,
+) -> io::Result<()> {
+    for entry in fs::read_dir(folder)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            // the output folder may be in the pictures folder
+            if &path != config.output_folder() {
+                rename_in_folder(config, output, &path, selection, journal, summary)?;
+            }
+            continue;
+        }
+        let file_name = entry.file_name();
+        let file_name = file_name.to_string_lossy();
+        let captures = match config.pattern().captures(&file_name) {
+            Some(captures) => captures,
+            None => continue,
+        };
+        if !is_selected(selection, &path) {
+            info!("{} is not selected. skipping", path.display());
+            continue;
+        }
+        info!("{} matches pattern. checking", path.display());
+        match rename_picture(config, output, &path, captures, journal) {
+            Ok(Some(dst_path)) => summary.renamed.push(RenamedFile {
+                source: path,
+                destination: dst_path,
+            }),
+            Ok(None) => summary.skipped += 1,
+            Err(err) => {
+                warn!("error moving '{}': {}", path.display(), err);
+                summary.errors.push(FileError {
+                    path,
+                    error: err.to_string(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// returns the path to copied/moved picture or None if skipped
+fn rename_picture(
+    config: &Pictures,
+    output: &Output,
+    path: &Path,
+    captures: Captures,
+    journal: &mut Journal,
+) -> io::Result<Option<PathBuf>> {
+    let taken = captures.name("time").and_then(|time| {
+        NaiveDateTime::parse_from_str(time.as_str(), "%Y-%m-%d_%H-%M-%S%.3f").ok()
+    });
+    let taken = match taken {
+        Some(taken) => taken,
+        None => DateTime::<Local>::from(fs::metadata(path)?.created()?).naive_local(),
+    };
+    let world = png_world_name(path)?;
+
+    let pat_iter = MatchingIter::new(config.output_pattern().iter(), |name| {
+        if name == "world" {
+            // the world name may have folder separators
+            let world = world.as_deref().unwrap_or("unknown");
+            return Some(Cow::Owned(output.sanitize_file_name(world)));
+        }
+        let (namespace, name) = name.split_once(':')?;
+        match namespace {
+            "regex" => Some(
+                captures
+                    .name(name)
+                    .map(|matches| Cow::Owned(matches.as_str().to_owned()))
+                    .unwrap_or(Cow::Borrowed("")),
+            ),
+            _ => None,
+        }
+    });
+    let file_name = taken.format_with_items(pat_iter).to_string();
+    let dst_path = config
+        .output_folder()
+        .join(output.sanitize_path(&file_name));
+    if dst_path.exists() {
+        info!(
+            "{} exists. we assume the picture is already copied",
+            dst_path.display()
+        );
+        return Ok(None);
+    }
+    // the pattern may have folders
+    if let Some(parent) = dst_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    journal.begin(path, &dst_path)?;
+    if config.keep_old() {
+        copy_file(path, &dst_path, &mut |_, _| true)?;
+        let metadata = fs::metadata(path)?;
+        let dst_file = fs::File::options().write(true).open(&dst_path)?;
+        copy_file_times(&metadata, &dst_file)?;
+    } else {
+        move_file(path, &dst_path, &mut |_, _| true)?;
+    }
+    journal.end(&dst_path)?;
+    Ok(Some(dst_path))
+}
+
+/// the text chunks larger than this are skipped. the XMP of VRChat is a few KiB
+const MAX_TEXT_CHUNK_LENGTH: u32 = 4 * 1024 * 1024;
+
+/// reads the world name VRChat writes to XMP metadata in the text chunks of png.
+/// None for the truncated or corrupted png so it's renamed as the unknown world
+fn png_world_name(path: &Path) -> io::Result<Option<String>> {
+    let file = BufReader::new(fs::File::open(path)?);
+    Ok(read_png_world_name(file).unwrap_or(None))
+}
+
+fn read_png_world_name(mut file: BufReader<fs::File>) -> io::Result<Option<String>> {
+    let mut signature = [0u8; 8];
+    file.read_exact(&mut signature)?;
+    if signature != PNG_SIGNATURE {
+        return Ok(None);
+    }
+    loop {
+        let mut header = [0u8; 8];
+        match file.read_exact(&mut header) {
+            Ok(()) => {}
+            Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e),
+        }
+        let length = u32::from_be_bytes(header[..4].try_into().unwrap());
+        let kind = &header[4..];
+        if kind == b"IEND" {
+            break;
+        }
+        if (kind == b"iTXt" || kind == b"tEXt") && length <= MAX_TEXT_CHUNK_LENGTH {
+            let mut data = vec![0u8; length as usize];
+            file.read_exact(&mut data)?;
+            // skip crc
+            file.seek_relative(4)?;
+            if let Some(name) = find_world_name(&String::from_utf8_lossy(&data)) {
+                return Ok(Some(name));
+            }
+        } else {
+            file.seek_relative(length as i64 + 4)?;
+        }
+    }
+    Ok(None)
+}
+
+fn find_world_name(xmp: &str) -> Option<String> {
+    let name = if let Some((_, rest)) = xmp.split_once("<vrc:WorldDisplayName>") {
+        rest.split_once("</vrc:WorldDisplayName>")?.0
+    } else {
+        let (_, rest) = xmp.split_once("vrc:WorldDisplayName=\"")?;
+        rest.split_once('"')?.0
+    };
+    Some(
+        name.replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&amp;", "&"),
+    )
+}
@@ -0,0 +1,158 @@
+// VRC Log Renamer - the tool to rename logs of VRChat to have date info
+// Copyright (C) 2022 anatawa12
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The preview lists what the renamer would do for each log with the current config, computed by
+//! the same planning stage as renaming but without modifying anything.
+
+use crate::config::{ConfigFile, Dedupe, Output, Source};
+use crate::ledger::Ledger;
+use crate::vfs::Env;
+use crate::{
+    find_archived, plan_destination, skip_reason, source_entries, sources_sha256, Destination,
+    SkipReason,
+};
+use serde::Serialize;
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug, Serialize)]
+pub(crate) struct PlannedFile {
+    pub source: PathBuf,
+    pub destination: Option<PathBuf>,
+    pub action: Action,
+    /// why the log is skipped, or the error examining the log
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Action {
+    Move,
+    Copy,
+    /// hard link to the log, or to the same log archived before
+    Link,
+    /// copied or moved to `unparsed` folder
+    Quarantine,
+    Skip,
+    Error,
+}
+
+impl Action {
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Move => "move",
+            Action::Copy => "copy",
+            Action::Link => "link",
+            Action::Quarantine => "quarantine",
+            Action::Skip => "skip",
+            Action::Error => "error",
+        }
+    }
+}
+
+/// plans the logs matching the source patterns. the pictures and the mirrors are not included
+pub(crate) fn preview_files(config: &ConfigFile) -> io::Result<Vec<PlannedFile>> {
+    let env = Env::real();
+    let ledger = Ledger::load()?;
+    let watermark = if config.incremental() {
+        ledger.watermark(&sources_sha256(config))
+    } else {
+        None
+    };
+    let mut planned = Vec::new();
+    for source in config.sources() {
+        let output = config.output_for(source);
+        for path in source_entries(env.fs, source, &output)? {
+            let file_name = path.file_name().unwrap().to_string_lossy().into_owned();
+            let captures = match source.pattern().captures(&file_name) {
+                Some(captures) => captures,
+                None => continue,
+            };
+            let plan = match skip_reason(env.fs, source, &path, &ledger, None, watermark) {
+                Ok(Some(reason)) => skipped(path, None, reason),
+                // preview shows the locked log as in use without waiting
+                Ok(None) => match plan_destination(env, source, &output, &path, captures, false) {
+                    // hashed only for the real destination like renaming
+                    Ok(Destination::Path(dst_path)) => {
+                        match find_archived(env.fs, &ledger, &output, &path) {
+                            Ok((_, Some(archived))) if output.dedupe() == Dedupe::Skip => {
+                                skipped(path, Some(archived), SkipReason::AlreadyArchived)
+                            }
+                            Ok((_, archived)) => {
+                                let destination = Destination::Path(dst_path);
+                                plan_file(source, &output, path, destination, archived.is_some())
+                            }
+                            Err(e) => failed(path, e),
+                        }
+                    }
+                    Ok(destination) => plan_file(source, &output, path, destination, false),
+                    Err(e) => failed(path, e),
+                },
+                Err(e) => failed(path, e),
+            };
+            planned.push(plan);
+        }
+    }
+    Ok(planned)
+}
+
+fn plan_file(
+    source: &Source,
+    output: &Output,
+    path: PathBuf,
+    destination: Destination,
+    archived: bool,
+) -> PlannedFile {
+    let (action, destination) = match destination {
+        Destination::Skip(reason) => return skipped(path, None, reason),
+        Destination::Quarantine(dst_path) => (Action::Quarantine, dst_path),
+        // same conditions as move_log_file
+        Destination::Path(dst_path) => {
+            let filtered = !output.filter_lines().is_empty();
+            if archived || source.keep_old() && source.hard_link() && !filtered {
+                (Action::Link, dst_path)
+            } else if source.keep_old() || filtered {
+                (Action::Copy, dst_path)
+            } else {
+                (Action::Move, dst_path)
+            }
+        }
+    };
+    PlannedFile {
+        source: path,
+        destination: Some(destination),
+        action,
+        reason: None,
+    }
+}
+
+fn skipped(path: PathBuf, destination: Option<PathBuf>, reason: SkipReason) -> PlannedFile {
+    PlannedFile {
+        source: path,
+        destination,
+        action: Action::Skip,
+        reason: Some(reason.name().to_owned()),
+    }
+}
+
+fn failed(path: PathBuf, error: io::Error) -> PlannedFile {
+    PlannedFile {
+        source: path,
+        destination: None,
+        action: Action::Error,
+        reason: Some(error.to_string()),
+    }
+}
@@ -0,0 +1,169 @@
+// VRC Log Renamer - the tool to rename logs of VRChat to have date info
+// Copyright (C) 2022 anatawa12
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Measures the time spent in each phase of renaming with `--profile`,
+//! to find out why renaming a large folder or a network drive is slow.
+
+use crate::impact::format_bytes;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static PHASES: Mutex<[PhaseStats; 4]> = Mutex::new([PhaseStats::ZERO; 4]);
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum Phase {
+    /// listing the logs in the source folders
+    Scan,
+    /// reading the launch time and deciding the destination
+    Parse,
+    /// copying, moving or linking to the output and mirror folders
+    Copy,
+    /// hashing the content for dedupe
+    Hash,
+}
+
+impl Phase {
+    const ALL: [Phase; 4] = [Phase::Scan, Phase::Parse, Phase::Copy, Phase::Hash];
+
+    fn name(self) -> &'static str {
+        match self {
+            Phase::Scan => "scan",
+            Phase::Parse => "parse",
+            Phase::Copy => "copy",
+            Phase::Hash => "hash",
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+struct PhaseStats {
+    time: Duration,
+    files: u64,
+    bytes: u64,
+}
+
+impl PhaseStats {
+    const ZERO: PhaseStats = PhaseStats {
+        time: Duration::ZERO,
+        files: 0,
+        bytes: 0,
+    };
+}
+
+pub(crate) fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub(crate) fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// adds the time until dropped to the phase. does nothing if not profiling
+pub(crate) struct Timer {
+    phase: Phase,
+    start: Option<Instant>,
+}
+
+pub(crate) fn start(phase: Phase) -> Timer {
+    Timer {
+        phase,
+        start: is_enabled().then(Instant::now),
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        if let Some(start) = self.start {
+            PHASES.lock().unwrap()[self.phase as usize].time += start.elapsed();
+        }
+    }
+}
+
+/// adds the files and the bytes processed in the phase
+pub(crate) fn count(phase: Phase, files: u64, bytes: u64) {
+    if is_enabled() {
+        let stats = &mut PHASES.lock().unwrap()[phase as usize];
+        stats.files += files;
+        stats.bytes += bytes;
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct PhaseReport {
+    phase: &'static str,
+    seconds: f64,
+    files: u64,
+    bytes: u64,
+    /// zero if no file is processed
+    seconds_per_file: f64,
+    /// zero if no time is spent
+    bytes_per_second: f64,
+}
+
+impl PhaseReport {
+    fn new(phase: Phase, stats: PhaseStats) -> PhaseReport {
+        let seconds = stats.time.as_secs_f64();
+        PhaseReport {
+            phase: phase.name(),
+            seconds,
+            files: stats.files,
+            bytes: stats.bytes,
+            seconds_per_file: if stats.files == 0 {
+                0.0
+            } else {
+                seconds / stats.files as f64
+            },
+            bytes_per_second: if seconds == 0.0 {
+                0.0
+            } else {
+                stats.bytes as f64 / seconds
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for PhaseReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {:.3} s, {} files",
+            self.phase, self.seconds, self.files
+        )?;
+        if self.files != 0 {
+            write!(f, ", {:.1} ms/file", self.seconds_per_file * 1000.0)?;
+        }
+        if self.bytes != 0 {
+            let throughput = format_bytes(self.bytes_per_second as u64);
+            write!(f, ", {}, {}/s", format_bytes(self.bytes), throughput)?;
+        }
+        Ok(())
+    }
+}
+
+/// the time of each phase, or None if not profiling
+pub(crate) fn report() -> Option<Vec<PhaseReport>> {
+    if !is_enabled() {
+        return None;
+    }
+    let phases = PHASES.lock().unwrap();
+    let reports = Phase::ALL
+        .iter()
+        .map(|&phase| PhaseReport::new(phase, phases[phase as usize]));
+    Some(reports.collect())
+}
@@ -0,0 +1,113 @@
+// VRC Log Renamer - the tool to rename logs of VRChat to have date info
+// Copyright (C) 2022 anatawa12
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Installs the running exe for the current user without an installer.
+//! The scheduled task registered from the downloaded exe breaks when the exe is moved or deleted,
+//! so the exe is copied to the per-user programs folder and the task is registered from there.
+
+use crate::config::Schedule;
+use crate::task_managers::uninstall;
+use crate::{create_shortcut, is_same_path};
+use anyhow::{bail, Context, Result};
+use std::os::windows::process::CommandExt;
+use std::path::PathBuf;
+use std::process::Command;
+use std::{fs, io};
+use winsafe::co::{KF, KNOWNFOLDERID};
+use winsafe::SHGetKnownFolderPath;
+
+const EXE_NAME: &str = "vrc-log-renamer.exe";
+const SHORTCUT_NAME: &str = "VRC Log Renamer.lnk";
+/// the config next to the exe is used instead of the one in LocalLow
+const PORTABLE_CONFIG_NAME: &str = "config.toml";
+
+/// `%LOCALAPPDATA%\Programs\vrc-log-renamer`
+fn install_folder() -> Result<PathBuf> {
+    Ok(known_folder(&KNOWNFOLDERID::LocalAppData)?
+        .join("Programs")
+        .join("vrc-log-renamer"))
+}
+
+/// the shortcut in the Start Menu of the current user
+fn shortcut_path() -> Result<PathBuf> {
+    Ok(known_folder(&KNOWNFOLDERID::Programs)?.join(SHORTCUT_NAME))
+}
+
+fn known_folder(id: &KNOWNFOLDERID) -> Result<PathBuf> {
+    SHGetKnownFolderPath(id, KF::DEFAULT, None)
+        .map(PathBuf::from)
+        .context("SHGetKnownFolderPath")
+}
+
+/// copies the exe to the install folder, creates the Start Menu shortcut and registers the
+/// schedule with the installed exe. returns the installed exe
+pub(crate) fn install_app() -> Result<PathBuf> {
+    let current = std::env::current_exe()?;
+    let folder = install_folder()?;
+    let installed = folder.join(EXE_NAME);
+    if !is_same_path(&current, &installed) {
+        fs::create_dir_all(&folder)?;
+        fs::copy(&current, &installed)
+            .with_context(|| format!("copying to {}. is it running?", installed.display()))?;
+        // keep using the config next to the downloaded exe
+        let config = current.with_file_name(PORTABLE_CONFIG_NAME);
+        if config.exists() {
+            fs::copy(&config, folder.join(PORTABLE_CONFIG_NAME))?;
+        }
+    }
+    create_shortcut(&shortcut_path()?, &installed)?;
+    // the task scheduler and the Run key use the path of the running exe
+    let status = Command::new(&installed).arg("register_schedule").status()?;
+    if !status.success() {
+        bail!(
+            "registering the schedule with the installed exe failed: {}",
+            status
+        );
+    }
+    Ok(installed)
+}
+
+/// unregisters the schedule, removes the shortcut and the install folder
+pub(crate) fn uninstall_app(schedule: &Schedule) -> Result<()> {
+    uninstall(schedule)?;
+    remove_if_exists(fs::remove_file(shortcut_path()?))?;
+    let folder = install_folder()?;
+    let current = std::env::current_exe()?;
+    if current
+        .parent()
+        .is_some_and(|parent| is_same_path(parent, &folder))
+    {
+        // the running exe cannot be removed, so cmd removes the folder after exit
+        let script = format!(
+            "ping -n 3 127.0.0.1 > nul & rmdir /s /q \"{}\"",
+            folder.display()
+        );
+        // cmd doesn't understand the quotes escaped by `args`
+        Command::new("cmd.exe")
+            .raw_arg(format!("/c {}", script))
+            .spawn()?;
+    } else {
+        remove_if_exists(fs::remove_dir_all(&folder))?;
+    }
+    Ok(())
+}
+
+fn remove_if_exists(result: io::Result<()>) -> io::Result<()> {
+    match result {
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
@@ -0,0 +1,84 @@
+// VRC Log Renamer - the tool to rename logs of VRChat to have date info
+// Copyright (C) 2022 anatawa12
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Copies the log in use by the running VRChat through a Volume Shadow Copy snapshot of its volume.
+//! The VSS requestor API is not in the bindings we use, so the snapshot is created and deleted
+//! with `Win32_ShadowCopy` of WMI through PowerShell. Creating a snapshot requires administrator.
+
+use crate::impact::volume_of;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// copies `path` as of now to `dst` even if it's opened for writing by another process
+pub(crate) fn copy_from_snapshot(path: &Path, dst: &Path) -> io::Result<()> {
+    let volume = volume_of(path)?;
+    let relative = path
+        .strip_prefix(&volume)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "the log is not in its volume"))?;
+    let (id, device) = create_snapshot(&volume)?;
+    info!("created shadow copy {} of {}", id, volume.display());
+    let result = std::fs::copy(device.join(relative), dst).map(|_| ());
+    if let Err(e) = delete_snapshot(&id) {
+        warn!("error deleting shadow copy {}: {}", id, e);
+    }
+    result
+}
+
+/// returns the id and the device path of the new snapshot
+fn create_snapshot(volume: &Path) -> io::Result<(String, PathBuf)> {
+    let script = format!(
+        "$r = (Get-WmiObject -List Win32_ShadowCopy).Create('{}', 'ClientAccessible'); \
+         if ($r.ReturnValue -ne 0) {{ exit $r.ReturnValue }}; \
+         $s = Get-WmiObject Win32_ShadowCopy | Where-Object {{ $_.ID -eq $r.ShadowID }}; \
+         Write-Output $s.ID; Write-Output $s.DeviceObject",
+        quote(&volume.to_string_lossy()),
+    );
+    let stdout = powershell(&script)?;
+    let mut lines = stdout.lines().map(str::trim).filter(|x| !x.is_empty());
+    match (lines.next(), lines.next()) {
+        (Some(id), Some(device)) => Ok((id.to_owned(), PathBuf::from(format!("{}\\", device)))),
+        _ => Err(io::Error::other("shadow copy is created but not found")),
+    }
+}
+
+fn delete_snapshot(id: &str) -> io::Result<()> {
+    let script = format!(
+        "Get-WmiObject Win32_ShadowCopy | Where-Object {{ $_.ID -eq '{}' }} | \
+         ForEach-Object {{ $_.Delete() }}",
+        quote(id),
+    );
+    powershell(&script).map(|_| ())
+}
+
+/// escapes for the single-quoted string of PowerShell
+fn quote(value: &str) -> String {
+    value.replace('\'', "''")
+}
+
+fn powershell(script: &str) -> io::Result<String> {
+    let output = Command::new("powershell.exe")
+        .args(["-NoProfile", "-NonInteractive", "-Command", script])
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "powershell exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -0,0 +1,128 @@
+// VRC Log Renamer - the tool to rename logs of VRChat to have date info
+// Copyright (C) 2022 anatawa12
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Splits a session log into one file per world visit.
+
+use crate::config::Output;
+use chrono::NaiveDateTime;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+/// the line the visit to the world starts. the world id follows
+pub(crate) const JOINING_MARKER: &str = "[Behaviour] Joining wrld_";
+/// the line with the name of the world joining
+pub(crate) const ROOM_NAME_MARKERS: &[&str] = &[
+    "[Behaviour] Joining or Creating Room: ",
+    "[Behaviour] Entering Room: ",
+];
+/// the line the visit to the world ends
+const LEFT_MARKERS: &[&str] = &["[Behaviour] OnLeftRoom", "Left Room"];
+
+struct Visit {
+    start: NaiveDateTime,
+    world_name: Option<String>,
+    lines: Vec<u8>,
+}
+
+/// splits the log into `worlds` folder in the output folder.
+/// the files are named with the visit start time and the world name.
+/// returns the paths of the written files. existing files are not overwritten
+pub(crate) fn split_by_world(log: &Path, output: &Output) -> io::Result<Vec<PathBuf>> {
+    let folder = output.folder().join("worlds");
+    let mut written = Vec::new();
+    let mut current: Option<Visit> = None;
+    let mut reader = BufReader::new(fs::File::open(log)?);
+    let mut line = Vec::new();
+
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+        let text = String::from_utf8_lossy(&line);
+        if text.contains(JOINING_MARKER) {
+            if let Some(visit) = current.take() {
+                written.extend(write_visit(&folder, output, visit)?);
+            }
+            if let Some(start) = line_time(&text) {
+                current = Some(Visit {
+                    start,
+                    world_name: None,
+                    lines: Vec::new(),
+                });
+            }
+        }
+        if let Some(visit) = &mut current {
+            if visit.world_name.is_none() {
+                visit.world_name = ROOM_NAME_MARKERS
+                    .iter()
+                    .find_map(|marker| text.split_once(marker))
+                    .map(|(_, name)| name.trim().to_owned());
+            }
+            visit.lines.extend_from_slice(&line);
+            if LEFT_MARKERS.iter().any(|marker| text.contains(marker)) {
+                written.extend(write_visit(&folder, output, current.take().unwrap())?);
+            }
+        }
+    }
+    if let Some(visit) = current.take() {
+        written.extend(write_visit(&folder, output, visit)?);
+    }
+    Ok(written)
+}
+
+/// returns the name of the first world visited in the log for `{world}` token
+pub(crate) fn first_world_name(log: impl Read) -> io::Result<Option<String>> {
+    let mut reader = BufReader::new(log);
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            return Ok(None);
+        }
+        let text = String::from_utf8_lossy(&line);
+        if let Some((_, name)) = ROOM_NAME_MARKERS
+            .iter()
+            .find_map(|marker| text.split_once(marker))
+        {
+            return Ok(Some(name.trim().to_owned()));
+        }
+    }
+}
+
+/// parses the time at the head of log line like `2022.10.01 12:34:56`
+pub(crate) fn line_time(line: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(line.get(..19)?, "%Y.%m.%d %H:%M:%S").ok()
+}
+
+fn write_visit(folder: &Path, output: &Output, visit: Visit) -> io::Result<Option<PathBuf>> {
+    let world_name = visit.world_name.as_deref().unwrap_or("unknown");
+    let file_name = format!(
+        "{}_{}.txt",
+        visit.start.format("%Y-%m-%d_%H-%M-%S"),
+        world_name
+    );
+    let path = folder.join(output.sanitize_file_name(&file_name));
+    if path.exists() {
+        return Ok(None);
+    }
+    fs::create_dir_all(folder)?;
+    let mut writer = BufWriter::new(fs::File::create(&path)?);
+    writer.write_all(&visit.lines)?;
+    writer.flush()?;
+    Ok(Some(path))
+}
@@ -0,0 +1,328 @@
+// VRC Log Renamer - the tool to rename logs of VRChat to have date info
+// Copyright (C) 2022 anatawa12
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The play time statistics summarized from the archived logs, saved next to the config file.
+//! Each archived log is parsed once into a session so summarizing doesn't read all the logs.
+
+use crate::config_file_path;
+use crate::split::{line_time, JOINING_MARKER, ROOM_NAME_MARKERS};
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Error, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+/// the format of the times in the store, in local time like the logs
+const TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Stats {
+    #[serde(default)]
+    sessions: Vec<Session>,
+}
+
+/// one launch of VRChat parsed from the archived log
+#[derive(Serialize, Deserialize, Debug)]
+struct Session {
+    log: PathBuf,
+    /// the time of the first line
+    start: String,
+    /// the time of the last line
+    end: String,
+    #[serde(default)]
+    visits: Vec<Visit>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct Visit {
+    joined_at: String,
+    world_name: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Summary {
+    pub sessions: usize,
+    pub total_hours: f64,
+    /// the recent weeks from the oldest, including the weeks not played
+    pub weeks: Vec<WeekHours>,
+    /// the worlds sorted by the number of visits
+    pub worlds: Vec<WorldVisits>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct WeekHours {
+    /// the monday of the week
+    #[serde(serialize_with = "serialize_date")]
+    pub week: NaiveDate,
+    pub hours: f64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct WorldVisits {
+    pub world_name: String,
+    pub visits: usize,
+}
+
+fn stats_file_path() -> PathBuf {
+    config_file_path().with_file_name("stats.toml")
+}
+
+impl Stats {
+    pub fn load() -> io::Result<Self> {
+        match fs::read_to_string(stats_file_path()) {
+            Ok(toml) => toml::from_str(&toml).map_err(|e| Error::new(ErrorKind::InvalidData, e)),
+            Err(ref e) if e.kind() == ErrorKind::NotFound => Ok(Default::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = stats_file_path();
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(
+            path,
+            toml::to_string(self).map_err(|e| Error::new(ErrorKind::InvalidData, e))?,
+        )
+    }
+
+    /// parses the archived logs not parsed yet. the logs failed to parse are tried again next time
+    pub fn update<'a>(&mut self, archived: impl Iterator<Item = &'a Path>) {
+        for log in archived {
+            if !log.exists() || self.sessions.iter().any(|x| x.log == log) {
+                continue;
+            }
+            match parse_session(log) {
+                Ok(Some(session)) => self.sessions.push(session),
+                Ok(None) => info!("{} has no timestamped lines", log.display()),
+                Err(e) => warn!("error parsing {}: {}", log.display(), e),
+            }
+        }
+    }
+
+    /// summarizes the last `weeks` weeks and the `worlds` most visited worlds
+    pub fn summary(&self, weeks: usize, worlds: usize) -> Summary {
+        let this_week = week_of(Local::now().naive_local().date());
+        let mut week_hours = (0..weeks as i64)
+            .rev()
+            .map(|i| WeekHours {
+                week: this_week - Duration::weeks(i),
+                hours: 0.0,
+            })
+            .collect::<Vec<_>>();
+        let mut total_hours = 0.0;
+        let mut visits = HashMap::<&str, usize>::new();
+        for session in &self.sessions {
+            if let (Some(start), Some(end)) = (parse_time(&session.start), parse_time(&session.end))
+            {
+                let hours = (end - start).num_seconds() as f64 / 3600.0;
+                total_hours += hours;
+                let week = week_of(start.date());
+                if let Some(x) = week_hours.iter_mut().find(|x| x.week == week) {
+                    x.hours += hours;
+                }
+            }
+            for visit in &session.visits {
+                *visits.entry(&visit.world_name).or_default() += 1;
+            }
+        }
+        let mut visits = visits
+            .into_iter()
+            .map(|(world_name, visits)| WorldVisits {
+                world_name: world_name.to_owned(),
+                visits,
+            })
+            .collect::<Vec<_>>();
+        visits.sort_by(|a, b| {
+            b.visits
+                .cmp(&a.visits)
+                .then(a.world_name.cmp(&b.world_name))
+        });
+        visits.truncate(worlds);
+        Summary {
+            sessions: self.sessions.len(),
+            total_hours,
+            weeks: week_hours,
+            worlds: visits,
+        }
+    }
+
+    /// the sessions as CSV with the columns start, end and worlds separated by `; `
+    pub fn sessions_csv(&self) -> String {
+        let mut csv = String::from("start,end,worlds\r\n");
+        for session in self.sorted_sessions() {
+            let worlds = session.world_names().join("; ");
+            csv.push_str(&format!(
+                "{},{},{}\r\n",
+                session.start,
+                session.end,
+                csv_field(&worlds)
+            ));
+        }
+        csv
+    }
+
+    /// the sessions as iCalendar events in the local time without the time zone
+    pub fn sessions_ics(&self) -> String {
+        const ICS_TIME: &str = "%Y%m%dT%H%M%S";
+        let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_owned(),
+            "VERSION:2.0".to_owned(),
+            "PRODID:-//anatawa12//VRC Log Renamer//EN".to_owned(),
+        ];
+        for session in self.sorted_sessions() {
+            let (start, end) = match (parse_time(&session.start), parse_time(&session.end)) {
+                (Some(start), Some(end)) => (start, end),
+                _ => continue,
+            };
+            let file_name = session
+                .log
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy();
+            lines.push("BEGIN:VEVENT".to_owned());
+            lines.push(format!(
+                "UID:{}-{}@vrc-log-renamer",
+                start.format(ICS_TIME),
+                ics_text(&file_name)
+            ));
+            lines.push(format!("DTSTAMP:{}", stamp));
+            lines.push(format!("DTSTART:{}", start.format(ICS_TIME)));
+            lines.push(format!("DTEND:{}", end.format(ICS_TIME)));
+            lines.push("SUMMARY:VRChat".to_owned());
+            let worlds = session.world_names().join("\n");
+            if !worlds.is_empty() {
+                lines.push(format!("DESCRIPTION:{}", ics_text(&worlds)));
+            }
+            lines.push("END:VEVENT".to_owned());
+        }
+        lines.push("END:VCALENDAR".to_owned());
+        lines
+            .iter()
+            .map(|line| fold_ics_line(line) + "\r\n")
+            .collect()
+    }
+
+    fn sorted_sessions(&self) -> Vec<&Session> {
+        let mut sessions = self.sessions.iter().collect::<Vec<_>>();
+        sessions.sort_by(|a, b| a.start.cmp(&b.start));
+        sessions
+    }
+}
+
+impl Session {
+    /// the worlds joined in the session without repeating the same world in a row
+    fn world_names(&self) -> Vec<&str> {
+        let mut names = self
+            .visits
+            .iter()
+            .map(|x| x.world_name.as_str())
+            .collect::<Vec<_>>();
+        names.dedup();
+        names
+    }
+}
+
+/// quotes the field if it has the characters special in CSV
+fn csv_field(value: &str) -> String {
+    if value.contains(&[',', '"', '\r', '\n'][..]) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+/// escapes the TEXT value of iCalendar
+fn ics_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// folds the content line longer than 75 octets as iCalendar requires
+fn fold_ics_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            // the leading space is counted
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded
+}
+
+/// the monday of the week
+fn week_of(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+fn serialize_date<S: Serializer>(date: &NaiveDate, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(&date.format("%Y-%m-%d"))
+}
+
+fn parse_time(time: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(time, TIME_FORMAT).ok()
+}
+
+/// reads the first and last time and the worlds joined. None if no line has the time
+fn parse_session(log: &Path) -> io::Result<Option<Session>> {
+    let mut reader = BufReader::new(fs::File::open(log)?);
+    let mut line = Vec::new();
+    let mut first = None;
+    let mut last = None;
+    let mut joining = None;
+    let mut visits = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+        let text = String::from_utf8_lossy(&line);
+        let time = match line_time(&text) {
+            Some(time) => time,
+            None => continue,
+        };
+        first.get_or_insert(time);
+        last = Some(time);
+        if text.contains(JOINING_MARKER) {
+            joining = Some(time);
+        } else if let Some((_, name)) = ROOM_NAME_MARKERS
+            .iter()
+            .find_map(|marker| text.split_once(marker))
+        {
+            // the room name follows the joining line. the second marker of the same join is ignored
+            if let Some(joined_at) = joining.take() {
+                visits.push(Visit {
+                    joined_at: joined_at.format(TIME_FORMAT).to_string(),
+                    world_name: name.trim().to_owned(),
+                });
+            }
+        }
+    }
+    Ok(first.zip(last).map(|(first, last)| Session {
+        log: log.to_owned(),
+        start: first.format(TIME_FORMAT).to_string(),
+        end: last.format(TIME_FORMAT).to_string(),
+        visits,
+    }))
+}
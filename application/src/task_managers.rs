@@ -14,68 +14,433 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use anyhow::Result;
+use crate::config::{Installer, Schedule, Trigger};
+use crate::config_file_path;
+use anyhow::{Context, Result};
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
 use winsafe::prelude::*;
 use winsafe::*;
 use winsafe::co::TASK_ACTION_TYPE;
+use windows::core::HSTRING;
+use windows::Win32::Foundation::ERROR_FILE_NOT_FOUND;
+use windows::Win32::System::Registry::{
+    RegDeleteKeyValueW, RegSetKeyValueW, HKEY_CURRENT_USER, REG_SZ,
+};
 
 // see https://learn.microsoft.com/en-us/windows/win32/taskschd/daily-trigger-example--c---
 
-const TASK_NAME: &'static str = "com.anatawa12.vrc-log-renamer";
+/// the name of the task registered to the root folder by older versions
+const LEGACY_TASK_NAME: &'static str = "com.anatawa12.vrc-log-renamer";
 
-pub(crate) fn register_task_manager() -> Result<()> {
-    let _scope = CoInitializeEx(co::COINIT::MULTITHREADED);
+/// the error of a COM call to the task scheduler with the name of the call
+#[derive(Debug)]
+pub(crate) struct TaskSchedulerError {
+    call: &'static str,
+    hresult: co::HRESULT,
+}
+
+impl TaskSchedulerError {
+    /// returns true if the task scheduler is not accessible by permission or policy
+    pub(crate) fn is_access_denied(&self) -> bool {
+        // SCHED_E_SERVICE_NOT_RUNNING: the service is disabled by the policy
+        self.hresult == co::HRESULT::E_ACCESSDENIED || self.hresult.raw() == 0x8004_1315
+    }
+}
+
+impl Display for TaskSchedulerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} failed: {}", self.call, self.hresult)
+    }
+}
+
+impl std::error::Error for TaskSchedulerError {}
+
+/// adds the name of the COM call to the error
+trait CallContext<T> {
+    fn call(self, call: &'static str) -> Result<T, TaskSchedulerError>;
+}
 
+impl<T> CallContext<T> for HrResult<T> {
+    fn call(self, call: &'static str) -> Result<T, TaskSchedulerError> {
+        self.map_err(|hresult| TaskSchedulerError { call, hresult })
+    }
+}
+
+impl<T> CallContext<T> for windows::core::Result<T> {
+    fn call(self, call: &'static str) -> Result<T, TaskSchedulerError> {
+        self.map_err(|e| TaskSchedulerError {
+            call,
+            hresult: unsafe { co::HRESULT::from_raw(e.code().0 as u32) },
+        })
+    }
+}
+
+/// connects to the task scheduler and returns the service and the root folder
+fn connect() -> Result<(ITaskService, ITaskFolder)> {
     let service: ITaskService =
-        CoCreateInstance(&co::CLSID::TaskScheduler, None, co::CLSCTX::INPROC_SERVER)?;
+        CoCreateInstance(&co::CLSID::TaskScheduler, None, co::CLSCTX::INPROC_SERVER)
+            .call("CoCreateInstance(TaskScheduler)")?;
 
-    service.Connect(None, None, None, None)?;
+    service
+        .Connect(None, None, None, None)
+        .call("ITaskService::Connect")?;
 
-    let root_folder: ITaskFolder = service.GetFolder(&r"\")?;
+    let root_folder: ITaskFolder = service.GetFolder(r"\").call("ITaskService::GetFolder")?;
 
-    // delete if exists
-    root_folder.DeleteTask(TASK_NAME).ok();
+    Ok((service, root_folder))
+}
+
+/// deletes the task. not existing task is not an error
+fn delete_task(folder: &ITaskFolder, path: &str) -> Result<()> {
+    match folder.DeleteTask(path) {
+        Ok(()) => Ok(()),
+        // HRESULT_FROM_WIN32(ERROR_FILE_NOT_FOUND) and HRESULT_FROM_WIN32(ERROR_PATH_NOT_FOUND)
+        Err(e) if matches!(e.raw(), 0x8007_0002 | 0x8007_0003) => Ok(()),
+        Err(e) => Err(e).call("ITaskFolder::DeleteTask")?,
+    }
+}
 
-    let task: ITaskDefinition = service.NewTask()?;
+/// the file remembering the path of the registered task,
+/// to remove the task when `schedule.task_path` is changed
+fn registered_task_file() -> PathBuf {
+    config_file_path().with_file_name("registered-task.txt")
+}
+
+/// deletes the task registered before if it's not at the path
+fn delete_registered_task(folder: &ITaskFolder, path: Option<&str>) -> Result<()> {
+    let registered = match fs::read_to_string(registered_task_file()) {
+        Ok(registered) => registered,
+        Err(ref e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e).context("reading the registered task"),
+    };
+    let registered = registered.trim();
+    let same = path.is_some_and(|path| path.eq_ignore_ascii_case(registered));
+    if !registered.is_empty() && !same {
+        delete_task(folder, registered)?;
+        delete_empty_folders(folder, registered)?;
+    }
+    Ok(())
+}
+
+/// deletes the folders of the task path from the innermost while they are empty
+fn delete_empty_folders(folder: &ITaskFolder, path: &str) -> Result<()> {
+    use windows::core::{Vtable, BSTR};
+    use windows::Win32::System::TaskScheduler::ITaskFolder as WinTaskFolder;
+    // DeleteFolder is not provided by winsafe
+    let raw = folder.ptr();
+    let folder = unsafe { WinTaskFolder::from_raw_borrowed(&raw) };
+    let mut path = path;
+    while let Some((parent, _)) = path.rsplit_once('\\') {
+        match unsafe { folder.DeleteFolder(&BSTR::from(parent), 0) } {
+            Ok(()) => {}
+            // HRESULT_FROM_WIN32(ERROR_FILE_NOT_FOUND) and HRESULT_FROM_WIN32(ERROR_PATH_NOT_FOUND)
+            Err(e) if matches!(e.code().0 as u32, 0x8007_0002 | 0x8007_0003) => {}
+            // HRESULT_FROM_WIN32(ERROR_DIR_NOT_EMPTY): other tasks are in the folder
+            Err(e) if e.code().0 as u32 == 0x8007_0091 => break,
+            Err(e) => Err(e).call("ITaskFolder::DeleteFolder")?,
+        }
+        path = parent;
+    }
+    Ok(())
+}
+
+/// remembers the path of the registered task, or forgets if None
+fn remember_registered_task(path: Option<&str>) {
+    let file = registered_task_file();
+    let result = match path {
+        Some(path) => fs::write(&file, path),
+        None => match fs::remove_file(&file) {
+            Err(ref e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            result => result,
+        },
+    };
+    if let Err(e) = result {
+        warn!("error writing {}: {}", file.display(), e);
+    }
+}
+
+/// registers the task and returns the next run time computed by the task scheduler
+pub(crate) fn register_task_manager(schedule: &Schedule) -> Result<Option<NaiveDateTime>> {
+    let _scope = CoInitializeEx(co::COINIT::MULTITHREADED);
+
+    let (service, root_folder) = connect()?;
+
+    // delete if exists. the task of older versions is also removed to migrate
+    delete_task(&root_folder, LEGACY_TASK_NAME)?;
+    delete_registered_task(&root_folder, Some(task_path(schedule)))?;
+    delete_task(&root_folder, task_path(schedule))?;
+
+    let task: ITaskDefinition = service.NewTask().call("ITaskService::NewTask")?;
     drop(service);
 
-    task.get_RegistrationInfo()?.put_Author(&"anatawa12")?;
-
-    let daily_trigger: IDailyTrigger = task
-        .get_Triggers()?
-        .Create(co::TASK_TRIGGER_TYPE2::DAILY)?
-        .QueryInterface::<IDailyTrigger>()?;
-    daily_trigger.put_Id(&"Trigger1")?;
-    daily_trigger.put_StartBoundary(&"2022-10-14T00:00:00")?;
-    daily_trigger.put_DaysInterval(1)?;
-
-    let action: IExecAction = task.get_Actions()?.Create(TASK_ACTION_TYPE::EXEC)?.QueryInterface()?;
-    action.put_Path(&std::env::current_exe()?.to_string_lossy().as_ref())?;
-    action.put_Arguments(&"scheduled")?;
-
-    let _task: IRegisteredTask = root_folder.RegisterTaskDefinition(
-        Some(TASK_NAME),
-        &task,
-        co::TASK_CREATION::CREATE_OR_UPDATE,
-        None,
-        None,
-        co::TASK_LOGON::INTERACTIVE_TOKEN,
-        None,
+    task.get_RegistrationInfo()
+        .and_then(|info| info.put_Author(&"anatawa12"))
+        .call("IRegistrationInfo::put_Author")?;
+
+    create_triggers(
+        &task.get_Triggers().call("ITaskDefinition::get_Triggers")?,
+        schedule,
     )?;
+    set_settings(&task, schedule)?;
+
+    let action: IExecAction = task
+        .get_Actions()
+        .and_then(|actions| actions.Create(TASK_ACTION_TYPE::EXEC))
+        .and_then(|action| action.QueryInterface())
+        .call("IActionCollection::Create")?;
+    action
+        .put_Path(&std::env::current_exe()?.to_string_lossy().as_ref())
+        .call("IExecAction::put_Path")?;
+    action
+        .put_Arguments(&"scheduled")
+        .call("IExecAction::put_Arguments")?;
+
+    let task: IRegisteredTask = root_folder
+        .RegisterTaskDefinition(
+            Some(task_path(schedule)),
+            &task,
+            co::TASK_CREATION::CREATE_OR_UPDATE,
+            None,
+            None,
+            co::TASK_LOGON::INTERACTIVE_TOKEN,
+            None,
+        )
+        .call("ITaskFolder::RegisterTaskDefinition")?;
+    remember_registered_task(Some(task_path(schedule)));
+    Ok(from_variant_time(
+        task.get_NextRunTime()
+            .call("IRegisteredTask::get_NextRunTime")?,
+    ))
+}
+
+/// creates triggers for each trigger in the config.
+/// every N hours trigger is represented with daily triggers at each time since
+/// repetition pattern is not available
+fn create_triggers(triggers: &ITriggerCollection, schedule: &Schedule) -> Result<()> {
+    let mut index = 0;
+    let mut create_daily = |time: NaiveTime, days: u16| -> Result<()> {
+        index += 1;
+        let daily_trigger: IDailyTrigger = triggers
+            .Create(co::TASK_TRIGGER_TYPE2::DAILY)
+            .and_then(|trigger| trigger.QueryInterface::<IDailyTrigger>())
+            .call("ITriggerCollection::Create")?;
+        daily_trigger
+            .put_Id(&format!("Trigger{}", index))
+            .call("IDailyTrigger::put_Id")?;
+        daily_trigger
+            .put_StartBoundary(&format!("2022-10-14T{}", time.format("%H:%M:%S")))
+            .call("IDailyTrigger::put_StartBoundary")?;
+        daily_trigger
+            .put_DaysInterval(days as i16)
+            .call("IDailyTrigger::put_DaysInterval")?;
+        Ok(())
+    };
+
+    for trigger in schedule.triggers() {
+        match *trigger {
+            Trigger::Daily => {
+                create_daily(schedule.time(), schedule.interval_days())?;
+            }
+            Trigger::Hours(hours) => {
+                for i in 0..(24 / hours) {
+                    let offset = Duration::hours((i * hours) as i64);
+                    create_daily(schedule.time() + offset, 1)?;
+                }
+            }
+            Trigger::Logon => {
+                let logon_trigger: ILogonTrigger = triggers
+                    .Create(co::TASK_TRIGGER_TYPE2::LOGON)
+                    .and_then(|trigger| trigger.QueryInterface::<ILogonTrigger>())
+                    .call("ITriggerCollection::Create")?;
+                // without user id, the task runs on logon of any user
+                if let (Ok(domain), Ok(user)) =
+                    (std::env::var("USERDOMAIN"), std::env::var("USERNAME"))
+                {
+                    logon_trigger
+                        .put_UserId(&format!("{}\\{}", domain, user))
+                        .call("ILogonTrigger::put_UserId")?;
+                }
+            }
+        }
+    }
     Ok(())
 }
 
-pub(crate) fn unregister_task_manager() -> Result<()> {
+/// sets the task settings. winsafe does not have ITaskSettings so use windows crate
+fn set_settings(task: &ITaskDefinition, schedule: &Schedule) -> Result<()> {
+    use windows::core::Vtable;
+    use windows::Win32::System::TaskScheduler::ITaskDefinition as RawTaskDefinition;
+    fn variant_bool(value: bool) -> i16 {
+        if value {
+            -1
+        } else {
+            0
+        }
+    }
+    unsafe {
+        let raw = task.ptr();
+        let settings = RawTaskDefinition::from_raw_borrowed(&raw)
+            .Settings()
+            .call("ITaskDefinition::get_Settings")?;
+        settings
+            .SetWakeToRun(variant_bool(schedule.wake_to_run()))
+            .call("ITaskSettings::put_WakeToRun")?;
+        settings
+            .SetStartWhenAvailable(variant_bool(schedule.start_when_available()))
+            .call("ITaskSettings::put_StartWhenAvailable")?;
+    }
+    Ok(())
+}
+
+/// converts the OLE automation date to date time. zero means no time
+fn from_variant_time(time: f64) -> Option<NaiveDateTime> {
+    if time == 0.0 {
+        return None;
+    }
+    let epoch = NaiveDate::from_ymd_opt(1899, 12, 30)?.and_hms_opt(0, 0, 0)?;
+    epoch.checked_add_signed(Duration::seconds((time * 86400.0).round() as i64))
+}
+
+/// returns the XML of the registered task
+pub(crate) fn export_task(schedule: &Schedule) -> Result<String> {
+    use windows::core::{Vtable, BSTR};
+    use windows::Win32::System::TaskScheduler::ITaskFolder as RawTaskFolder;
     let _scope = CoInitializeEx(co::COINIT::MULTITHREADED);
 
-    let service: ITaskService =
-        CoCreateInstance(&co::CLSID::TaskScheduler, None, co::CLSCTX::INPROC_SERVER)?;
+    let (_service, root_folder) = connect()?;
+
+    // winsafe does not have ITaskFolder::GetTask so use windows crate
+    let xml = unsafe {
+        let raw = root_folder.ptr();
+        RawTaskFolder::from_raw_borrowed(&raw)
+            .GetTask(&BSTR::from(task_path(schedule)))
+            .call("ITaskFolder::GetTask")
+            .with_context(|| format!("task {} is not registered", schedule.task_path()))?
+            .Xml()
+            .call("IRegisteredTask::get_Xml")?
+    };
+    Ok(xml.to_string())
+}
+
+/// registers the task with the XML to the configured path and returns the next run time
+pub(crate) fn import_task(schedule: &Schedule, xml: &str) -> Result<Option<NaiveDateTime>> {
+    let _scope = CoInitializeEx(co::COINIT::MULTITHREADED);
+
+    let (service, root_folder) = connect()?;
+
+    let task: ITaskDefinition = service.NewTask().call("ITaskService::NewTask")?;
+    drop(service);
 
-    service.Connect(None, None, None, None)?;
+    task.put_XmlText(xml)
+        .call("ITaskDefinition::put_XmlText")
+        .context("parsing task XML")?;
 
-    let root_folder: ITaskFolder = service.GetFolder(r"\")?;
+    delete_task(&root_folder, LEGACY_TASK_NAME)?;
+    delete_registered_task(&root_folder, Some(task_path(schedule)))?;
+
+    let task: IRegisteredTask = root_folder
+        .RegisterTaskDefinition(
+            Some(task_path(schedule)),
+            &task,
+            co::TASK_CREATION::CREATE_OR_UPDATE,
+            None,
+            None,
+            co::TASK_LOGON::INTERACTIVE_TOKEN,
+            None,
+        )
+        .call("ITaskFolder::RegisterTaskDefinition")?;
+    remember_registered_task(Some(task_path(schedule)));
+    Ok(from_variant_time(
+        task.get_NextRunTime()
+            .call("IRegisteredTask::get_NextRunTime")?,
+    ))
+}
+
+/// returns the task path relative to the root folder. missing folders are created on registration
+fn task_path(schedule: &Schedule) -> &str {
+    schedule.task_path().trim_start_matches('\\')
+}
+
+pub(crate) fn unregister_task_manager(schedule: &Schedule) -> Result<()> {
+    let _scope = CoInitializeEx(co::COINIT::MULTITHREADED);
+
+    let (_service, root_folder) = connect()?;
 
     // delete if exists
-    root_folder.DeleteTask(TASK_NAME).ok();
+    delete_task(&root_folder, LEGACY_TASK_NAME)?;
+    delete_registered_task(&root_folder, None)?;
+    delete_task(&root_folder, task_path(schedule))?;
+    remember_registered_task(None);
     Ok(())
 }
+
+/// installs with the installer in config and removes the other installation.
+/// returns the next run time if task scheduler is used
+pub(crate) fn install(schedule: &Schedule) -> Result<Option<NaiveDateTime>> {
+    match schedule.installer() {
+        Installer::TaskScheduler => {
+            let next_run = register_task_manager(schedule)?;
+            unregister_run_key()?;
+            Ok(next_run)
+        }
+        Installer::RunKey => {
+            register_run_key()?;
+            // task scheduler may be restricted so errors are ignored
+            unregister_task_manager(schedule).ok();
+            Ok(None)
+        }
+    }
+}
+
+/// removes both installations
+pub(crate) fn uninstall(schedule: &Schedule) -> Result<()> {
+    unregister_run_key()?;
+    match schedule.installer() {
+        Installer::TaskScheduler => unregister_task_manager(schedule),
+        Installer::RunKey => {
+            unregister_task_manager(schedule).ok();
+            Ok(())
+        }
+    }
+}
+
+const RUN_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Run";
+const RUN_VALUE_NAME: &str = "VRC Log Renamer";
+
+/// registers the renamer to run at logon with the `Run` key of current user
+pub(crate) fn register_run_key() -> Result<()> {
+    let command = format!("\"{}\" scheduled", std::env::current_exe()?.display());
+    let data = command.encode_utf16().chain(Some(0)).collect::<Vec<_>>();
+    unsafe {
+        RegSetKeyValueW(
+            HKEY_CURRENT_USER,
+            &HSTRING::from(RUN_KEY),
+            &HSTRING::from(RUN_VALUE_NAME),
+            REG_SZ.0,
+            Some(data.as_ptr().cast()),
+            (data.len() * 2) as u32,
+        )
+    }
+    .to_hresult()
+    .ok()
+    .context("RegSetKeyValueW")
+}
+
+/// removes the renamer from the `Run` key of current user if exists
+pub(crate) fn unregister_run_key() -> Result<()> {
+    let result = unsafe {
+        RegDeleteKeyValueW(
+            HKEY_CURRENT_USER,
+            &HSTRING::from(RUN_KEY),
+            &HSTRING::from(RUN_VALUE_NAME),
+        )
+    };
+    if result == ERROR_FILE_NOT_FOUND {
+        return Ok(());
+    }
+    result.to_hresult().ok().context("RegDeleteKeyValueW")
+}
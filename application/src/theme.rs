@@ -0,0 +1,172 @@
+// VRC Log Renamer - the tool to rename logs of VRChat to have date info
+// Copyright (C) 2022 anatawa12
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The dark mode of GUI. Win32 controls don't support dark mode officially, so the colors are set
+//! with WM_CTLCOLOR* messages and the undocumented dark visual styles used by Explorer.
+
+use crate::config::Theme;
+use once_cell::sync::OnceCell;
+use windows::core::{w, HSTRING};
+use windows::Win32::Foundation::{BOOL, ERROR_SUCCESS, HWND as RawHwnd};
+use windows::Win32::Graphics::Dwm::{DwmSetWindowAttribute, DWMWA_USE_IMMERSIVE_DARK_MODE};
+use windows::Win32::System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_DWORD};
+use windows::Win32::UI::Controls::SetWindowTheme;
+use winsafe::prelude::*;
+use winsafe::{co, gui, AnyResult, COLORREF, HBRUSH, HDC, HWND};
+
+const BACKGROUND_COLOR: COLORREF = COLORREF::new(32, 32, 32);
+const CONTROL_COLOR: COLORREF = COLORREF::new(51, 51, 51);
+const TEXT_COLOR: COLORREF = COLORREF::new(240, 240, 240);
+
+const PERSONALIZE_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Themes\Personalize";
+
+/// the raw handles of the brushes since HBRUSH cannot be shared between threads
+struct DarkBrushes {
+    background: usize,
+    control: usize,
+}
+
+impl DarkBrushes {
+    fn background(&self) -> HBRUSH {
+        unsafe { HBRUSH::from_ptr(self.background as _) }
+    }
+
+    fn control(&self) -> HBRUSH {
+        unsafe { HBRUSH::from_ptr(self.control as _) }
+    }
+}
+
+static DARK_BRUSHES: OnceCell<DarkBrushes> = OnceCell::new();
+
+/// decides whether to use dark mode. must be called before creating any window
+pub fn init_theme(theme: Theme) {
+    let dark = match theme {
+        Theme::Auto => !apps_use_light_theme(),
+        Theme::Light => false,
+        Theme::Dark => true,
+    };
+    if !dark {
+        return;
+    }
+    // the brushes are used until the process exits so never deleted
+    let brush = |color| HBRUSH::CreateSolidBrush(color).map(|mut x| x.leak());
+    match (brush(BACKGROUND_COLOR), brush(CONTROL_COLOR)) {
+        (Ok(background), Ok(control)) => {
+            let _ = DARK_BRUSHES.set(DarkBrushes {
+                background: background.ptr() as usize,
+                control: control.ptr() as usize,
+            });
+        }
+        (Err(e), _) | (_, Err(e)) => eprintln!("error creating brushes for dark mode: {}", e),
+    }
+}
+
+/// reads the app theme chosen in the settings of Windows.
+/// true if not found like before Windows 10 1809
+fn apps_use_light_theme() -> bool {
+    let mut value = 1u32;
+    let mut size = std::mem::size_of::<u32>() as u32;
+    let result = unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            &HSTRING::from(PERSONALIZE_KEY),
+            &HSTRING::from("AppsUseLightTheme"),
+            RRF_RT_REG_DWORD,
+            None,
+            Some((&mut value as *mut u32).cast()),
+            Some(&mut size),
+        )
+    };
+    result != ERROR_SUCCESS || value != 0
+}
+
+fn dark_brushes() -> Option<&'static DarkBrushes> {
+    DARK_BRUSHES.get()
+}
+
+/// the brush to paint the background of windows
+pub(crate) fn background_brush() -> gui::Brush {
+    match dark_brushes() {
+        Some(brushes) => gui::Brush::Handle(brushes.background()),
+        None => gui::Brush::Color(co::COLOR::BTNFACE),
+    }
+}
+
+/// paints the text of the controls in the window with dark colors if dark mode.
+/// must be called before the window is created
+pub(crate) fn set_control_colors(window: &impl GuiParent) {
+    fn colors(hdc: &HDC, brush: HBRUSH, background: COLORREF) -> AnyResult<HBRUSH> {
+        hdc.SetTextColor(TEXT_COLOR)?;
+        hdc.SetBkColor(background)?;
+        Ok(brush)
+    }
+
+    let brushes = match dark_brushes() {
+        Some(brushes) => brushes,
+        None => return,
+    };
+    let on = window.on();
+    on.wm_ctl_color_static(move |p| colors(&p.hdc, brushes.background(), BACKGROUND_COLOR));
+    on.wm_ctl_color_btn(move |p| colors(&p.hdc, brushes.background(), BACKGROUND_COLOR));
+    on.wm_ctl_color_edit(move |p| colors(&p.hdc, brushes.control(), CONTROL_COLOR));
+    on.wm_ctl_color_list_box(move |p| colors(&p.hdc, brushes.control(), CONTROL_COLOR));
+}
+
+/// makes the title bar and the controls of the window dark if dark mode.
+/// must be called after the controls are created, like in WM_CREATE
+pub(crate) fn apply_dark_styles(hwnd: &HWND) {
+    if dark_brushes().is_none() {
+        return;
+    }
+    let raw = |hwnd: &HWND| RawHwnd(hwnd.ptr() as isize);
+    unsafe {
+        let dark = BOOL::from(true);
+        // fails before Windows 10 20H1, where the title bar is kept light
+        let _ = DwmSetWindowAttribute(
+            raw(hwnd),
+            DWMWA_USE_IMMERSIVE_DARK_MODE,
+            (&dark as *const BOOL).cast(),
+            std::mem::size_of::<BOOL>() as u32,
+        );
+    }
+    hwnd.EnumChildWindows(|child| {
+        let style = child.GetWindowLongPtr(co::GWLP::STYLE) as u32;
+        let result = unsafe {
+            match child.GetClassName().unwrap_or_default().as_str() {
+                // check boxes ignore the text color with visual styles so classic style is used
+                "Button" if is_check_box(style) => SetWindowTheme(raw(&child), w!(""), w!("")),
+                "Button" | "Edit" => SetWindowTheme(raw(&child), w!("DarkMode_Explorer"), None),
+                "ComboBox" => SetWindowTheme(raw(&child), w!("DarkMode_CFD"), None),
+                _ => Ok(()),
+            }
+        };
+        if let Err(e) = result {
+            eprintln!("error applying dark style: {}", e);
+        }
+        true
+    });
+}
+
+fn is_check_box(style: u32) -> bool {
+    [
+        co::BS::CHECKBOX,
+        co::BS::AUTOCHECKBOX,
+        co::BS::R3STATE,
+        co::BS::AUTO3STATE,
+    ]
+    .iter()
+    .any(|x| x.raw() == style & co::BS::TYPEMASK.raw())
+}
@@ -0,0 +1,112 @@
+// VRC Log Renamer - the tool to rename logs of VRChat to have date info
+// Copyright (C) 2022 anatawa12
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The tooltips of the controls. winsafe doesn't have the tooltip control so it's created with
+//! the Win32 API.
+
+use std::cell::RefCell;
+use std::iter::once;
+use windows::core::{Error, HSTRING, PCWSTR, PWSTR};
+use windows::Win32::Foundation::{HINSTANCE, HWND as RawHwnd, LPARAM, WPARAM};
+use windows::Win32::UI::Controls::{
+    TOOLTIPS_CLASSW, TTDT_AUTOPOP, TTF_IDISHWND, TTF_SUBCLASS, TTM_ADDTOOLW, TTM_SETDELAYTIME,
+    TTM_SETMAXTIPWIDTH, TTS_ALWAYSTIP, TTS_NOPREFIX, TTTOOLINFOW,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, SendMessageW, CW_USEDEFAULT, HMENU, WINDOW_STYLE, WS_EX_TOPMOST, WS_POPUP,
+};
+use winsafe::prelude::*;
+use winsafe::HWND;
+
+/// the max width of tooltips in pixels. longer lines are wrapped
+const MAX_TIP_WIDTH: isize = 400;
+/// how long tooltips are shown in milliseconds. the default is too short to read the examples
+const AUTOPOP_DELAY: isize = 30_000;
+
+/// shows the text when the mouse is on the control or its children like the edit of a combo box.
+/// must be called after the controls are created, like in WM_CREATE
+pub(crate) fn add_tooltips(
+    parent: &HWND,
+    tools: &[(Box<dyn GuiWindow>, &str)],
+) -> Result<(), Error> {
+    let raw = |hwnd: &HWND| RawHwnd(hwnd.ptr() as isize);
+    let tooltip = unsafe {
+        CreateWindowExW(
+            WS_EX_TOPMOST,
+            &HSTRING::from(TOOLTIPS_CLASSW),
+            PCWSTR::null(),
+            WS_POPUP | WINDOW_STYLE(TTS_ALWAYSTIP | TTS_NOPREFIX),
+            CW_USEDEFAULT,
+            CW_USEDEFAULT,
+            CW_USEDEFAULT,
+            CW_USEDEFAULT,
+            raw(parent),
+            HMENU::default(),
+            HINSTANCE::default(),
+            None,
+        )
+    };
+    if tooltip.0 == 0 {
+        return Err(Error::from_win32());
+    }
+    unsafe {
+        // the text is shown in one line without max width even if it has '\n'
+        SendMessageW(
+            tooltip,
+            TTM_SETMAXTIPWIDTH,
+            WPARAM(0),
+            LPARAM(MAX_TIP_WIDTH),
+        );
+        SendMessageW(
+            tooltip,
+            TTM_SETDELAYTIME,
+            WPARAM(TTDT_AUTOPOP as usize),
+            LPARAM(AUTOPOP_DELAY),
+        );
+    }
+
+    for (control, text) in tools {
+        let targets = RefCell::new(vec![unsafe { control.hwnd().raw_copy() }]);
+        control.hwnd().EnumChildWindows(|child| {
+            targets.borrow_mut().push(child);
+            true
+        });
+        // the tooltip copies the text so it's enough to live while adding
+        let mut text = text.encode_utf16().chain(once(0)).collect::<Vec<u16>>();
+        for target in targets.into_inner() {
+            let info = TTTOOLINFOW {
+                cbSize: std::mem::size_of::<TTTOOLINFOW>() as u32,
+                uFlags: TTF_IDISHWND | TTF_SUBCLASS,
+                hwnd: raw(parent),
+                uId: target.ptr() as usize,
+                lpszText: PWSTR(text.as_mut_ptr()),
+                ..Default::default()
+            };
+            let added = unsafe {
+                SendMessageW(
+                    tooltip,
+                    TTM_ADDTOOLW,
+                    WPARAM(0),
+                    LPARAM(&info as *const TTTOOLINFOW as isize),
+                )
+            };
+            if added.0 == 0 {
+                eprintln!("error adding tooltip to {:?}", target);
+            }
+        }
+    }
+    Ok(())
+}
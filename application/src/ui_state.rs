@@ -0,0 +1,68 @@
+// VRC Log Renamer - the tool to rename logs of VRChat to have date info
+// Copyright (C) 2022 anatawa12
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The UI state is the state of GUI kept between runs like the window position, saved next to
+//! the config file. Unlike the config, this is saved without asking the user.
+
+use crate::config_file_path;
+use serde::{Deserialize, Serialize};
+use std::io::{Error, ErrorKind};
+use std::path::PathBuf;
+use std::{fs, io};
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct UiState {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    window: Option<WindowPosition>,
+}
+
+/// the position of the top-left corner of the main window in workspace coordinates
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct WindowPosition {
+    pub x: i32,
+    pub y: i32,
+}
+
+fn ui_state_file_path() -> PathBuf {
+    config_file_path().with_file_name("ui-state.toml")
+}
+
+impl UiState {
+    pub fn load() -> io::Result<Self> {
+        match fs::read_to_string(ui_state_file_path()) {
+            Ok(toml) => toml::from_str(&toml).map_err(|e| Error::new(ErrorKind::InvalidData, e)),
+            Err(ref e) if e.kind() == ErrorKind::NotFound => Ok(Default::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = ui_state_file_path();
+        fs::create_dir_all(path.parent().unwrap())?;
+        fs::write(
+            path,
+            toml::to_string(self).map_err(|e| Error::new(ErrorKind::InvalidData, e))?,
+        )
+    }
+
+    pub fn window(&self) -> Option<WindowPosition> {
+        self.window
+    }
+
+    pub fn set_window(&mut self, position: WindowPosition) {
+        self.window = Some(position);
+    }
+}
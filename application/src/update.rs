@@ -0,0 +1,135 @@
+// VRC Log Renamer - the tool to rename logs of VRChat to have date info
+// Copyright (C) 2022 anatawa12
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Checks the latest release on GitHub and downloads the new executable.
+
+use crate::{http_agent, transfer_agent};
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use std::{fs, io};
+use winsafe::co::{KF, KNOWNFOLDERID};
+use winsafe::SHGetKnownFolderPath;
+
+const LATEST_RELEASE_URL: &str =
+    "https://api.github.com/repos/anatawa12/vrc-log-renamer/releases/latest";
+
+/// checking updates should not keep users waiting when offline
+const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct Release {
+    tag_name: String,
+    html_url: String,
+    #[serde(default)]
+    assets: Vec<Asset>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Asset {
+    name: String,
+    browser_download_url: String,
+    /// the size in bytes to check the download is complete
+    #[serde(default)]
+    size: Option<u64>,
+}
+
+impl Release {
+    pub(crate) fn version(&self) -> &str {
+        self.tag_name.trim_start_matches('v')
+    }
+
+    pub(crate) fn html_url(&self) -> &str {
+        &self.html_url
+    }
+
+    fn exe_asset(&self) -> Option<&Asset> {
+        self.assets.iter().find(|x| x.name.ends_with(".exe"))
+    }
+}
+
+/// returns the latest release if it's newer than running one
+pub(crate) fn check_update() -> Result<Option<Release>> {
+    let release = http_agent()
+        .get(LATEST_RELEASE_URL)
+        .set("Accept", "application/vnd.github+json")
+        .set(
+            "User-Agent",
+            concat!("vrc-log-renamer/", env!("CARGO_PKG_VERSION")),
+        )
+        .timeout(CHECK_TIMEOUT)
+        .call()
+        .context("fetching latest release")?
+        .into_json::<Release>()
+        .context("parsing latest release")?;
+    let latest = parse_version(release.version());
+    let current = parse_version(env!("CARGO_PKG_VERSION"));
+    if latest.is_some() && latest > current {
+        Ok(Some(release))
+    } else {
+        Ok(None)
+    }
+}
+
+/// parses `major.minor.patch` ignoring pre-release and build metadata
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let version = version.split(&['-', '+'][..]).next()?;
+    let mut numbers = version.split('.').map(|x| x.parse::<u64>().ok());
+    Some((
+        numbers.next()??,
+        numbers.next()??,
+        numbers.next().unwrap_or(Some(0))?,
+    ))
+}
+
+/// downloads the executable of the release to the downloads folder and returns the path.
+/// the error message has the release page if the release has no executable
+pub(crate) fn download(release: &Release) -> Result<PathBuf> {
+    let asset = release
+        .exe_asset()
+        .with_context(|| format!("no executable found. see {}", release.html_url()))?;
+    let folder = SHGetKnownFolderPath(&KNOWNFOLDERID::Downloads, KF::DEFAULT, None)
+        .map(PathBuf::from)
+        .context("getting downloads folder")?;
+    let path = folder.join(&asset.name);
+    // the exe is not left truncated if the download is interrupted
+    let part = folder.join(format!("{}.part", asset.name));
+    match download_to(asset, &part) {
+        Ok(()) => {
+            fs::rename(&part, &path).with_context(|| format!("writing {}", path.display()))?;
+            Ok(path)
+        }
+        Err(e) => {
+            let _ = fs::remove_file(&part);
+            Err(e)
+        }
+    }
+}
+
+fn download_to(asset: &Asset, path: &Path) -> Result<()> {
+    let response = transfer_agent()
+        .get(&asset.browser_download_url)
+        .call()
+        .context("downloading update")?;
+    let mut file = fs::File::create(path)?;
+    let written = io::copy(&mut response.into_reader(), &mut file)
+        .with_context(|| format!("writing {}", path.display()))?;
+    if let Some(size) = asset.size.filter(|&size| size != written) {
+        bail!("download is incomplete: {} of {} bytes", written, size);
+    }
+    Ok(())
+}
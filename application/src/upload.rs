@@ -0,0 +1,293 @@
+// VRC Log Renamer - the tool to rename logs of VRChat to have date info
+// Copyright (C) 2022 anatawa12
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::config::{Upload, UploadKind};
+use crate::ledger::Ledger;
+use crate::transfer_agent;
+use crate::{FileError, RenameSummary};
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// the longest wait between retries
+const MAX_RETRY_WAIT: Duration = Duration::from_secs(60);
+
+/// uploads archived files which are not uploaded yet, including ones failed in previous runs.
+/// the files are uploaded with the path relative to the output folder in `folders`.
+/// stops at the first file failed even with retries since the server is likely down
+pub(crate) fn upload_archived(
+    config: &Upload,
+    folders: &[PathBuf],
+    ledger: &mut Ledger,
+    summary: &mut RenameSummary,
+) {
+    let credentials = match Credentials::find(config) {
+        Ok(credentials) => credentials,
+        Err(e) => {
+            warn!("error reading upload credentials: {}", e);
+            summary.errors.push(FileError {
+                path: config.url().into(),
+                error: format!("reading credentials: {}", e),
+            });
+            return;
+        }
+    };
+    let not_uploaded = ledger.not_uploaded();
+    for (i, path) in not_uploaded.iter().enumerate() {
+        info!("uploading {}", path.display());
+        match upload_with_retry(config, credentials.as_ref(), folders, path) {
+            Ok(()) => ledger.mark_uploaded(path),
+            Err(e) => {
+                warn!("error uploading '{}': {}", path.display(), e);
+                summary.errors.push(FileError {
+                    path: path.clone(),
+                    error: format!("uploading: {:#}", e),
+                });
+                let rest = not_uploaded.len() - i - 1;
+                if rest != 0 {
+                    info!("{} files are left to upload in the next run", rest);
+                }
+                break;
+            }
+        }
+    }
+}
+
+fn upload_with_retry(
+    config: &Upload,
+    credentials: Option<&Credentials>,
+    folders: &[PathBuf],
+    path: &Path,
+) -> Result<()> {
+    let key = remote_key(folders, path)?;
+    let mut attempt = 0;
+    loop {
+        let result = match config.kind() {
+            UploadKind::WebDav => upload_webdav(config, credentials, path, &key),
+            UploadKind::S3 => upload_s3(config, credentials, path, &key),
+        };
+        match result {
+            Ok(()) => return Ok(()),
+            Err(e) if attempt < config.retry() => {
+                let wait = Duration::from_secs(1 << attempt.min(6)).min(MAX_RETRY_WAIT);
+                warn!("upload failed: {:#}. retrying in {:?}", e, wait);
+                std::thread::sleep(wait);
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// the uri-encoded path relative to the output folder separated by `/`,
+/// or the file name if not in any output folder
+fn remote_key(folders: &[PathBuf], path: &Path) -> Result<String> {
+    let relative = folders
+        .iter()
+        .find_map(|folder| path.strip_prefix(folder).ok())
+        .unwrap_or_else(|| path.file_name().map_or(path, Path::new));
+    let segments = relative
+        .iter()
+        .map(|x| uri_encode(&x.to_string_lossy()))
+        .collect::<Vec<_>>();
+    if segments.is_empty() {
+        bail!("no file name");
+    }
+    Ok(segments.join("/"))
+}
+
+fn upload_webdav(
+    config: &Upload,
+    credentials: Option<&Credentials>,
+    path: &Path,
+    key: &str,
+) -> Result<()> {
+    let base = config.url().trim_end_matches('/');
+    let authorize = |request: ureq::Request| match credentials {
+        Some(credentials) => {
+            let basic =
+                base64::encode(format!("{}:{}", credentials.username, credentials.password));
+            request.set("Authorization", &format!("Basic {}", basic))
+        }
+        None => request,
+    };
+    // PUT fails if the parent collection doesn't exist
+    let folders = key.rsplit_once('/').map_or("", |x| x.0);
+    let mut parent = String::new();
+    for segment in folders.split('/').filter(|x| !x.is_empty()) {
+        parent.push_str(segment);
+        parent.push('/');
+        let url = format!("{}/{}", base, parent);
+        match authorize(transfer_agent().request("MKCOL", &url)).call() {
+            // 405 Method Not Allowed if the collection exists
+            Ok(_) | Err(ureq::Error::Status(405, _)) => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    let file = fs::File::open(path)?;
+    let length = file.metadata()?.len();
+    let request = transfer_agent()
+        .put(&format!("{}/{}", base, key))
+        .set("Content-Length", &length.to_string());
+    authorize(request).send(file)?;
+    Ok(())
+}
+
+/// uploads with path-style PUT Object signed with AWS Signature Version 4.
+/// the payload is not signed to stream large logs.
+fn upload_s3(
+    config: &Upload,
+    credentials: Option<&Credentials>,
+    path: &Path,
+    key: &str,
+) -> Result<()> {
+    let credentials = credentials.ok_or_else(|| anyhow!("credentials are required for s3"))?;
+    let endpoint = config.url().trim_end_matches('/');
+    let host = endpoint
+        .split_once("://")
+        .map(|x| x.1)
+        .unwrap_or(endpoint)
+        .split('/')
+        .next()
+        .unwrap();
+    let region = config.region().unwrap_or("us-east-1");
+    let uri = format!("/{}/{}", uri_encode(config.bucket().unwrap()), key);
+
+    let now = Utc::now();
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+    let payload_hash = "UNSIGNED-PAYLOAD";
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+
+    let canonical_request = format!(
+        "PUT\n{uri}\n\nhost:{host}\nx-amz-content-sha256:{payload_hash}\nx-amz-date:{amz_date}\n\n{signed_headers}\n{payload_hash}"
+    );
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+    let mut key = hmac_sha256(format!("AWS4{}", credentials.password).as_bytes(), &date);
+    for part in [region, "s3", "aws4_request"] {
+        key = hmac_sha256(&key, part);
+    }
+    let signature = hex(&hmac_sha256(&key, &string_to_sign));
+
+    let file = fs::File::open(path)?;
+    let length = file.metadata()?.len();
+    transfer_agent()
+        .put(&format!("{}{}", endpoint, uri))
+        .set("Content-Length", &length.to_string())
+        .set("x-amz-content-sha256", payload_hash)
+        .set("x-amz-date", &amz_date)
+        .set(
+            "Authorization",
+            &format!(
+                "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+                credentials.username, scope, signed_headers, signature
+            ),
+        )
+        .send(file)?;
+    Ok(())
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("hmac accepts any key length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// percent-encodes all characters except unreserved characters of RFC 3986
+fn uri_encode(str: &str) -> String {
+    let mut encoded = String::new();
+    for b in str.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(b as char)
+            }
+            b => encoded.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    encoded
+}
+
+struct Credentials {
+    username: String,
+    password: String,
+}
+
+impl Credentials {
+    fn find(config: &Upload) -> Result<Option<Self>> {
+        if let (Some(username), Some(password)) = (config.username(), config.password()) {
+            return Ok(Some(Self {
+                username: username.to_owned(),
+                password: password.to_owned(),
+            }));
+        }
+        match config.credential() {
+            Some(target) => read_credential(target).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// reads generic credential from Windows Credential Manager
+fn read_credential(target: &str) -> Result<Credentials> {
+    use windows::core::HSTRING;
+    use windows::Win32::Security::Credentials::{
+        CredFree, CredReadW, CREDENTIALW, CRED_TYPE_GENERIC,
+    };
+
+    unsafe {
+        let mut credential: *mut CREDENTIALW = std::ptr::null_mut();
+        if !CredReadW(&HSTRING::from(target), CRED_TYPE_GENERIC.0, 0, &mut credential).as_bool() {
+            return Err(std::io::Error::last_os_error())
+                .with_context(|| format!("reading credential '{}'", target));
+        }
+        let cred = &*credential;
+        let username = if cred.UserName.is_null() {
+            String::new()
+        } else {
+            cred.UserName.to_string()?
+        };
+        let blob = std::slice::from_raw_parts(cred.CredentialBlob, cred.CredentialBlobSize as usize);
+        // credentials saved with control panel are UTF-16
+        let password = if blob.len() % 2 == 0 {
+            let wide = blob
+                .chunks_exact(2)
+                .map(|x| u16::from_le_bytes([x[0], x[1]]))
+                .collect::<Vec<_>>();
+            String::from_utf16_lossy(&wide)
+        } else {
+            String::from_utf8_lossy(blob).into_owned()
+        };
+        CredFree(credential as *const _);
+        if username.is_empty() {
+            bail!("credential '{}' does not have user name", target);
+        }
+        Ok(Credentials { username, password })
+    }
+}
@@ -0,0 +1,461 @@
+// VRC Log Renamer - the tool to rename logs of VRChat to have date info
+// Copyright (C) 2022 anatawa12
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The file system and the clock the rename engine runs on. The renamer uses the real ones, and
+//! the `simulate` command and tests run the same engine on the in-memory tree with a fixed "now".
+
+use crate::config::Output;
+use crate::{
+    checksums, content_hash, copy_file, copy_file_times, copy_filtered, filter_lines, leave_link,
+    move_file, set_attributes, shadow,
+};
+use regex::Regex;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, ErrorKind, Read};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+pub(crate) trait Clock {
+    fn now(&self) -> SystemTime;
+}
+
+pub(crate) struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// the clock always returning the same time
+pub(crate) struct FixedClock(pub SystemTime);
+
+impl Clock for FixedClock {
+    fn now(&self) -> SystemTime {
+        self.0
+    }
+}
+
+/// the metadata of the path itself. the symbolic link is not followed
+#[derive(Debug, Clone)]
+pub(crate) struct Metadata {
+    pub len: u64,
+    pub is_dir: bool,
+    pub is_symlink: bool,
+    pub created: SystemTime,
+    pub modified: SystemTime,
+}
+
+/// the file operations of the rename engine
+pub(crate) trait FileSystem {
+    /// the paths of the entries in the folder
+    fn read_dir(&self, folder: &Path) -> io::Result<Vec<PathBuf>>;
+    fn metadata(&self, path: &Path) -> io::Result<Metadata>;
+    fn exists(&self, path: &Path) -> bool {
+        self.metadata(path).is_ok()
+    }
+    fn open(&self, path: &Path) -> io::Result<Box<dyn Read + '_>>;
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        let mut content = Vec::new();
+        self.open(path)?.read_to_end(&mut content)?;
+        Ok(content)
+    }
+    fn sha256(&self, path: &Path) -> io::Result<String> {
+        checksums::read_sha256(self.open(path)?)
+    }
+    /// opens the file to check if it's locked. VRChat doesn't allow others to write its log
+    fn check_open(&self, path: &Path, write: bool) -> io::Result<()>;
+    fn create_dir_all(&self, path: &Path) -> io::Result<()>;
+    fn remove_file(&self, path: &Path) -> io::Result<()>;
+    fn hard_link(&self, original: &Path, link: &Path) -> io::Result<()>;
+    /// copies the file with ctime and mtime. the lines matching any of the filters are dropped
+    fn copy(
+        &self,
+        from: &Path,
+        to: &Path,
+        filters: &[Regex],
+        progress: &mut dyn FnMut(u64, u64) -> bool,
+    ) -> io::Result<()>;
+    /// copies the file without filters reading it once, and returns the sha256 of the content
+    fn copy_hashed(
+        &self,
+        from: &Path,
+        to: &Path,
+        buffer_size: usize,
+        progress: &mut dyn FnMut(u64, u64) -> bool,
+    ) -> io::Result<String>;
+    /// moves the file keeping the times, by copying if across volumes
+    fn rename(
+        &self,
+        from: &Path,
+        to: &Path,
+        progress: &mut dyn FnMut(u64, u64) -> bool,
+    ) -> io::Result<()>;
+    /// compresses and marks read-only the archived log as the output configures
+    fn set_attributes(&self, output: &Output, path: &Path, read_only: bool);
+    /// leaves the link to the moved log at the original path
+    fn leave_link(&self, original: &Path, moved: &Path) -> io::Result<()>;
+    /// copies the log in use to the path. the failure is not an error
+    fn capture_live(&self, path: &Path, dst_path: &Path);
+}
+
+/// the file system and the clock the engine runs on
+#[derive(Copy, Clone)]
+pub(crate) struct Env<'a> {
+    pub fs: &'a dyn FileSystem,
+    pub clock: &'a dyn Clock,
+}
+
+impl Env<'static> {
+    pub fn real() -> Self {
+        Env {
+            fs: &RealFs,
+            clock: &SystemClock,
+        }
+    }
+}
+
+pub(crate) struct RealFs;
+
+impl FileSystem for RealFs {
+    fn read_dir(&self, folder: &Path) -> io::Result<Vec<PathBuf>> {
+        fs::read_dir(folder)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect()
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+        let metadata = fs::symlink_metadata(path)?;
+        Ok(Metadata {
+            len: metadata.len(),
+            is_dir: metadata.is_dir(),
+            is_symlink: metadata.file_type().is_symlink(),
+            created: metadata.created()?,
+            modified: metadata.modified()?,
+        })
+    }
+
+    fn open(&self, path: &Path) -> io::Result<Box<dyn Read + '_>> {
+        Ok(Box::new(fs::File::open(path)?))
+    }
+
+    fn check_open(&self, path: &Path, write: bool) -> io::Result<()> {
+        fs::File::options()
+            .write(write)
+            .read(true)
+            .open(path)
+            .map(drop)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        fs::create_dir_all(path)
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        fs::remove_file(path)
+    }
+
+    fn hard_link(&self, original: &Path, link: &Path) -> io::Result<()> {
+        fs::hard_link(original, link)
+    }
+
+    fn copy(
+        &self,
+        from: &Path,
+        to: &Path,
+        filters: &[Regex],
+        progress: &mut dyn FnMut(u64, u64) -> bool,
+    ) -> io::Result<()> {
+        if !filters.is_empty() {
+            return copy_filtered(from, to, filters);
+        }
+        copy_file(from, to, progress)?;
+        let metadata = fs::File::open(from)?.metadata()?;
+        let dst_file = fs::File::options().write(true).open(to)?;
+        copy_file_times(&metadata, &dst_file)
+    }
+
+    fn copy_hashed(
+        &self,
+        from: &Path,
+        to: &Path,
+        buffer_size: usize,
+        progress: &mut dyn FnMut(u64, u64) -> bool,
+    ) -> io::Result<String> {
+        checksums::copy_hashed(from, to, buffer_size, progress)
+    }
+
+    fn rename(
+        &self,
+        from: &Path,
+        to: &Path,
+        progress: &mut dyn FnMut(u64, u64) -> bool,
+    ) -> io::Result<()> {
+        move_file(from, to, progress)
+    }
+
+    fn set_attributes(&self, output: &Output, path: &Path, read_only: bool) {
+        set_attributes(output, path, read_only)
+    }
+
+    fn leave_link(&self, original: &Path, moved: &Path) -> io::Result<()> {
+        leave_link(original, moved)
+    }
+
+    fn capture_live(&self, path: &Path, dst_path: &Path) {
+        let result = fs::create_dir_all(dst_path.parent().unwrap())
+            .and_then(|()| shadow::copy_from_snapshot(path, dst_path));
+        match result {
+            Ok(()) => info!("captured partial log to {}", dst_path.display()),
+            Err(e) => warn!("error capturing {}: {}", path.display(), e),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Entry {
+    Dir,
+    File(File),
+    /// the link left at the original path of the moved log
+    Link,
+}
+
+#[derive(Debug, Clone)]
+struct File {
+    content: Content,
+    created: SystemTime,
+    modified: SystemTime,
+}
+
+#[derive(Debug, Clone)]
+enum Content {
+    Memory(Vec<u8>),
+    /// the file on the disk not read yet with its length
+    Disk(PathBuf, u64),
+}
+
+/// the tree on the memory. the files can be taken from the disk, which are read only when needed,
+/// but nothing is written to the disk. the paths are case-sensitive unlike windows
+#[derive(Default)]
+pub(crate) struct MemoryFs {
+    entries: RefCell<BTreeMap<PathBuf, Entry>>,
+}
+
+impl MemoryFs {
+    /// adds the file and its parent folders
+    #[cfg(test)]
+    pub fn add_file(&self, path: &Path, content: &[u8], modified: SystemTime) {
+        self.add_parents(path);
+        let file = File {
+            content: Content::Memory(content.to_vec()),
+            created: modified,
+            modified,
+        };
+        self.entries
+            .borrow_mut()
+            .insert(path.to_owned(), Entry::File(file));
+    }
+
+    /// adds the files in the folder on the disk recursively. the folder not found is ignored
+    pub fn add_from_disk(&self, folder: &Path) -> io::Result<()> {
+        let entries = match fs::read_dir(folder) {
+            Ok(entries) => entries,
+            Err(ref e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        self.add_parents(&folder.join("_"));
+        for entry in entries {
+            let path = entry?.path();
+            let metadata = RealFs.metadata(&path)?;
+            if metadata.is_dir {
+                self.add_from_disk(&path)?;
+                continue;
+            }
+            let entry = if metadata.is_symlink {
+                Entry::Link
+            } else {
+                Entry::File(File {
+                    content: Content::Disk(path.clone(), metadata.len),
+                    created: metadata.created,
+                    modified: metadata.modified,
+                })
+            };
+            self.entries.borrow_mut().insert(path, entry);
+        }
+        Ok(())
+    }
+
+    /// the paths of all files and links
+    #[cfg(test)]
+    pub fn files(&self) -> Vec<PathBuf> {
+        let entries = self.entries.borrow();
+        let files = entries.iter().filter(|(_, x)| !matches!(x, Entry::Dir));
+        files.map(|(path, _)| path.clone()).collect()
+    }
+
+    fn add_parents(&self, path: &Path) {
+        let mut entries = self.entries.borrow_mut();
+        for parent in path.ancestors().skip(1) {
+            if parent.as_os_str().is_empty() {
+                break;
+            }
+            entries.entry(parent.to_owned()).or_insert(Entry::Dir);
+        }
+    }
+
+    fn file(&self, path: &Path) -> io::Result<File> {
+        match self.entries.borrow().get(path) {
+            Some(Entry::File(file)) => Ok(file.clone()),
+            Some(_) => Err(io::Error::other("not a file")),
+            None => Err(not_found(path)),
+        }
+    }
+
+    /// adds the file at the path which must not exist
+    fn create(&self, path: &Path, file: File) -> io::Result<()> {
+        if !self.exists(path.parent().unwrap_or(path)) {
+            return Err(not_found(path.parent().unwrap_or(path)));
+        }
+        let mut entries = self.entries.borrow_mut();
+        if entries.contains_key(path) {
+            return Err(io::Error::new(ErrorKind::AlreadyExists, "file exists"));
+        }
+        entries.insert(path.to_owned(), Entry::File(file));
+        Ok(())
+    }
+}
+
+fn not_found(path: &Path) -> io::Error {
+    io::Error::new(ErrorKind::NotFound, format!("{} not found", path.display()))
+}
+
+impl FileSystem for MemoryFs {
+    fn read_dir(&self, folder: &Path) -> io::Result<Vec<PathBuf>> {
+        if !matches!(self.entries.borrow().get(folder), Some(Entry::Dir)) {
+            return Err(not_found(folder));
+        }
+        let entries = self.entries.borrow();
+        let children = entries.keys().filter(|path| path.parent() == Some(folder));
+        Ok(children.cloned().collect())
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+        let entry = self.entries.borrow().get(path).cloned();
+        let (len, created, modified) = match &entry {
+            Some(Entry::File(file)) => {
+                let len = match &file.content {
+                    Content::Memory(content) => content.len() as u64,
+                    Content::Disk(_, len) => *len,
+                };
+                (len, file.created, file.modified)
+            }
+            Some(_) => (0, SystemTime::UNIX_EPOCH, SystemTime::UNIX_EPOCH),
+            None => return Err(not_found(path)),
+        };
+        Ok(Metadata {
+            len,
+            is_dir: matches!(entry, Some(Entry::Dir)),
+            is_symlink: matches!(entry, Some(Entry::Link)),
+            created,
+            modified,
+        })
+    }
+
+    fn open(&self, path: &Path) -> io::Result<Box<dyn Read + '_>> {
+        match self.file(path)?.content {
+            Content::Memory(content) => Ok(Box::new(io::Cursor::new(content))),
+            Content::Disk(path, _) => Ok(Box::new(fs::File::open(path)?)),
+        }
+    }
+
+    fn check_open(&self, path: &Path, _write: bool) -> io::Result<()> {
+        self.file(path).map(drop)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        self.add_parents(&path.join("_"));
+        Ok(())
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        let mut entries = self.entries.borrow_mut();
+        match entries.get(path) {
+            Some(Entry::Dir) => Err(io::Error::other("not a file")),
+            Some(_) => {
+                entries.remove(path);
+                Ok(())
+            }
+            None => Err(not_found(path)),
+        }
+    }
+
+    fn hard_link(&self, original: &Path, link: &Path) -> io::Result<()> {
+        // the content is not shared but never modified
+        let file = self.file(original)?;
+        self.create(link, file)
+    }
+
+    fn copy(
+        &self,
+        from: &Path,
+        to: &Path,
+        filters: &[Regex],
+        _progress: &mut dyn FnMut(u64, u64) -> bool,
+    ) -> io::Result<()> {
+        let file = self.file(from)?;
+        let mut content = Vec::new();
+        filter_lines(&mut self.open(from)?, &mut content, filters)?;
+        let content = Content::Memory(content);
+        self.create(to, File { content, ..file })
+    }
+
+    fn copy_hashed(
+        &self,
+        from: &Path,
+        to: &Path,
+        _buffer_size: usize,
+        progress: &mut dyn FnMut(u64, u64) -> bool,
+    ) -> io::Result<String> {
+        self.copy(from, to, &[], progress)?;
+        Ok(content_hash(&self.read(to)?, "sha256").unwrap())
+    }
+
+    fn rename(
+        &self,
+        from: &Path,
+        to: &Path,
+        _progress: &mut dyn FnMut(u64, u64) -> bool,
+    ) -> io::Result<()> {
+        let file = self.file(from)?;
+        self.create(to, file)?;
+        self.remove_file(from)
+    }
+
+    fn set_attributes(&self, _output: &Output, _path: &Path, _read_only: bool) {}
+
+    fn leave_link(&self, original: &Path, _moved: &Path) -> io::Result<()> {
+        let mut entries = self.entries.borrow_mut();
+        if entries.contains_key(original) {
+            return Err(io::Error::new(ErrorKind::AlreadyExists, "file exists"));
+        }
+        entries.insert(original.to_owned(), Entry::Link);
+        Ok(())
+    }
+
+    fn capture_live(&self, _path: &Path, _dst_path: &Path) {}
+}